@@ -1,48 +1,346 @@
+use crate::transaction::Transaction;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
 pub struct Account {
     pub address: String
 }
 
+/// Ledger-level invariants `State::transfer`/`State::apply_transaction`
+/// reject rather than silently let balances go negative or a transaction
+/// replay against the chain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerError {
+    UnknownAccount(Account),
+    InvalidAmount(f64),
+    InsufficientFunds {
+        account: Account,
+        balance: f64,
+        amount: f64,
+    },
+    InvalidNonce {
+        account: Account,
+        expected: u64,
+        got: u64,
+    },
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::UnknownAccount(account) => {
+                write!(f, "unknown account {}", account.address)
+            }
+            LedgerError::InvalidAmount(amount) => write!(f, "invalid transfer amount {}", amount),
+            LedgerError::InsufficientFunds { account, balance, amount } => write!(
+                f,
+                "account {} has balance {} but tried to send {}",
+                account.address, balance, amount
+            ),
+            LedgerError::InvalidNonce { account, expected, got } => write!(
+                f,
+                "account {} expected nonce {} but transaction carries {}",
+                account.address, expected, got
+            ),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct State {
     pub accounts: Vec<Account>,
     pub balances: HashMap<Account, f64>,
+    /// The next nonce each account's sender-side transactions must carry,
+    /// so `apply_transaction` can reject a replayed or out-of-order
+    /// transaction instead of applying it twice.
+    pub nonce: HashMap<String, u64>,
 }
 
 impl State {
     pub fn new() -> Self {
-        State { accounts: Vec::new(), balances: HashMap::new() }
+        State { accounts: Vec::new(), balances: HashMap::new(), nonce: HashMap::new() }
     }
 
     pub fn add_account(&mut self, account: Account) {
         if !self.balances.contains_key(&account) {
             self.balances.insert(account.clone(), 0.00);
+            self.nonce.entry(account.address.clone()).or_insert(0);
             self.accounts.push(account);
         }
     }
 
     pub fn remove_account(&mut self, account: Account) {
         self.balances.remove(&account);
+        self.nonce.remove(&account.address);
         self.accounts.retain(|a| a != &account);
     }
 
-    pub fn transfer(&mut self, from: Account, to: Account, amount: f64) {
+    /// Moves `amount` from `from` to `to`, atomically: rejects (without
+    /// touching either balance) a non-finite or negative amount, an unknown
+    /// sender/recipient, or a sender balance below `amount`, so a successful
+    /// call always conserves total value and never drives a balance
+    /// negative.
+    pub fn transfer(&mut self, from: Account, to: Account, amount: f64) -> Result<(), LedgerError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(LedgerError::InvalidAmount(amount));
+        }
+        if !self.balances.contains_key(&from) {
+            return Err(LedgerError::UnknownAccount(from));
+        }
+        if !self.balances.contains_key(&to) {
+            return Err(LedgerError::UnknownAccount(to));
+        }
+        let balance = self.get_balance(from.clone());
+        if balance < amount {
+            return Err(LedgerError::InsufficientFunds { account: from, balance, amount });
+        }
         self.balances.entry(from).and_modify(|v| *v -= amount);
         self.balances.entry(to).and_modify(|v| *v += amount);
+        Ok(())
     }
 
-    pub fn stake(&mut self, account: Account, amount: f64) {
+    /// Adds `amount` to `account`'s stake, rejecting (without touching the
+    /// balance) a non-finite or negative amount or an unknown account, the
+    /// same guards `transfer` applies.
+    pub fn stake(&mut self, account: Account, amount: f64) -> Result<(), LedgerError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(LedgerError::InvalidAmount(amount));
+        }
+        if !self.balances.contains_key(&account) {
+            return Err(LedgerError::UnknownAccount(account));
+        }
         self.balances.entry(account).and_modify(|v| *v += amount);
+        Ok(())
     }
 
-    pub fn unstake(&mut self, account: Account, amount: f64) {
+    /// Removes `amount` from `account`'s stake, rejecting (without touching
+    /// the balance) a non-finite or negative amount, an unknown account, or
+    /// an amount exceeding the account's current stake — an unstake can
+    /// never drive a balance negative.
+    pub fn unstake(&mut self, account: Account, amount: f64) -> Result<(), LedgerError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(LedgerError::InvalidAmount(amount));
+        }
+        if !self.balances.contains_key(&account) {
+            return Err(LedgerError::UnknownAccount(account));
+        }
+        let balance = self.get_balance(account.clone());
+        if balance < amount {
+            return Err(LedgerError::InsufficientFunds { account, balance, amount });
+        }
         self.balances.entry(account).and_modify(|v| *v -= amount);
+        Ok(())
     }
 
     pub fn get_balance(&self, account: Account) -> f64 {
         *self.balances.get(&account).unwrap_or(&0.00)
     }
+
+    /// The nonce `account`'s next transaction must carry.
+    pub fn next_nonce(&self, account: &Account) -> u64 {
+        self.nonce.get(&account.address).cloned().unwrap_or(0)
+    }
+
+    /// Validates `txn` against the ledger and applies it: rejects a sender
+    /// nonce that doesn't match `next_nonce`, then runs `transfer` for
+    /// `txn.sender` -> `txn.recipient`, and only advances the sender's
+    /// nonce once the transfer itself succeeds.
+    pub fn apply_transaction(&mut self, txn: &Transaction) -> Result<(), LedgerError> {
+        let expected = self.next_nonce(&txn.sender);
+        if txn.nonce != expected {
+            return Err(LedgerError::InvalidNonce {
+                account: txn.sender.clone(),
+                expected,
+                got: txn.nonce,
+            });
+        }
+        self.transfer(txn.sender.clone(), txn.recipient.clone(), txn.amount)?;
+        self.nonce.insert(txn.sender.address.clone(), expected + 1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+    use crate::wallet::Wallet;
+
+    fn funded_account(state: &mut State, balance: f64) -> Account {
+        let account = Account { address: format!("addr-{}", state.accounts.len()) };
+        state.add_account(account.clone());
+        state.balances.insert(account.clone(), balance);
+        account
+    }
+
+    #[test]
+    fn test_transfer_moves_funds_between_known_accounts() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 100.0);
+        let bob = funded_account(&mut state, 0.0);
+
+        assert!(state.transfer(alice.clone(), bob.clone(), 40.0).is_ok());
+        assert_eq!(state.get_balance(alice), 60.0);
+        assert_eq!(state.get_balance(bob), 40.0);
+    }
+
+    #[test]
+    fn test_transfer_rejects_insufficient_funds_without_mutating_balances() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+        let bob = funded_account(&mut state, 0.0);
+
+        let result = state.transfer(alice.clone(), bob.clone(), 40.0);
+        assert!(matches!(result, Err(LedgerError::InsufficientFunds { .. })));
+        assert_eq!(state.get_balance(alice), 10.0);
+        assert_eq!(state.get_balance(bob), 0.0);
+    }
+
+    #[test]
+    fn test_transfer_rejects_a_negative_amount() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+        let bob = funded_account(&mut state, 0.0);
+
+        assert!(matches!(
+            state.transfer(alice, bob, -5.0),
+            Err(LedgerError::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn test_transfer_rejects_a_nan_amount() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+        let bob = funded_account(&mut state, 0.0);
+
+        assert!(matches!(
+            state.transfer(alice, bob, f64::NAN),
+            Err(LedgerError::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn test_transfer_rejects_an_unknown_sender_or_recipient() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+        let stranger = Account { address: "nowhere".to_string() };
+
+        assert!(matches!(
+            state.transfer(stranger.clone(), alice.clone(), 1.0),
+            Err(LedgerError::UnknownAccount(_))
+        ));
+        assert!(matches!(
+            state.transfer(alice, stranger, 1.0),
+            Err(LedgerError::UnknownAccount(_))
+        ));
+    }
+
+    #[test]
+    fn test_stake_adds_to_an_existing_balance() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+
+        assert!(state.stake(alice.clone(), 5.0).is_ok());
+        assert_eq!(state.get_balance(alice), 15.0);
+    }
+
+    #[test]
+    fn test_stake_rejects_a_negative_amount() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+
+        assert!(matches!(
+            state.stake(alice.clone(), -5.0),
+            Err(LedgerError::InvalidAmount(_))
+        ));
+        assert_eq!(state.get_balance(alice), 10.0);
+    }
+
+    #[test]
+    fn test_stake_rejects_an_unknown_account() {
+        let mut state = State::new();
+        let stranger = Account { address: "nowhere".to_string() };
+
+        assert!(matches!(
+            state.stake(stranger, 5.0),
+            Err(LedgerError::UnknownAccount(_))
+        ));
+    }
+
+    #[test]
+    fn test_unstake_removes_from_an_existing_balance() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+
+        assert!(state.unstake(alice.clone(), 4.0).is_ok());
+        assert_eq!(state.get_balance(alice), 6.0);
+    }
+
+    #[test]
+    fn test_unstake_rejects_overdraft_without_mutating_balance() {
+        let mut state = State::new();
+        let alice = funded_account(&mut state, 10.0);
+
+        assert!(matches!(
+            state.unstake(alice.clone(), 40.0),
+            Err(LedgerError::InsufficientFunds { .. })
+        ));
+        assert_eq!(state.get_balance(alice), 10.0);
+    }
+
+    #[test]
+    fn test_apply_transaction_rejects_a_replayed_nonce() {
+        let mut state = State::new();
+        let mut wallet = Wallet::new().unwrap();
+        let sender = Account { address: wallet.get_public_key() };
+        let recipient = funded_account(&mut state, 0.0);
+        state.add_account(sender.clone());
+        state.balances.insert(sender.clone(), 100.0);
+
+        let txn = Transaction::new(&mut wallet, sender.clone(), recipient.clone(), 10.0, 0, 0, TransactionType::TRANSACTION, None).unwrap();
+        assert!(state.apply_transaction(&txn).is_ok());
+        assert_eq!(state.next_nonce(&sender), 1);
+
+        // Replaying the exact same (already-consumed) nonce must fail.
+        assert!(matches!(
+            state.apply_transaction(&txn),
+            Err(LedgerError::InvalidNonce { .. })
+        ));
+    }
+
+    #[test]
+    fn test_apply_transaction_rejects_an_out_of_order_nonce() {
+        let mut state = State::new();
+        let mut wallet = Wallet::new().unwrap();
+        let sender = Account { address: wallet.get_public_key() };
+        let recipient = funded_account(&mut state, 0.0);
+        state.add_account(sender.clone());
+        state.balances.insert(sender.clone(), 100.0);
+
+        let txn = Transaction::new(&mut wallet, sender.clone(), recipient, 10.0, 0, 5, TransactionType::TRANSACTION, None).unwrap();
+        assert!(matches!(
+            state.apply_transaction(&txn),
+            Err(LedgerError::InvalidNonce { expected: 0, got: 5 })
+        ));
+    }
+
+    #[test]
+    fn test_apply_transaction_advances_the_nonce_on_success() {
+        let mut state = State::new();
+        let mut wallet = Wallet::new().unwrap();
+        let sender = Account { address: wallet.get_public_key() };
+        let recipient = funded_account(&mut state, 0.0);
+        state.add_account(sender.clone());
+        state.balances.insert(sender.clone(), 100.0);
+
+        let first = Transaction::new(&mut wallet, sender.clone(), recipient.clone(), 10.0, 0, 0, TransactionType::TRANSACTION, None).unwrap();
+        let second = Transaction::new(&mut wallet, sender.clone(), recipient, 10.0, 0, 1, TransactionType::TRANSACTION, None).unwrap();
+
+        assert!(state.apply_transaction(&first).is_ok());
+        assert!(state.apply_transaction(&second).is_ok());
+        assert_eq!(state.next_nonce(&sender), 2);
+    }
 }
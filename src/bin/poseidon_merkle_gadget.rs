@@ -0,0 +1,166 @@
+// In-circuit opening of a `merkle::PoseidonMerkleTreeBuilder` inclusion
+// proof: given a leaf, its sibling path and left/right selector bits, fold
+// the path up through the same Poseidon permutation `poseidon::compress2`
+// uses natively, and assert the result equals a public root. Unlike
+// `bin/test_circuit.rs`'s SHA3 gadget, every step here is native field
+// add/mul — no bit decomposition, no 64-bit lane bookkeeping — which is
+// the entire point of using an arithmetization-friendly hash for trees
+// that need to be opened inside a proof.
+
+use expander_compiler::frontend::*;
+use internal::Serde;
+use niropok_pq_sidechain::poseidon;
+
+const TREE_DEPTH: usize = 4;
+
+/// `x^5`, mirroring `poseidon::sbox` but over in-circuit `Variable`s.
+fn sbox_gadget(builder: &mut API<M31Config>, x: Variable) -> Variable {
+    let x2 = builder.mul(x, x);
+    let x4 = builder.mul(x2, x2);
+    builder.mul(x4, x)
+}
+
+/// Mirrors `poseidon::mds_mix`: each output lane is a fixed linear
+/// combination of the input lanes, the coefficients coming straight from
+/// `poseidon::mds_matrix()`.
+fn mds_mix_gadget(
+    builder: &mut API<M31Config>,
+    state: &[Variable; poseidon::STATE_WIDTH],
+    mds: &[[u64; poseidon::STATE_WIDTH]; poseidon::STATE_WIDTH],
+) -> [Variable; poseidon::STATE_WIDTH] {
+    let mut out = Vec::with_capacity(poseidon::STATE_WIDTH);
+    for row in mds.iter() {
+        let mut acc: Option<Variable> = None;
+        for (coeff, lane) in row.iter().zip(state.iter()) {
+            let term = builder.mul(*lane, M31::from(*coeff as u32));
+            acc = Some(match acc {
+                Some(running) => builder.add(running, term),
+                None => term,
+            });
+        }
+        out.push(acc.unwrap());
+    }
+    out.try_into().unwrap_or_else(|_| unreachable!())
+}
+
+/// Mirrors `poseidon::permute`: the same full/partial/full round sandwich,
+/// constants and MDS mix, just operating on wires instead of `u64`s.
+fn permute_gadget(builder: &mut API<M31Config>, state: &mut [Variable; poseidon::STATE_WIDTH]) {
+    let constants = poseidon::round_constants();
+    let mds = poseidon::mds_matrix();
+    let half_full = poseidon::FULL_ROUNDS / 2;
+
+    for (round, round_constants) in constants.iter().enumerate().take(poseidon::TOTAL_ROUNDS) {
+        for (lane, c) in state.iter_mut().zip(round_constants.iter()) {
+            *lane = builder.add(*lane, M31::from(*c as u32));
+        }
+
+        let is_full_round = round < half_full || round >= half_full + poseidon::PARTIAL_ROUNDS;
+        if is_full_round {
+            for lane in state.iter_mut() {
+                *lane = sbox_gadget(builder, *lane);
+            }
+        } else {
+            state[0] = sbox_gadget(builder, state[0]);
+        }
+
+        *state = mds_mix_gadget(builder, state, mds);
+    }
+}
+
+/// Mirrors `poseidon::compress2`: absorbs `(a, b)` into `[zero, a, b]` and
+/// squeezes lane 0.
+fn compress2_gadget(
+    builder: &mut API<M31Config>,
+    a: Variable,
+    b: Variable,
+    zero: Variable,
+) -> Variable {
+    let mut state = [zero, a, b];
+    permute_gadget(builder, &mut state);
+    state[0]
+}
+
+/// `when_zero` if `bit == 0`, `when_one` if `bit == 1`; `bit` is asserted
+/// boolean by the caller before this is used.
+fn select(builder: &mut API<M31Config>, bit: Variable, when_zero: Variable, when_one: Variable) -> Variable {
+    let diff = builder.sub(when_one, when_zero);
+    let scaled = builder.mul(bit, diff);
+    builder.add(when_zero, scaled)
+}
+
+declare_circuit!(MerklePathCircuit {
+    leaf: Variable,
+    siblings: [Variable; TREE_DEPTH],
+    // 0 => `leaf`/the running hash is the left child at this level, 1 => the right child.
+    path_bits: [Variable; TREE_DEPTH],
+    root: Variable,
+});
+
+impl Define<M31Config> for MerklePathCircuit<Variable> {
+    fn define(&self, builder: &mut API<M31Config>) {
+        // Derived via `x * 0` rather than an unchecked "constant" builder
+        // call, same caution `bin/test_circuit.rs` takes deriving its zero
+        // wire via self-XOR.
+        let zero = builder.mul(self.leaf, M31::from(0u32));
+
+        let mut current = self.leaf;
+        for i in 0..TREE_DEPTH {
+            let bit = self.path_bits[i];
+            let bit_squared = builder.mul(bit, bit);
+            builder.assert_is_equal(bit_squared, bit);
+
+            let sibling = self.siblings[i];
+            let left = select(builder, bit, current, sibling);
+            let right = select(builder, bit, sibling, current);
+            current = compress2_gadget(builder, left, right, zero);
+        }
+
+        builder.assert_is_equal(current, self.root);
+    }
+}
+
+fn main() {
+    let compile_result = compile(&MerklePathCircuit::default()).unwrap();
+
+    let leaf_val: u64 = 5;
+    let siblings_val: [u64; TREE_DEPTH] = [11, 22, 33, 44];
+    // left at levels 0 and 2, right at levels 1 and 3.
+    let path_bits_val: [u64; TREE_DEPTH] = [0, 1, 0, 1];
+
+    let mut current = leaf_val;
+    for i in 0..TREE_DEPTH {
+        let (left, right) = if path_bits_val[i] == 0 {
+            (current, siblings_val[i])
+        } else {
+            (siblings_val[i], current)
+        };
+        current = poseidon::compress2(left, right);
+    }
+    let root_val = current;
+
+    let assignment = MerklePathCircuit::<M31> {
+        leaf: M31::from(leaf_val as u32),
+        siblings: siblings_val.map(|s| M31::from(s as u32)),
+        path_bits: path_bits_val.map(|b| M31::from(b as u32)),
+        root: M31::from(root_val as u32),
+    };
+
+    let witness = compile_result
+        .witness_solver
+        .solve_witness(&assignment)
+        .unwrap();
+    let output = compile_result.layered_circuit.run(&witness);
+    assert_eq!(output, vec![true]);
+
+    let file = std::fs::File::create("poseidon_merkle_circuit.txt").unwrap();
+    let writer = std::io::BufWriter::new(file);
+    compile_result
+        .layered_circuit
+        .serialize_into(writer)
+        .unwrap();
+
+    let file = std::fs::File::create("poseidon_merkle_witness.txt").unwrap();
+    let writer = std::io::BufWriter::new(file);
+    witness.serialize_into(writer).unwrap();
+}
@@ -29,7 +29,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
         recipient,
         100.0, // amount to send
         0,     // fee
+        0,     // nonce
         TransactionType::TRANSACTION,
+        None,
     )?;
 
     // Serialize the transaction to JSON
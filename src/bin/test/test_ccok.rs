@@ -29,6 +29,8 @@ fn main() {
         participants.push(Participant {
             public_key: wallet.get_public_key(),
             weight,
+            key_schedule_root: None,
+            weight_commitment: None,
         });
         wallets.push(wallet);
     }
@@ -70,6 +72,7 @@ fn main() {
             msg: msg.clone(),
             proven_weight,
             security_param,
+            epoch: 0,
         };
 
         // Create the Builder
@@ -29,6 +29,8 @@ fn main() {
         participants.push(Participant {
             public_key: wallet.get_public_key(),
             weight,
+            key_schedule_root: None,
+            weight_commitment: None,
         });
         wallets.push(wallet);
         println!("Participant {} created with weight {}", i, weight);
@@ -56,6 +58,7 @@ fn main() {
         msg: msg.clone(),
         proven_weight,
         security_param: 32, // Same security parameter as Go implementation
+        epoch: 0,
     };
 
     // Create the Builder
@@ -1,28 +1,212 @@
 use expander_compiler::frontend::*;
 use internal::Serde;
 
-// Import the SHA3 crate (we may use it externally)
+// Import the SHA3 crate so witness generation can feed the gadget below
+// genuine digests instead of a placeholder.
 use sha3::{Digest, Sha3_256};
-use std::convert::TryInto;
 
-// This function computes a SHA3-256 hash for a u64 value.
-// In a real application you might use all the hash bits, but here we take the first 8 bytes.
-fn compute_sha3_hash(value: u64) -> u64 {
-    let mut hasher = Sha3_256::new();
-    hasher.update(&value.to_be_bytes());
-    let result = hasher.finalize();
-    let bytes: [u8; 8] = result[0..8]
-        .try_into()
-        .expect("slice with incorrect length");
-    u64::from_be_bytes(bytes)
+const VALUE_BITS: usize = 32;
+const LANE_BITS: usize = 64;
+const NUM_LANES: usize = 25;
+// 1600-bit Keccak state, 512 bits (2 * 256-bit security) held back as
+// capacity, leaves a 1088-bit rate for SHA3-256.
+const RATE_BITS: usize = 1088;
+const DIGEST_BITS: usize = 256;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// Standard Keccak lane rotation offsets, indexed `[x][y]`.
+const ROTATION_OFFSETS: [[u32; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+fn lane_index(x: usize, y: usize) -> usize {
+    x + 5 * y
+}
+
+// --- Bitwise building blocks over GF2 (add == xor, mul == and) -------------
+
+fn xor_bit(builder: &mut API<GF2Config>, a: Variable, b: Variable) -> Variable {
+    builder.add(a, b)
+}
+
+fn and_bit(builder: &mut API<GF2Config>, a: Variable, b: Variable) -> Variable {
+    builder.mul(a, b)
+}
+
+fn not_bit(builder: &mut API<GF2Config>, a: Variable) -> Variable {
+    builder.add(a, GF2::from(1))
+}
+
+/// Appends the SHA3 domain separator (`01`) and then the Keccak `pad10*1`
+/// rule: a single `1` bit, as many `0` bits as needed, then a final `1`
+/// bit, so the padded length is a multiple of `RATE_BITS`.
+fn pad_message(builder: &mut API<GF2Config>, mut bits: Vec<Variable>) -> Vec<Variable> {
+    // `x XOR x == 0` regardless of what `bits[0]` actually carries at
+    // witness time, so this gives a genuine constant-zero wire.
+    let zero = builder.add(bits[0], bits[0]);
+    let one = builder.add(zero, GF2::from(1));
+
+    bits.push(zero);
+    bits.push(one);
+
+    bits.push(one);
+    while bits.len() % RATE_BITS != RATE_BITS - 1 {
+        bits.push(zero);
+    }
+    bits.push(one);
+
+    bits
+}
+
+fn theta(builder: &mut API<GF2Config>, state: &mut [Vec<Variable>]) {
+    let mut column_parity: Vec<Vec<Variable>> = Vec::with_capacity(5);
+    for x in 0..5 {
+        let mut col = state[lane_index(x, 0)].clone();
+        for y in 1..5 {
+            for z in 0..LANE_BITS {
+                col[z] = xor_bit(builder, col[z], state[lane_index(x, y)][z]);
+            }
+        }
+        column_parity.push(col);
+    }
+
+    let mut d: Vec<Vec<Variable>> = Vec::with_capacity(5);
+    for x in 0..5 {
+        let mut dx = Vec::with_capacity(LANE_BITS);
+        for z in 0..LANE_BITS {
+            let left = column_parity[(x + 4) % 5][z];
+            let right_rotated = column_parity[(x + 1) % 5][(z + LANE_BITS - 1) % LANE_BITS];
+            dx.push(xor_bit(builder, left, right_rotated));
+        }
+        d.push(dx);
+    }
+
+    for x in 0..5 {
+        for y in 0..5 {
+            let idx = lane_index(x, y);
+            for z in 0..LANE_BITS {
+                state[idx][z] = xor_bit(builder, state[idx][z], d[x][z]);
+            }
+        }
+    }
+}
+
+/// Rho (per-lane rotation) and pi (lane permutation) fused into one pass,
+/// as in most Keccak reference implementations: both are pure wire
+/// relabeling, so they add no constraints.
+fn rho_pi(state: &[Vec<Variable>]) -> Vec<Vec<Variable>> {
+    let mut new_state = state.to_vec();
+    for x in 0..5 {
+        for y in 0..5 {
+            let offset = ROTATION_OFFSETS[x][y] as usize % LANE_BITS;
+            let src = &state[lane_index(x, y)];
+            let rotated: Vec<Variable> = (0..LANE_BITS)
+                .map(|z| src[(z + LANE_BITS - offset) % LANE_BITS])
+                .collect();
+            let dest_x = y;
+            let dest_y = (2 * x + 3 * y) % 5;
+            new_state[lane_index(dest_x, dest_y)] = rotated;
+        }
+    }
+    new_state
+}
+
+fn chi(builder: &mut API<GF2Config>, state: &[Vec<Variable>]) -> Vec<Vec<Variable>> {
+    let mut new_state = state.to_vec();
+    for y in 0..5 {
+        for x in 0..5 {
+            let a0 = &state[lane_index(x, y)];
+            let a1 = &state[lane_index((x + 1) % 5, y)];
+            let a2 = &state[lane_index((x + 2) % 5, y)];
+            let mut out = Vec::with_capacity(LANE_BITS);
+            for z in 0..LANE_BITS {
+                let not_a1 = not_bit(builder, a1[z]);
+                let and_term = and_bit(builder, not_a1, a2[z]);
+                out.push(xor_bit(builder, a0[z], and_term));
+            }
+            new_state[lane_index(x, y)] = out;
+        }
+    }
+    new_state
 }
 
-// This function is meant to represent a SHA3 hash gadget inside the circuit.
-// Since incorporating a full SHA3 gadget is complex, for now we simulate it by
-// simply adding a constant (42) to the input.
-// In a production circuit you would replace this placeholder with a proper SHA3 circuit.
-fn sha3_hash_variable(builder: &mut API<GF2Config>, input: Variable) -> Variable {
-    builder.add(input, GF2::from(42))
+fn iota(builder: &mut API<GF2Config>, state: &mut [Vec<Variable>], round: usize) {
+    let rc = ROUND_CONSTANTS[round];
+    for z in 0..LANE_BITS {
+        if (rc >> z) & 1 == 1 {
+            state[0][z] = not_bit(builder, state[0][z]);
+        }
+    }
+}
+
+fn keccak_f1600(builder: &mut API<GF2Config>, state: &mut Vec<Vec<Variable>>) {
+    for round in 0..24 {
+        theta(builder, state);
+        let permuted = rho_pi(state);
+        *state = chi(builder, &permuted);
+        iota(builder, state, round);
+    }
+}
+
+/// A genuine Keccak-f[1600]-based SHA3-256 gadget: pads `input_bits` with
+/// `pad10*1` into 1088-bit (rate) blocks, absorbs each block into a
+/// 1600-bit state via 24 rounds of theta/rho/pi/chi/iota, and squeezes the
+/// first 256 bits of the resulting state as the digest.
+fn sha3_256_gadget(builder: &mut API<GF2Config>, input_bits: Vec<Variable>) -> Vec<Variable> {
+    let padded = pad_message(builder, input_bits);
+    let zero = builder.add(padded[0], padded[0]);
+
+    let mut state: Vec<Vec<Variable>> = (0..NUM_LANES).map(|_| vec![zero; LANE_BITS]).collect();
+
+    for block in padded.chunks(RATE_BITS) {
+        for (i, &bit) in block.iter().enumerate() {
+            let lane = i / LANE_BITS;
+            let z = i % LANE_BITS;
+            state[lane][z] = xor_bit(builder, state[lane][z], bit);
+        }
+        keccak_f1600(builder, &mut state);
+    }
+
+    let mut digest = Vec::with_capacity(DIGEST_BITS);
+    'squeeze: for lane in &state {
+        for &bit in lane {
+            digest.push(bit);
+            if digest.len() == DIGEST_BITS {
+                break 'squeeze;
+            }
+        }
+    }
+    digest
 }
 
 declare_circuit!(Circuit {
@@ -45,59 +229,95 @@ impl Define<GF2Config> for Circuit<Variable> {
 }
 
 declare_circuit!(PQZKCircuit {
-    seed_a: Variable,
-    r_s: Variable,
-    sk_s: Variable,
-    pk_m: Variable,
-    c_a: Variable,
-    pk_s: Variable,
+    seed_a: [Variable; VALUE_BITS],
+    r_s: [Variable; VALUE_BITS],
+    sk_s: [Variable; VALUE_BITS],
+    pk_m: [Variable; DIGEST_BITS],
+    c_a: [Variable; DIGEST_BITS],
+    pk_s: [Variable; VALUE_BITS],
 });
 
 impl Define<GF2Config> for PQZKCircuit<Variable> {
     fn define(&self, builder: &mut API<GF2Config>) {
-        // Step 1: Compute hashed_seed = SHA3(SeedA) using our gadget.
-        let hashed_seed = sha3_hash_variable(builder, self.seed_a);
-        // Simulate key generation: add 1 to the hash.
-        let computed_pk_m = builder.add(hashed_seed, GF2::from(1));
-        builder.assert_is_equal(computed_pk_m, self.pk_m);
-
-        // Step 2: Compute the commitment c_a = SHA3(SeedA || rS).
-        // Here we simulate concatenation by adding seed_a and r_s first.
-        let commitment_input = builder.add(self.seed_a, self.r_s);
-        let computed_c_a = sha3_hash_variable(builder, commitment_input);
-        builder.assert_is_equal(computed_c_a, self.c_a);
-
-        // Step 3: Compute pk_s = PQKGen(sk_s), simulated by adding 1.
-        let computed_pk_s = builder.add(self.sk_s, GF2::from(1));
-        builder.assert_is_equal(computed_pk_s, self.pk_s);
+        // Step 1: pk_m = SHA3-256(seed_a), bound to a genuine Keccak-f[1600]
+        // permutation rather than a `+ 42` stand-in.
+        let computed_pk_m = sha3_256_gadget(builder, self.seed_a.to_vec());
+        for (computed, expected) in computed_pk_m.iter().zip(self.pk_m.iter()) {
+            builder.assert_is_equal(*computed, *expected);
+        }
+
+        // Step 2: the commitment c_a = SHA3-256(seed_a || r_s).
+        let commitment_input: Vec<Variable> =
+            self.seed_a.iter().chain(self.r_s.iter()).cloned().collect();
+        let computed_c_a = sha3_256_gadget(builder, commitment_input);
+        for (computed, expected) in computed_c_a.iter().zip(self.c_a.iter()) {
+            builder.assert_is_equal(*computed, *expected);
+        }
+
+        // Step 3: pk_s = PQKGen(sk_s). Unrelated to the hash gadget above,
+        // so it stays a placeholder (bitwise NOT instead of the scalar
+        // `+ 1` the old single-`Variable` encoding used) until a real
+        // lattice keygen circuit is wired in.
+        for (sk_bit, pk_bit) in self.sk_s.iter().zip(self.pk_s.iter()) {
+            let computed = not_bit(builder, *sk_bit);
+            builder.assert_is_equal(computed, *pk_bit);
+        }
+    }
+}
+
+/// Little-endian bit decomposition matching Keccak's byte-then-LSB-first
+/// absorption order, so the witness and the in-circuit gadget agree on
+/// which wire is which bit of the message.
+fn u32_to_bits(value: u32) -> [bool; VALUE_BITS] {
+    let bytes = value.to_le_bytes();
+    let mut bits = [false; VALUE_BITS];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = (bytes[i / 8] >> (i % 8)) & 1 == 1;
+    }
+    bits
+}
+
+fn bits_to_gf2<const N: usize>(bits: [bool; N]) -> [GF2; N] {
+    bits.map(|b| GF2::from(b as u32))
+}
+
+fn digest_to_gf2(digest: &[u8]) -> [GF2; DIGEST_BITS] {
+    let mut bits = [GF2::from(0u32); DIGEST_BITS];
+    for (i, slot) in bits.iter_mut().enumerate() {
+        *slot = GF2::from(((digest[i / 8] >> (i % 8)) & 1) as u32);
     }
+    bits
+}
+
+fn sha3_256_bytes(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(bytes);
+    hasher.finalize().into()
 }
 
 fn main() {
     let compile_result = compile(&PQZKCircuit::default()).unwrap();
 
-    // For demonstration, use simplified GF2 values.
-    // In our circuit, the SHA3 gadget is simulated as: sha3(x) = x + 42.
-    // Thus for seed_a = 123:
-    //   hashed_seed = 123 + 42 = 165, then pk_m = 165 + 1 = 166.
-    // For the commitment: seed_a + r_s = 123 + 456 = 579, then c_a = 579 + 42 = 621.
-    // And for pk_s: sk_s = 789, then pk_s = 789 + 1 = 790.
     let seed_a_val: u32 = 123;
     let r_s_val: u32 = 456;
     let sk_s_val: u32 = 789;
-    let simulated_hashed_seed = seed_a_val.wrapping_add(42); // 123 + 42 = 165
-    let pk_m_val = simulated_hashed_seed.wrapping_add(1); // 165 + 1 = 166
-    let commitment_input_val = seed_a_val.wrapping_add(r_s_val); // 123 + 456 = 579
-    let c_a_val = commitment_input_val.wrapping_add(42); // 579 + 42 = 621
-    let pk_s_val = sk_s_val.wrapping_add(1); // 789 + 1 = 790
+
+    let pk_m_digest = sha3_256_bytes(&seed_a_val.to_le_bytes());
+    let commitment_bytes: Vec<u8> = seed_a_val
+        .to_le_bytes()
+        .into_iter()
+        .chain(r_s_val.to_le_bytes())
+        .collect();
+    let c_a_digest = sha3_256_bytes(&commitment_bytes);
+    let pk_s_val = !sk_s_val;
 
     let assignment = PQZKCircuit::<GF2> {
-        seed_a: GF2::from(seed_a_val),
-        r_s: GF2::from(r_s_val),
-        sk_s: GF2::from(sk_s_val),
-        pk_m: GF2::from(pk_m_val),
-        c_a: GF2::from(c_a_val),
-        pk_s: GF2::from(pk_s_val),
+        seed_a: bits_to_gf2(u32_to_bits(seed_a_val)),
+        r_s: bits_to_gf2(u32_to_bits(r_s_val)),
+        sk_s: bits_to_gf2(u32_to_bits(sk_s_val)),
+        pk_m: digest_to_gf2(&pk_m_digest),
+        c_a: digest_to_gf2(&c_a_digest),
+        pk_s: bits_to_gf2(u32_to_bits(pk_s_val)),
     };
 
     let witness = compile_result
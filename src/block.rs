@@ -1,8 +1,9 @@
 use crate::accounts::Account;
 use crate::ccok::Certificate;
+use crate::lottery::LeaderProof;
 use crate::transaction::Transaction;
 use crate::utils::Seed;
-use rs_merkle::{Hasher, MerkleTree};
+use rs_merkle::{Hasher, MerkleProof, MerkleTree};
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_256};
 
@@ -30,9 +31,84 @@ pub struct Block {
     pub proposer_hash: String,
     pub seed: Seed,
     pub certificate: Option<Certificate>,
+    /// Proof that `proposer_address` privately won the leader lottery for
+    /// this slot. `None` for blocks built before the private lottery
+    /// existed (e.g. genesis).
+    pub leader_proof: Option<LeaderProof>,
+}
+
+/// A `Block`'s linkage and post-quantum certificate, without its
+/// transactions — what a peer needs to sync and validate the chain's
+/// structure during headers-first sync before backfilling any bodies.
+/// `tx_root` is `hash` named for what it actually commits to (the current
+/// `Block::hash` already *is* the Merkle root over `txn`, per
+/// `Block::compute_merkle_root`); once a body arrives, comparing its
+/// recomputed root to this header's `tx_root` is how `verify_tx_inclusion`
+/// and friends confirm the body actually matches the header that was synced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    pub id: usize,
+    pub hash: [u8; 32],
+    pub previous_hash: [u8; 32],
+    pub timestamp: usize,
+    pub proposer_address: Account,
+    pub proposer_hash: String,
+    pub seed: Seed,
+    pub certificate: Option<Certificate>,
+    pub tx_root: [u8; 32],
+}
+
+impl BlockHeader {
+    /// Checks this header continues `prev` (matching `id`/`previous_hash`)
+    /// and, if a certificate is attached, that it isn't a degenerate
+    /// zero-weight one — all without needing the block body. This is the
+    /// cheap check a headers-first sync runs while chaining headers; full
+    /// cryptographic certificate verification still needs the validator
+    /// party tree and proven weight (see
+    /// `crate::block_queue::BlockVerificationContext`/`verify_stage1`),
+    /// which aren't available from a pair of headers alone.
+    pub fn verify_links(&self, prev: &BlockHeader) -> Result<(), String> {
+        if self.id != prev.id + 1 {
+            return Err(format!(
+                "header {}: id does not continue from previous header {}",
+                self.id, prev.id
+            ));
+        }
+        if self.previous_hash != prev.hash {
+            return Err(format!(
+                "header {}: previous_hash does not match the previous header's hash",
+                self.id
+            ));
+        }
+        if let Some(certificate) = &self.certificate {
+            if certificate.signed_weight == 0 {
+                return Err(format!(
+                    "header {}: certificate carries zero signed weight",
+                    self.id
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Block {
+    /// Derives this block's [`BlockHeader`]: everything a headers-first sync
+    /// needs to chain and validate linkage without downloading `self.txn`.
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            id: self.id,
+            hash: self.hash,
+            previous_hash: self.previous_hash,
+            timestamp: self.timestamp,
+            proposer_address: self.proposer_address.clone(),
+            proposer_hash: self.proposer_hash.clone(),
+            seed: self.seed.clone(),
+            certificate: self.certificate.clone(),
+            tx_root: self.hash,
+        }
+    }
+
     pub fn new(
         id: usize,
         previous_hash: [u8; 32],
@@ -42,6 +118,7 @@ impl Block {
         proposer_hash: String,
         seed: Seed,
         certificate: Option<Certificate>,
+        leader_proof: Option<LeaderProof>,
     ) -> Result<Self, String> {
         let mut block = Self {
             id,
@@ -53,6 +130,7 @@ impl Block {
             proposer_hash,
             seed,
             certificate,
+            leader_proof,
         };
         block.hash = block.compute_merkle_root();
         Ok(block)
@@ -66,4 +144,194 @@ impl Block {
         let tree = MerkleTree::<Sha3Hasher>::from_leaves(&leaves);
         tree.root().unwrap()
     }
+
+    /// Recomputes the Merkle root over `self.txn` and compares it to
+    /// `self.hash`, catching a block whose advertised hash doesn't match the
+    /// transactions it actually carries. Used as the first of
+    /// `block_queue::verify_stage1`'s checks.
+    pub fn verify_merkle_root(&self) -> bool {
+        self.compute_merkle_root() == self.hash
+    }
+
+    /// Builds a serialized Merkle inclusion proof for `self.txn[tx_index]`,
+    /// so a light client holding only this block's header (`self.hash`) can
+    /// confirm one transaction was included without fetching every
+    /// transaction in the block. Rebuilds the same `MerkleTree` used by
+    /// `compute_merkle_root` (leaf order matches transaction insertion
+    /// order, so indices line up with `self.txn`) rather than caching the
+    /// tree, since `Block` only ever needs one proof at a time and isn't on
+    /// a path hot enough to justify holding the tree around between calls.
+    /// Returns `None` for an empty block (whose `hash` is the all-zero
+    /// sentinel, not a real Merkle root) or an out-of-range `tx_index`.
+    pub fn merkle_proof(&self, tx_index: usize) -> Option<Vec<u8>> {
+        if self.txn.is_empty() || tx_index >= self.txn.len() {
+            return None;
+        }
+        let leaves: Vec<[u8; 32]> = self.txn.iter().map(|tx| tx.hash).collect();
+        let tree = MerkleTree::<Sha3Hasher>::from_leaves(&leaves);
+        let proof = tree.proof(&[tx_index]);
+        Some(proof.to_bytes())
+    }
+}
+
+/// Verifies an SPV-style inclusion proof produced by [`Block::merkle_proof`]
+/// against a block header's `root` alone — no other transaction in the
+/// block is needed. Rejects the all-zero sentinel root an empty block
+/// produces and any `tx_index` outside `leaf_count`, since both would
+/// otherwise let a malformed proof "verify" against data that was never a
+/// real Merkle tree.
+pub fn verify_tx_inclusion(
+    root: [u8; 32],
+    proof_bytes: &[u8],
+    tx_hash: [u8; 32],
+    tx_index: usize,
+    leaf_count: usize,
+) -> bool {
+    if leaf_count == 0 || root == [0u8; 32] || tx_index >= leaf_count {
+        return false;
+    }
+    let Ok(proof) = MerkleProof::<Sha3Hasher>::from_bytes(proof_bytes) else {
+        return false;
+    };
+    proof.verify(root, &[tx_index], &[tx_hash], leaf_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+    use crate::wallet::Wallet;
+
+    fn test_block(txn_count: usize) -> Block {
+        test_block_at(1, [0u8; 32], txn_count)
+    }
+
+    fn test_block_at(id: usize, previous_hash: [u8; 32], txn_count: usize) -> Block {
+        let proposer_address = Account { address: "proposer".to_string() };
+        let txns: Vec<Transaction> = (0..txn_count)
+            .map(|i| {
+                let mut wallet = Wallet::new().unwrap();
+                let sender = Account { address: wallet.get_public_key() };
+                let recipient = Account { address: format!("recipient-{}", i) };
+                Transaction::new(&mut wallet, sender, recipient, i as f64, 0, 0, TransactionType::TRANSACTION, None).unwrap()
+            })
+            .collect();
+        Block::new(
+            id,
+            previous_hash,
+            0,
+            txns,
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_merkle_proof_verifies_against_the_block_hash() {
+        let block = test_block(5);
+        let tx_index = 2;
+        let proof_bytes = block.merkle_proof(tx_index).expect("a non-empty block must produce a proof");
+
+        assert!(verify_tx_inclusion(
+            block.hash,
+            &proof_bytes,
+            block.txn[tx_index].hash,
+            tx_index,
+            block.txn.len(),
+        ));
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_the_wrong_transaction_hash() {
+        let block = test_block(5);
+        let tx_index = 2;
+        let proof_bytes = block.merkle_proof(tx_index).unwrap();
+
+        assert!(!verify_tx_inclusion(
+            block.hash,
+            &proof_bytes,
+            block.txn[tx_index + 1].hash,
+            tx_index,
+            block.txn.len(),
+        ));
+    }
+
+    #[test]
+    fn test_merkle_proof_rejects_a_mismatched_tx_index() {
+        let block = test_block(5);
+        let tx_index = 2;
+        let proof_bytes = block.merkle_proof(tx_index).unwrap();
+
+        assert!(!verify_tx_inclusion(
+            block.hash,
+            &proof_bytes,
+            block.txn[tx_index].hash,
+            tx_index + 1,
+            block.txn.len(),
+        ));
+    }
+
+    #[test]
+    fn test_merkle_proof_returns_none_for_an_empty_block() {
+        let block = test_block(0);
+        assert_eq!(block.hash, [0u8; 32], "an empty block's hash is the all-zero sentinel");
+        assert!(block.merkle_proof(0).is_none());
+    }
+
+    #[test]
+    fn test_merkle_proof_returns_none_for_an_out_of_range_index() {
+        let block = test_block(3);
+        assert!(block.merkle_proof(3).is_none());
+    }
+
+    #[test]
+    fn test_verify_tx_inclusion_rejects_the_all_zero_sentinel_root() {
+        assert!(!verify_tx_inclusion([0u8; 32], &[], [1u8; 32], 0, 1));
+    }
+
+    #[test]
+    fn test_verify_tx_inclusion_rejects_an_out_of_range_tx_index() {
+        let block = test_block(3);
+        let proof_bytes = block.merkle_proof(0).unwrap();
+        assert!(!verify_tx_inclusion(block.hash, &proof_bytes, block.txn[0].hash, 3, block.txn.len()));
+    }
+
+    #[test]
+    fn test_header_carries_the_block_hash_as_its_tx_root() {
+        let block = test_block(4);
+        let header = block.header();
+
+        assert_eq!(header.id, block.id);
+        assert_eq!(header.hash, block.hash);
+        assert_eq!(header.tx_root, block.hash);
+        assert!(header.certificate.is_none());
+    }
+
+    #[test]
+    fn test_verify_links_accepts_a_header_that_continues_the_previous_one() {
+        let genesis = test_block_at(1, [0u8; 32], 0);
+        let next = test_block_at(2, genesis.hash, 3);
+
+        assert!(next.header().verify_links(&genesis.header()).is_ok());
+    }
+
+    #[test]
+    fn test_verify_links_rejects_a_mismatched_previous_hash() {
+        let genesis = test_block_at(1, [0u8; 32], 0);
+        let next = test_block_at(2, [9u8; 32], 3);
+
+        assert!(next.header().verify_links(&genesis.header()).is_err());
+    }
+
+    #[test]
+    fn test_verify_links_rejects_a_non_consecutive_id() {
+        let genesis = test_block_at(1, [0u8; 32], 0);
+        let skipped = test_block_at(3, genesis.hash, 3);
+
+        assert!(skipped.header().verify_links(&genesis.header()).is_err());
+    }
 }
@@ -0,0 +1,392 @@
+//! Decouples expensive per-block verification (Merkle root recompute,
+//! hashchain/proposer checks, compact-certificate verification) from the
+//! p2p receive path, mirroring how a full node pipelines block validation
+//! off its network thread instead of blocking gossip delivery on it.
+//!
+//! `BlockQueue` itself only tracks which block hashes are `unverified`,
+//! `verifying`, `bad`, or `verified` and deduplicates across all four sets;
+//! it deliberately doesn't reach into `Blockchain`/`Validator` state
+//! directly (same trait/context-seam style as `crate::settlement`'s
+//! `L1Checkpointer` and `crate::bridge::deposit`'s `RouterLogSource`). The
+//! caller builds a [`BlockVerificationContext`] once per block from its own
+//! chain/validator state — see `Blockchain::party_tree` for the equivalent
+//! derivation already used by `finalize_block`/`is_block_final` — and
+//! [`verify_stage1`] checks a `Block` against it.
+
+use crate::block::Block;
+use crate::ccok::Params;
+use crate::hashchain::verify_hash_chain_index;
+use log::warn;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Condvar, Mutex};
+
+/// External chain/validator state a bare `Block` doesn't carry itself but
+/// stage-1 verification needs: the expected predecessor hash, the proposer's
+/// hashchain commitment for the current epoch, and the validator party tree
+/// a certificate (if any) must verify against.
+#[derive(Debug, Clone)]
+pub struct BlockVerificationContext {
+    pub expected_previous_hash: [u8; 32],
+    pub proposer_hash_chain_index: String,
+    pub epoch_timestamp: u64,
+    pub proven_weight: u64,
+    pub party_tree_root: Vec<u8>,
+}
+
+/// Runs the queue's stage-1 checks against `block`: recomputed Merkle root
+/// matches `block.hash`, `previous_hash` links to `ctx.expected_previous_hash`,
+/// `proposer_hash` reveals the expected hashchain position, and — if present
+/// — `certificate` verifies against `ctx.party_tree_root`. The genesis block
+/// (`id == 1`) has no predecessor or proposer commitment to check against,
+/// mirroring `Blockchain::verify_block`'s and `Blockchain::validate_block`'s
+/// own early return for it.
+pub fn verify_stage1(block: &Block, ctx: &BlockVerificationContext) -> Result<(), String> {
+    if !block.verify_merkle_root() {
+        return Err(format!(
+            "block {}: merkle root does not match its transactions",
+            block.id
+        ));
+    }
+
+    if block.id != 1 {
+        if block.previous_hash != ctx.expected_previous_hash {
+            return Err(format!(
+                "block {}: previous_hash does not match the expected chain tip",
+                block.id
+            ));
+        }
+
+        if !verify_hash_chain_index(
+            ctx.proposer_hash_chain_index.clone(),
+            ctx.epoch_timestamp,
+            block.proposer_hash.clone(),
+        ) {
+            return Err(format!(
+                "block {}: proposer_hash does not reveal the expected hashchain position",
+                block.id
+            ));
+        }
+    }
+
+    if let Some(certificate) = &block.certificate {
+        let params = Params {
+            msg: hex::encode(ctx.expected_previous_hash).into_bytes(),
+            proven_weight: ctx.proven_weight,
+            security_param: 128,
+            epoch: 0,
+        };
+        match certificate.verify(&params, &ctx.party_tree_root) {
+            Ok(true) => {}
+            Ok(false) => return Err(format!("block {}: certificate failed to verify", block.id)),
+            Err(e) => {
+                return Err(format!(
+                    "block {}: certificate verification error: {}",
+                    block.id, e
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Inner {
+    unverified: VecDeque<Block>,
+    unverified_hashes: HashSet<[u8; 32]>,
+    verifying: HashSet<[u8; 32]>,
+    bad: HashMap<[u8; 32], String>,
+    verified: VecDeque<Block>,
+    verified_hashes: HashSet<[u8; 32]>,
+}
+
+impl Inner {
+    fn knows_hash(&self, hash: &[u8; 32]) -> bool {
+        self.unverified_hashes.contains(hash)
+            || self.verifying.contains(hash)
+            || self.bad.contains_key(hash)
+            || self.verified_hashes.contains(hash)
+    }
+}
+
+/// A multi-stage, hash-deduplicating block verification pipeline sitting
+/// between the p2p receive path and chain import. A receive-side caller
+/// [`push`](BlockQueue::push)es incoming blocks; a worker repeatedly calls
+/// [`process_next`](BlockQueue::process_next) to run stage-1 checks on one
+/// block at a time; an import loop blocks on
+/// [`pop_verified`](BlockQueue::pop_verified) for blocks ready to execute.
+pub struct BlockQueue {
+    inner: Mutex<Inner>,
+    verified_ready: Condvar,
+}
+
+impl BlockQueue {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner::default()),
+            verified_ready: Condvar::new(),
+        }
+    }
+
+    /// Queues `block` for stage-1 verification unless its hash is already
+    /// known to any of the four sets, in which case it's dropped. Returns
+    /// whether the block was actually queued.
+    pub fn push(&self, block: Block) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.knows_hash(&block.hash) {
+            return false;
+        }
+        inner.unverified_hashes.insert(block.hash);
+        inner.unverified.push_back(block);
+        true
+    }
+
+    /// Total number of block hashes this queue currently knows about, across
+    /// all of `unverified`, `verifying`, `bad`, and `verified` — the figure
+    /// `push`'s deduplication is checked against.
+    pub fn total_queue_size(&self) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner.unverified.len() + inner.verifying.len() + inner.bad.len() + inner.verified.len()
+    }
+
+    /// Pulls the oldest queued block and runs stage-1 checks against it. On
+    /// success the block moves to `verified` and wakes any waiter blocked on
+    /// `pop_verified`; on failure its hash moves to `bad` with the failure
+    /// reason logged. Returns `false` if the block failed verification,
+    /// `true` if it passed, or `None` if there was nothing queued.
+    pub fn process_next(&self, ctx: &BlockVerificationContext) -> Option<bool> {
+        let block = {
+            let mut inner = self.inner.lock().unwrap();
+            let block = inner.unverified.pop_front()?;
+            inner.unverified_hashes.remove(&block.hash);
+            inner.verifying.insert(block.hash);
+            block
+        };
+
+        match verify_stage1(&block, ctx) {
+            Ok(()) => {
+                let mut inner = self.inner.lock().unwrap();
+                inner.verifying.remove(&block.hash);
+                inner.verified_hashes.insert(block.hash);
+                inner.verified.push_back(block);
+                self.verified_ready.notify_one();
+                Some(true)
+            }
+            Err(reason) => {
+                warn!("Dropping block {}: {}", block.id, reason);
+                let mut inner = self.inner.lock().unwrap();
+                inner.verifying.remove(&block.hash);
+                inner.bad.insert(block.hash, reason);
+                Some(false)
+            }
+        }
+    }
+
+    /// Blocks until a verified block is available, then returns it.
+    pub fn pop_verified(&self) -> Block {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            if let Some(block) = inner.verified.pop_front() {
+                inner.verified_hashes.remove(&block.hash);
+                return block;
+            }
+            inner = self.verified_ready.wait(inner).unwrap();
+        }
+    }
+
+    /// The logged reason a block hash was rejected, if any.
+    pub fn rejection_reason(&self, hash: &[u8; 32]) -> Option<String> {
+        self.inner.lock().unwrap().bad.get(hash).cloned()
+    }
+}
+
+impl Default for BlockQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::Account;
+    use crate::ccok::{Builder as CertBuilder, Participant};
+    use crate::merkle::MerkleTreeBuilder;
+    use crate::transaction::{Transaction, TransactionType};
+    use crate::utils::Seed;
+    use crate::wallet::Wallet;
+
+    fn test_block(id: usize, previous_hash: [u8; 32], proposer_hash: &str) -> Block {
+        let proposer_address = Account {
+            address: "proposer".to_string(),
+        };
+        Block::new(
+            id,
+            previous_hash,
+            0,
+            vec![],
+            proposer_address,
+            proposer_hash.to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn default_ctx() -> BlockVerificationContext {
+        BlockVerificationContext {
+            expected_previous_hash: [0u8; 32],
+            proposer_hash_chain_index: String::new(),
+            epoch_timestamp: 0,
+            proven_weight: 0,
+            party_tree_root: vec![],
+        }
+    }
+
+    #[test]
+    fn test_push_queues_a_new_block_and_rejects_a_duplicate_hash() {
+        let queue = BlockQueue::new();
+        let block = test_block(1, [0u8; 32], "proposer-hash");
+
+        assert!(queue.push(block.clone()));
+        assert!(!queue.push(block), "a block whose hash is already queued must be dropped");
+        assert_eq!(queue.total_queue_size(), 1);
+    }
+
+    #[test]
+    fn test_process_next_promotes_a_valid_genesis_block_to_verified() {
+        let queue = BlockQueue::new();
+        let block = test_block(1, [0u8; 32], "proposer-hash");
+        let block_hash = block.hash;
+        queue.push(block);
+
+        assert_eq!(queue.process_next(&default_ctx()), Some(true));
+        let popped = queue.pop_verified();
+        assert_eq!(popped.hash, block_hash);
+    }
+
+    #[test]
+    fn test_process_next_rejects_a_block_with_the_wrong_previous_hash() {
+        let queue = BlockQueue::new();
+        let block = test_block(2, [0xffu8; 32], "proposer-hash");
+        let block_hash = block.hash;
+        queue.push(block);
+
+        let ctx = BlockVerificationContext {
+            expected_previous_hash: [0u8; 32],
+            ..default_ctx()
+        };
+        assert_eq!(queue.process_next(&ctx), Some(false));
+        assert!(queue.rejection_reason(&block_hash).unwrap().contains("previous_hash"));
+        assert_eq!(queue.total_queue_size(), 1, "the rejected hash stays known, in `bad`");
+    }
+
+    #[test]
+    fn test_process_next_rejects_a_tampered_merkle_root() {
+        let queue = BlockQueue::new();
+        let mut wallet = Wallet::new().unwrap();
+        let sender = Account { address: wallet.get_public_key() };
+        let txn = Transaction::new(&mut wallet, sender.clone(), sender, 1.0, 0, 0, TransactionType::TRANSACTION, None).unwrap();
+        let mut block = test_block(1, [0u8; 32], "proposer-hash");
+        block.txn.push(txn);
+        let block_hash = block.hash;
+        queue.push(block);
+
+        assert_eq!(queue.process_next(&default_ctx()), Some(false));
+        assert!(queue.rejection_reason(&block_hash).unwrap().contains("merkle root"));
+    }
+
+    #[test]
+    fn test_process_next_rejects_a_wrong_proposer_hash() {
+        let queue = BlockQueue::new();
+        let block = test_block(2, [0u8; 32], &hex::encode([9u8; 32]));
+        let block_hash = block.hash;
+        queue.push(block);
+
+        let ctx = BlockVerificationContext {
+            expected_previous_hash: [0u8; 32],
+            proposer_hash_chain_index: hex::encode([7u8; 32]),
+            ..default_ctx()
+        };
+        assert_eq!(queue.process_next(&ctx), Some(false));
+        assert!(queue.rejection_reason(&block_hash).unwrap().contains("proposer_hash"));
+    }
+
+    #[test]
+    fn test_process_next_verifies_a_present_certificate() {
+        let wallet = Wallet::new().expect("failed to create wallet");
+        let participants = vec![Participant {
+            public_key: wallet.get_public_key(),
+            weight: 10,
+            key_schedule_root: None,
+            weight_commitment: None,
+        }];
+        let mut party_tree = MerkleTreeBuilder::new();
+        party_tree.build(&participants).expect("failed to build party tree");
+        let party_tree_root = party_tree.root();
+
+        let previous_hash = [3u8; 32];
+        let msg = hex::encode(previous_hash).into_bytes();
+        let params = Params {
+            msg: msg.clone(),
+            proven_weight: 5,
+            security_param: 128,
+            epoch: 0,
+        };
+        let mut builder = CertBuilder::new(params, participants, party_tree_root.clone());
+        builder
+            .add_signature(0, wallet.sign_message(&msg))
+            .expect("failed to add signature");
+        let certificate = builder.build().expect("failed to build certificate");
+
+        let proposer_address = Account { address: "proposer".to_string() };
+        let mut block = Block::new(
+            2,
+            previous_hash,
+            0,
+            vec![],
+            proposer_address,
+            hex::encode([0u8; 32]),
+            Seed { seed: [0u8; 32] },
+            Some(certificate),
+            None,
+        )
+        .unwrap();
+        block.proposer_hash = hex::encode([0u8; 32]);
+
+        let queue = BlockQueue::new();
+        let block_hash = block.hash;
+        queue.push(block);
+
+        let ctx = BlockVerificationContext {
+            expected_previous_hash: previous_hash,
+            proposer_hash_chain_index: hex::encode([0u8; 32]),
+            epoch_timestamp: 0,
+            proven_weight: 5,
+            party_tree_root,
+        };
+        assert_eq!(queue.process_next(&ctx), Some(true));
+        assert_eq!(queue.pop_verified().hash, block_hash);
+    }
+
+    #[test]
+    fn test_pop_verified_blocks_until_a_block_is_pushed_and_processed() {
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::Duration;
+
+        let queue = Arc::new(BlockQueue::new());
+        let reader = Arc::clone(&queue);
+        let handle = thread::spawn(move || reader.pop_verified());
+
+        thread::sleep(Duration::from_millis(50));
+        let block = test_block(1, [0u8; 32], "proposer-hash");
+        let block_hash = block.hash;
+        queue.push(block);
+        queue.process_next(&default_ctx());
+
+        let popped = handle.join().expect("pop_verified thread panicked");
+        assert_eq!(popped.hash, block_hash);
+    }
+}
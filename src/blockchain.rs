@@ -1,19 +1,27 @@
 use crate::accounts::{Account, State};
 use crate::block::Block;
+use crate::block_queue::{BlockQueue, BlockVerificationContext};
 use crate::ccok::{Builder as CertBuilder, Certificate, Params, Participant};
+use crate::chain_spec::ChainSpec;
 #[allow(unused_imports)]
-use crate::config::EPOCH_DURATION;
-use crate::epoch::Epoch;
+use crate::config::{EPOCH_DURATION, EPOCH_REWARD_POOL, MAX_VALIDATOR_SLOTS, STAKING_AMOUNT};
+use crate::consensus::{RoundState, VoteOutcome};
+use crate::epoch::{Epoch, RewardAccumulator};
+use crate::genesis::Genesis;
 use crate::hashchain::{verify_hash_chain_index, HashChain};
+use crate::lottery::{Coin, LeaderProof, ACTIVE_SLOT_COEFFICIENT};
 use crate::mempool::Mempool;
 use crate::merkle::MerkleTreeBuilder;
-use crate::p2p::BlockSignature;
-use crate::transaction::{Transaction, TransactionType};
-use crate::utils::{get_block_seed, select_block_proposer, Seed};
+use crate::p2p::{BlockSignature, VoteMessage};
+use crate::settlement::{compute_epoch_checkpoint_root, Checkpoint, L1Checkpointer};
+use crate::storage::ChainStore;
+use crate::transaction::{Transaction, TransactionType, VerifiedTransaction};
+use crate::utils::{get_block_seed, select_round_proposer, Seed};
 use crate::validator::Validator;
 use crate::wallet::Wallet;
 use hex;
 use log::{error, info, warn};
+use rand::Rng;
 use std::collections::HashMap;
 use std::convert::TryInto;
 
@@ -24,10 +32,93 @@ pub struct Blockchain {
     pub state: State,
     pub validator: Validator,
     pub epoch: Epoch,
+    /// Tracks which validators proposed blocks (and their stake while doing
+    /// so) over the current epoch, so `end_of_epoch` can split
+    /// `EPOCH_REWARD_POOL` among them via `Epoch::settle_rewards`.
+    pub reward_accumulator: RewardAccumulator,
     pub buffer: Buffer,
     pub hash_chain: HashChain,
     pub pending_signatures: HashMap<usize, Vec<BlockSignature>>,
     pub last_certificate: Option<(usize, Certificate)>,
+    /// Backing persistence for this chain, if any. `Blockchain::new` leaves
+    /// this `None` (purely in-memory, as before); `Blockchain::open` wires
+    /// up a `ChainStore` so every executed block writes through to disk.
+    pub store: Option<ChainStore>,
+    /// This node's private leader-lottery coin. Only its committed hash
+    /// (published via the epoch's `HashChainCom`) and the `LeaderProof`s it
+    /// produces ever leave the node. See `crate::lottery`.
+    pub coin: Coin,
+    /// Liveness clock, advanced one tick per elapsed `BLOCK_INTERVAL` by
+    /// `tick()`. Ticks (not wall-clock time) drive the stall watchdog so
+    /// tests can exercise it deterministically instead of sleeping.
+    pub current_tick: u64,
+    /// The tick `execute_block` last appended to `self.chain` at, so
+    /// `tip_age_ticks` can tell how long the tip has been stuck.
+    pub last_tip_progress_tick: u64,
+    /// The tick each height's `pending_signatures` bucket was first opened
+    /// at, so `check_liveness` can tell a certificate collection that's
+    /// taking too long from one that's merely in progress.
+    pub pending_signature_started_at: HashMap<usize, u64>,
+    /// Round-based BFT prevote/precommit tallies, one `RoundState` per
+    /// in-flight height. Additive to (not a replacement for) the
+    /// compact-certificate finality above `pending_signatures` feeds: this
+    /// lets a node independently recognize a Precommit quorum and trigger
+    /// `execute_block` without waiting on the certificate/gossip-relay path.
+    pub bft_rounds: HashMap<usize, RoundState>,
+    /// Blocks proposed into the BFT gadget via `register_bft_proposal`,
+    /// held here until their height's `RoundState` reaches a Precommit
+    /// quorum on their hash, at which point `record_bft_vote` executes and
+    /// removes them.
+    pub bft_pending_blocks: HashMap<usize, Block>,
+    /// L1 checkpoint submission client, set via `set_l1_checkpointer`.
+    /// `None` (the default) leaves `end_of_epoch` a no-op on this front, so
+    /// nodes that don't configure L1 anchoring are unaffected.
+    pub checkpointer: Option<Box<dyn L1Checkpointer + Send>>,
+    /// Index into `self.chain` of the first block not yet covered by a
+    /// submitted checkpoint, so each epoch only anchors the blocks that are
+    /// new since the last one.
+    pub last_checkpointed_height: usize,
+    /// Monotonic counter stamped onto each `Checkpoint` as its `epoch`
+    /// number, incremented only when a submission succeeds.
+    pub anchored_epoch_count: u64,
+    /// Stage-1 verification pipeline a gossiped block passes through before
+    /// `handle_incoming_block` runs fork-choice/validation/execution on it.
+    /// See `receive_block` and `crate::block_queue`.
+    pub block_queue: BlockQueue,
+}
+
+/// Why the liveness watchdog fired, returned by `Blockchain::check_liveness`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallReason {
+    /// The chain tip hasn't advanced for `max_tip_stall_intervals` ticks.
+    TipNotAdvancing,
+    /// The next height's pending-signature set never reached the
+    /// signed-weight threshold within `max_signature_wait_intervals` ticks.
+    SignatureDeadlineExceeded,
+}
+
+/// A recoverable stall the watchdog cleared, naming the stuck height and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StallEvent {
+    pub height: usize,
+    pub reason: StallReason,
+}
+
+/// Outcome of comparing a candidate block against the current chain tip.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ForkChoice {
+    /// Extends the current tip; apply it directly.
+    Accept,
+    /// Competes with the current tip at the same height and wins the
+    /// deterministic tie-break (lowest block hash); roll back and replay.
+    Reorg,
+    /// Loses the fork-choice comparison; discard it.
+    Reject,
+}
+
+fn new_random_coin() -> Coin {
+    let mut rng = rand::thread_rng();
+    Coin::new(rng.gen::<[u8; 32]>(), rng.gen::<[u8; 32]>(), 0.0)
 }
 
 pub struct Buffer {
@@ -57,12 +148,25 @@ impl Blockchain {
             wallet,
             state: State::new(),
             validator: Validator::new(),
-            epoch: Epoch::new(),
+            epoch: Epoch::new(EPOCH_DURATION),
+            reward_accumulator: RewardAccumulator::new(EPOCH_REWARD_POOL),
             buffer: Buffer::new(),
             hash_chain: HashChain { hash_chain: vec![] },
             pending_signatures: HashMap::new(),
             last_certificate: None,
+            store: None,
+            coin: new_random_coin(),
+            current_tick: 0,
+            last_tip_progress_tick: 0,
+            pending_signature_started_at: HashMap::new(),
+            bft_rounds: HashMap::new(),
+            bft_pending_blocks: HashMap::new(),
+            checkpointer: None,
+            last_checkpointed_height: 0,
+            anchored_epoch_count: 0,
+            block_queue: BlockQueue::new(),
         };
+        let key_ownership_proof = crate::zkid::prove_key_ownership(&blockchain.wallet);
         let wallet = &mut blockchain.wallet;
         let account = Account {
             address: wallet.get_public_key().to_string(),
@@ -77,7 +181,9 @@ impl Blockchain {
                     account.clone(),
                     100.00,
                     0,
+                    0,
                     TransactionType::STAKE,
+                    Some(key_ownership_proof),
                 )
                 .unwrap(),
             )
@@ -90,15 +196,159 @@ impl Blockchain {
         blockchain
     }
 
-    pub fn select_block_proposer(&self, seed: Seed) -> &Account {
-        select_block_proposer(seed, &self.validator)
+    /// Opens (or creates) the SQLite database at `db_path` and reconstructs
+    /// in-memory state from it if it already holds a chain, so a restarted
+    /// node resumes instead of re-syncing genesis. With an empty/fresh
+    /// database this falls back to the same genesis bootstrap as `new`.
+    pub fn open(wallet: Wallet, db_path: &str) -> Result<Self, String> {
+        let store = ChainStore::open(db_path)?;
+        let persisted_chain = store.load_chain()?;
+
+        if persisted_chain.is_empty() {
+            let mut blockchain = Self::new(wallet);
+            blockchain.store = Some(store);
+            return Ok(blockchain);
+        }
+
+        let state = store.load_state()?;
+        let hash_chain = HashChain {
+            hash_chain: store.load_hash_chain()?,
+        };
+
+        let blockchain = Self {
+            chain: persisted_chain,
+            mempool: Mempool::new(),
+            wallet,
+            state,
+            validator: Validator::new(),
+            epoch: Epoch::new(EPOCH_DURATION),
+            reward_accumulator: RewardAccumulator::new(EPOCH_REWARD_POOL),
+            buffer: Buffer::new(),
+            hash_chain,
+            pending_signatures: HashMap::new(),
+            last_certificate: None,
+            store: Some(store),
+            coin: new_random_coin(),
+            current_tick: 0,
+            last_tip_progress_tick: 0,
+            pending_signature_started_at: HashMap::new(),
+            bft_rounds: HashMap::new(),
+            bft_pending_blocks: HashMap::new(),
+            checkpointer: None,
+            last_checkpointed_height: 0,
+            anchored_epoch_count: 0,
+            block_queue: BlockQueue::new(),
+        };
+
+        Ok(blockchain)
+    }
+
+    /// Chain-spec-driven counterpart to `open`: opens `db_path` guarded by
+    /// `spec.network` (via `ChainStore::open_for_network`, rejecting a
+    /// database created for a different network) and, on a fresh database,
+    /// seeds the ledger and validator set from `spec` (via
+    /// `Genesis::from_spec`) and runs `spec.epoch_duration` instead of the
+    /// hardcoded `EPOCH_DURATION`, so nodes on different networks or specs
+    /// can't accidentally interoperate or silently run mismatched epoch
+    /// lengths. A non-empty existing database still resumes exactly as
+    /// `open` does, just with the spec's network guard and epoch duration
+    /// applied.
+    pub fn open_with_spec(wallet: Wallet, db_path: &str, spec: &ChainSpec) -> Result<Self, String> {
+        let store = ChainStore::open_for_network(db_path, spec.network)?;
+        let persisted_chain = store.load_chain()?;
+
+        if persisted_chain.is_empty() {
+            let (genesis_state, genesis_stakes) = Genesis::from_spec(spec);
+            let mut blockchain = Self::new(wallet);
+            blockchain.epoch = Epoch::new(spec.epoch_duration);
+            for account in &genesis_state.accounts {
+                blockchain.state.add_account(account.clone());
+                blockchain
+                    .state
+                    .balances
+                    .insert(account.clone(), genesis_state.get_balance(account.clone()));
+            }
+            for stake_txn in genesis_stakes {
+                let account = stake_txn.recipient.clone();
+                blockchain
+                    .validator
+                    .add_validator(account, stake_txn)
+                    .map_err(|e| format!("failed to admit a genesis stake: {}", e))?;
+            }
+            blockchain.store = Some(store);
+            return Ok(blockchain);
+        }
+
+        let state = store.load_state()?;
+        let hash_chain = HashChain {
+            hash_chain: store.load_hash_chain()?,
+        };
+
+        let blockchain = Self {
+            chain: persisted_chain,
+            mempool: Mempool::new(),
+            wallet,
+            state,
+            validator: Validator::new(),
+            epoch: Epoch::new(spec.epoch_duration),
+            reward_accumulator: RewardAccumulator::new(EPOCH_REWARD_POOL),
+            buffer: Buffer::new(),
+            hash_chain,
+            pending_signatures: HashMap::new(),
+            last_certificate: None,
+            store: Some(store),
+            coin: new_random_coin(),
+            current_tick: 0,
+            last_tip_progress_tick: 0,
+            pending_signature_started_at: HashMap::new(),
+            bft_rounds: HashMap::new(),
+            bft_pending_blocks: HashMap::new(),
+            checkpointer: None,
+            last_checkpointed_height: 0,
+            anchored_epoch_count: 0,
+            block_queue: BlockQueue::new(),
+        };
+
+        Ok(blockchain)
     }
 
     pub fn new_epoch(&mut self) -> Seed {
         Seed::new_epoch_seed(&self.validator)
     }
 
-    fn handle_transaction(&mut self, transaction: Transaction) {
+    /// Commitment to this node's current coin, published each epoch so
+    /// other validators can later verify its `LeaderProof`s.
+    pub fn coin_commitment(&self) -> [u8; 32] {
+        self.coin.commitment(self.wallet.get_public_key().as_bytes())
+    }
+
+    /// Privately tries to win the leader lottery for the current slot. On a
+    /// win, marks the coin's nullifier as spent and evolves it so it can't
+    /// be replayed, then returns the `LeaderProof` to attach to the
+    /// proposed block. Returns `None` if the coin didn't win this slot.
+    pub fn try_claim_block(&mut self, seed: Seed) -> Option<LeaderProof> {
+        let my_address = Account {
+            address: self.wallet.get_public_key().to_string(),
+        };
+        self.coin.weight = self.validator.state.get_balance(my_address);
+        let total_active_stake = self.validator.total_active_stake();
+        if total_active_stake <= 0.0 {
+            return None;
+        }
+
+        let proof = self.coin.try_claim_slot(
+            seed.get_seed(),
+            self.epoch.timestamp as usize,
+            total_active_stake,
+            ACTIVE_SLOT_COEFFICIENT,
+            self.wallet.get_public_key().as_bytes(),
+        )?;
+        self.validator.mark_nullifier_used(proof.nullifier);
+        self.coin = self.coin.evolve();
+        Some(proof)
+    }
+
+    fn handle_transaction(&mut self, transaction: VerifiedTransaction) {
         if transaction.txn_type == TransactionType::TRANSACTION {
             self.execute_transaction(transaction);
         } else if transaction.txn_type == TransactionType::STAKE {
@@ -106,29 +356,82 @@ impl Blockchain {
         }
     }
 
-    fn execute_transaction(&mut self, transaction: Transaction) {
-        if transaction.verify().unwrap() {
-            self.state.transfer(
-                transaction.sender.clone(),
-                transaction.recipient.clone(),
-                transaction.amount,
-            );
+    fn execute_transaction(&mut self, transaction: VerifiedTransaction) {
+        if let Err(e) = self.state.apply_transaction(&transaction) {
+            error!("Failed to apply transaction {:?}: {}", transaction.hash, e);
         }
     }
 
-    fn handle_stake(&mut self, transaction: Transaction) {
-        if transaction.verify().unwrap() {
-            // Add to buffer
-            self.buffer.accounts.push(transaction.sender.clone());
-            self.buffer.txns.push(transaction.clone());
-        }
+    fn handle_stake(&mut self, transaction: VerifiedTransaction) {
+        self.buffer.accounts.push(transaction.sender.clone());
+        self.buffer.txns.push(transaction.into_inner());
     }
 
     pub fn end_of_epoch(&mut self) {
         self.validator
             .apply_buffer(self.buffer.accounts.clone(), self.buffer.txns.clone());
+        self.validator.reset_nullifiers();
         self.buffer.reset();
+        if self.epoch.is_end_of_epoch() {
+            self.epoch.settle_rewards(&mut self.state, &self.reward_accumulator);
+            self.reward_accumulator.reset();
+        }
         self.epoch.reset();
+        self.anchor_checkpoint();
+    }
+
+    /// Installs the client `end_of_epoch` submits L1 checkpoints through.
+    /// Leaving this unset (the default) keeps checkpoint anchoring a no-op,
+    /// so nodes that don't configure L1 settlement are unaffected.
+    pub fn set_l1_checkpointer(&mut self, checkpointer: Box<dyn L1Checkpointer + Send>) {
+        self.checkpointer = Some(checkpointer);
+    }
+
+    /// The most recently L1-anchored checkpoint this node's checkpointer
+    /// knows about, if any has been submitted yet.
+    pub fn latest_finalized_checkpoint(&self) -> Option<Checkpoint> {
+        self.checkpointer
+            .as_ref()
+            .and_then(|checkpointer| checkpointer.latest_finalized_checkpoint())
+    }
+
+    /// If an `L1Checkpointer` is configured, anchors every block added since
+    /// the last successful checkpoint. A no-op when `self.checkpointer` is
+    /// `None` or there are no new blocks to cover. The proof artifact slot
+    /// is left empty here: the `DilithiumCore` circuit's witness pipeline
+    /// (`src/bin/circuits.rs`) runs out-of-process and isn't yet wired to
+    /// hand its output to the chain at this call site.
+    fn anchor_checkpoint(&mut self) {
+        let Some(checkpointer) = self.checkpointer.as_mut() else {
+            return;
+        };
+        if self.last_checkpointed_height >= self.chain.len() {
+            return;
+        }
+        let block_hashes: Vec<[u8; 32]> = self.chain[self.last_checkpointed_height..]
+            .iter()
+            .map(|block| block.hash)
+            .collect();
+        let state_root = match compute_epoch_checkpoint_root(&block_hashes, &[]) {
+            Ok(root) => root,
+            Err(e) => {
+                error!("Failed to compute checkpoint root: {}", e);
+                return;
+            }
+        };
+        let checkpoint = Checkpoint {
+            epoch: self.anchored_epoch_count,
+            state_root,
+            proof_bytes: vec![],
+        };
+        match checkpointer.submit_checkpoint(checkpoint) {
+            Ok(tx_hash) => {
+                info!("Anchored checkpoint to L1, tx {}", tx_hash);
+                self.last_checkpointed_height = self.chain.len();
+                self.anchored_epoch_count += 1;
+            }
+            Err(e) => error!("Failed to submit L1 checkpoint: {}", e),
+        }
     }
     // TODO
     // fn handle_unstake(&mut self, transaction: Transaction) {}
@@ -149,6 +452,7 @@ impl Blockchain {
         proposer_address: Account,
         txns: Vec<Transaction>,
         seed: Seed,
+        leader_proof: Option<LeaderProof>,
     ) -> Block {
         // Check if the last certificate corresponds to the immediate previous block.
         let cert_to_attach = if let Some((cert_block_id, cert)) = self.last_certificate.take() {
@@ -168,7 +472,14 @@ impl Blockchain {
             None
         };
 
-        self.propose_block_with_certificate(proposer_hash, proposer_address, txns, seed, cert_to_attach)
+        self.propose_block_with_certificate(
+            proposer_hash,
+            proposer_address,
+            txns,
+            seed,
+            cert_to_attach,
+            leader_proof,
+        )
     }
 
     pub fn propose_block_with_certificate(
@@ -178,6 +489,7 @@ impl Blockchain {
         txns: Vec<Transaction>,
         seed: Seed,
         certificate: Option<Certificate>,
+        leader_proof: Option<LeaderProof>,
     ) -> Block {
         let block = if self.chain.is_empty() {
             Block::new(
@@ -189,6 +501,7 @@ impl Blockchain {
                 proposer_hash,
                 seed,
                 certificate,
+                leader_proof,
             )
             .unwrap()
         } else {
@@ -202,6 +515,7 @@ impl Blockchain {
                 proposer_hash,
                 seed,
                 certificate,
+                leader_proof,
             )
             .unwrap()
         };
@@ -244,24 +558,360 @@ impl Blockchain {
         true
     }
 
+    /// Full incoming-block validation gate, run before a gossiped block is
+    /// ever executed (borrows Alfis's "block adding check"). Unlike the
+    /// lighter `verify_block`, this also pins the block id to the exact
+    /// next height, checks the declared proposer actually won the lottery
+    /// for `seed`, and requires every contained transaction to verify and
+    /// (for ordinary transfers) already be known to the mempool.
+    pub fn validate_block(&self, block: &Block, seed: Seed) -> Result<(), String> {
+        let expected_id = if self.chain.is_empty() {
+            1
+        } else {
+            self.get_latest_block_id() as usize + 1
+        };
+        if block.id != expected_id {
+            return Err(format!(
+                "block id {} does not extend the chain tip (expected {})",
+                block.id, expected_id
+            ));
+        }
+
+        // The genesis block has no previous proposer commitment to check
+        // against, mirroring `verify_block`'s early return for id == 1.
+        if block.id == 1 {
+            return self.validate_transactions(block);
+        }
+
+        let previous_block = self
+            .chain
+            .last()
+            .ok_or("no previous block to validate against")?;
+        if block.previous_hash != previous_block.hash {
+            return Err("previous_hash does not match the current tip".to_string());
+        }
+
+        let proposer_commitment = self
+            .validator
+            .get_validator_commitment(block.proposer_address.clone());
+        if !verify_hash_chain_index(
+            proposer_commitment.hash_chain_index.clone(),
+            self.epoch.timestamp,
+            block.proposer_hash.clone(),
+        ) {
+            return Err("hashchain index does not reveal the expected position for this epoch".to_string());
+        }
+
+        let leader_proof = block
+            .leader_proof
+            .as_ref()
+            .ok_or("block is missing a LeaderProof for the private proposer lottery")?;
+
+        if leader_proof.epoch_nonce != seed.get_seed() {
+            return Err("LeaderProof epoch_nonce does not match this slot's seed".to_string());
+        }
+        if self.validator.is_nullifier_used(&leader_proof.nullifier) {
+            return Err("LeaderProof nullifier has already been spent this epoch".to_string());
+        }
+
+        // The commitment and registered weight are both taken from this
+        // node's own view of the validator set, never from the proof
+        // itself, so a proposer can't inflate its declared stake to clear
+        // an easier threshold.
+        let proposer_weight = self.validator.state.get_balance(block.proposer_address.clone());
+        let total_active_stake = self.validator.total_active_stake();
+        if !leader_proof.verify(
+            block.proposer_address.address.as_bytes(),
+            proposer_weight,
+            total_active_stake,
+            ACTIVE_SLOT_COEFFICIENT,
+        ) {
+            return Err("LeaderProof does not prove a lottery win for the declared stake".to_string());
+        }
+
+        self.validate_transactions(block)
+    }
+
+    fn validate_transactions(&self, block: &Block) -> Result<(), String> {
+        for txn in &block.txn {
+            if !txn
+                .verify()
+                .map_err(|e| format!("transaction verification error: {}", e))?
+            {
+                return Err(format!("transaction {:?} failed signature verification", txn.hash));
+            }
+
+            if txn.txn_type == TransactionType::TRANSACTION && !self.mempool.txn_exists(&txn.hash) {
+                return Err(format!(
+                    "transaction {:?} is not known to the mempool",
+                    txn.hash
+                ));
+            }
+
+            if matches!(txn.txn_type, TransactionType::COINBASE | TransactionType::ValidatorReward)
+                && txn.amount > STAKING_AMOUNT
+            {
+                return Err(format!(
+                    "reward amount {} exceeds the maximum allowed per block",
+                    txn.amount
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares a block proposed against this chain's tip and decides
+    /// whether it should be accepted outright, trigger a reorg, or be
+    /// rejected, applying a deterministic longest-chain rule (ties at the
+    /// same height broken by the lowest block hash) so every honest node
+    /// converges on the same fork.
+    pub fn fork_choice(&self, candidate: &Block) -> ForkChoice {
+        match self.chain.last() {
+            None => ForkChoice::Accept,
+            Some(tip) if candidate.id == tip.id + 1 => ForkChoice::Accept,
+            Some(tip) if candidate.id == tip.id => {
+                if candidate.hash < tip.hash {
+                    ForkChoice::Reorg
+                } else {
+                    ForkChoice::Reject
+                }
+            }
+            _ => ForkChoice::Reject,
+        }
+    }
+
+    /// Rolls the chain back to just before `height` and replays the
+    /// surviving blocks' transactions to reconstruct account state, then
+    /// executes `replacement` as the new tip at that height.
+    pub fn reorg_to(&mut self, height: usize, replacement: Block) {
+        self.chain.retain(|b| b.id < height);
+        self.state = State::new();
+        for block in self.chain.clone() {
+            for txn in block.txn {
+                if let Ok(verified) = txn.verify_into() {
+                    self.handle_transaction(verified);
+                }
+            }
+        }
+        self.execute_block(replacement);
+    }
+
+    /// Header-first sync: validates a peer's full `ChainResponse.blocks`
+    /// before touching any local state, then adopts it only if it is
+    /// strictly longer than the local chain. Validation walks the blocks in
+    /// id order checking `id` continuity and `previous_hash`/`hash`
+    /// linkage first (the "header" pass, cheap and stateless), then checks
+    /// each new block's `proposer_hash` against its proposer's registered
+    /// hashchain commitment (the check `verify_block` makes) before it's
+    /// executed. Returns `Ok(true)` if a (possibly reorg'd) chain was
+    /// adopted, `Ok(false)` if the response wasn't longer than what's
+    /// already local, and `Err` on the first header or hashchain failure —
+    /// with nothing mutated in that case.
+    pub fn handle_chain_response(&mut self, response_blocks: Vec<Block>) -> Result<bool, String> {
+        let mut incoming = response_blocks;
+        incoming.sort_by_key(|b| b.id);
+
+        // Headers-first pass: derive each block's lightweight `BlockHeader`
+        // and chain them via `verify_links` rather than full bodies, so a
+        // bad id/previous_hash linkage is caught before any Merkle/
+        // certificate/transaction work below touches the (possibly much
+        // larger) block bodies.
+        for window in incoming.windows(2) {
+            let (prev, next) = (&window[0], &window[1]);
+            if let Err(e) = next.header().verify_links(&prev.header()) {
+                return Err(format!("chain response failed header verification: {}", e));
+            }
+        }
+
+        if incoming.len() <= self.chain.len() {
+            return Ok(false);
+        }
+
+        // Walk both chains from genesis to find the last height they agree
+        // on; blocks past that point are what we'd need to adopt.
+        let mut common_ancestor_height = 0usize;
+        for (i, block) in incoming.iter().enumerate() {
+            if block.id != i + 1 {
+                return Err(format!(
+                    "chain response must start at block 1 and be contiguous, got id {} at position {}",
+                    block.id, i
+                ));
+            }
+            match self.chain.get(i) {
+                Some(local) if local.hash == block.hash => common_ancestor_height = block.id,
+                _ => break,
+            }
+        }
+
+        let new_blocks: Vec<Block> = incoming
+            .into_iter()
+            .skip(common_ancestor_height)
+            .collect();
+        if new_blocks.is_empty() {
+            return Ok(false);
+        }
+
+        // Stateless header pass is done; now check each new block's
+        // `proposer_hash` against its proposer's hashchain commitment before
+        // touching state. This is `verify_block`'s check, made directly:
+        // `verify_block` itself reads the local tip, which a freshly joined
+        // node doesn't have, and the header pass above already checked each
+        // block's `previous_hash` against its incoming predecessor. Nothing
+        // is rolled back or replayed yet, so a failure here leaves the local
+        // chain untouched.
+        for block in &new_blocks {
+            if block.id == 1 {
+                continue;
+            }
+            let commitment = self
+                .validator
+                .get_validator_commitment(block.proposer_address.clone());
+            if !verify_hash_chain_index(
+                commitment.hash_chain_index.clone(),
+                self.epoch.timestamp,
+                block.proposer_hash.clone(),
+            ) {
+                return Err(format!("block {} failed hashchain verification", block.id));
+            }
+        }
+
+        // Only now roll back to the common ancestor (same replay idiom as
+        // `reorg_to`) and execute the validated new blocks in order.
+        self.chain.truncate(common_ancestor_height);
+        self.state = State::new();
+        for block in self.chain.clone() {
+            for txn in block.txn {
+                if let Ok(verified) = txn.verify_into() {
+                    self.handle_transaction(verified);
+                }
+            }
+        }
+        for block in new_blocks {
+            self.execute_block(block);
+        }
+
+        Ok(true)
+    }
+
+    /// Builds the `BlockVerificationContext` `self.block_queue` needs to
+    /// stage-1-verify `block` against this node's own live chain/validator
+    /// state — the same state `party_tree` already derives certificate
+    /// verification from, reused here instead of duplicated.
+    fn block_verification_context(&self, block: &Block) -> BlockVerificationContext {
+        let expected_previous_hash = self.chain.last().map(|b| b.hash).unwrap_or([0u8; 32]);
+        let proposer_hash_chain_index = self
+            .validator
+            .hash_chain_com
+            .get(&block.proposer_address.address)
+            .map(|com| com.hash_chain_index.clone())
+            .unwrap_or_default();
+        let (_, party_tree_root, total_weight) = self.party_tree();
+        BlockVerificationContext {
+            expected_previous_hash,
+            proposer_hash_chain_index,
+            epoch_timestamp: self.epoch.timestamp,
+            proven_weight: finality_threshold(total_weight),
+            party_tree_root,
+        }
+    }
+
+    /// Receive-path entry point for a gossiped block: pushes it through
+    /// `self.block_queue`'s stage-1 verification (cheap Merkle/linkage/
+    /// certificate checks) before handing a passing block to
+    /// `handle_incoming_block` for fork-choice/validation/execution. A
+    /// duplicate or already-known block hash is dropped by `push` before
+    /// any of that work runs. Since this codebase's p2p receive path is
+    /// single-threaded rather than a separate worker pool, `push`,
+    /// `process_next`, and `pop_verified` run back-to-back here instead of
+    /// across threads — `block_queue` still earns its keep as a real,
+    /// hash-deduplicating pre-filter ahead of the expensive checks
+    /// `handle_incoming_block` already contains.
+    pub fn receive_block(&mut self, block: Block, seed: Seed) -> Result<bool, String> {
+        if !self.block_queue.push(block.clone()) {
+            return Ok(false);
+        }
+        let ctx = self.block_verification_context(&block);
+        match self.block_queue.process_next(&ctx) {
+            Some(true) => {
+                let verified = self.block_queue.pop_verified();
+                self.handle_incoming_block(verified, seed)
+            }
+            Some(false) => Err(self
+                .block_queue
+                .rejection_reason(&block.hash)
+                .unwrap_or_else(|| format!("block {} failed stage-1 verification", block.id))),
+            None => Ok(false),
+        }
+    }
+
+    /// Entry point for a gossiped block: applies the fork-choice rule
+    /// against the current tip, validates the winning candidate, and
+    /// executes it (rolling back and replaying first, on a reorg). Returns
+    /// `Ok(true)` if the block was applied, `Ok(false)` if it lost the
+    /// fork-choice comparison, and `Err` if it failed validation outright.
+    pub fn handle_incoming_block(&mut self, block: Block, seed: Seed) -> Result<bool, String> {
+        match self.fork_choice(&block) {
+            ForkChoice::Reject => Ok(false),
+            ForkChoice::Accept => {
+                self.validate_block(&block, seed)?;
+                self.spend_leader_proof_nullifier(&block);
+                self.execute_block(block);
+                Ok(true)
+            }
+            ForkChoice::Reorg => {
+                self.validate_block(&block, seed)?;
+                self.spend_leader_proof_nullifier(&block);
+                let height = block.id;
+                self.reorg_to(height, block);
+                Ok(true)
+            }
+        }
+    }
+
+    fn spend_leader_proof_nullifier(&mut self, block: &Block) {
+        if let Some(proof) = &block.leader_proof {
+            self.validator.mark_nullifier_used(proof.nullifier);
+        }
+    }
+
     pub fn execute_block(&mut self, block: Block) {
+        let proposer_stake = self.validator.state.get_balance(block.proposer_address.clone());
+        self.reward_accumulator.record_block(block.proposer_address.clone(), proposer_stake);
+
         // if txns, do nothing
         if block.txn.is_empty() {
             info!("Block has no transactions");
             self.chain.push(block.clone());
+            self.persist_tip(&block);
+            self.last_tip_progress_tick = self.current_tick;
             return;
         }
         for txn in block.txn.clone() {
-            if txn.verify().unwrap() {
-                self.handle_transaction(txn);
+            let hash = txn.hash;
+            match txn.verify_into() {
+                Ok(verified) => self.handle_transaction(verified),
+                Err(e) => error!("skipping transaction {:?} in block {}: {}", hash, block.id, e),
             }
         }
         self.chain.push(block.clone());
+        self.persist_tip(&block);
+        self.last_tip_progress_tick = self.current_tick;
         for txn in block.txn {
             self.mempool.delete_transaction(txn);
         }
     }
 
+    /// Writes the newly executed block and the resulting account state
+    /// through to the backing `ChainStore`, if one is attached. A node
+    /// running without persistence (`Blockchain::new`) simply skips this.
+    fn persist_tip(&mut self, block: &Block) {
+        if let Some(store) = self.store.as_mut() {
+            if let Err(e) = store.persist_block(block, &self.state) {
+                error!("Failed to persist block {} to storage: {}", block.id, e);
+            }
+        }
+    }
+
     #[allow(dead_code)]
     pub fn get_validators(&self) -> &Validator {
         &self.validator
@@ -289,86 +939,335 @@ impl Blockchain {
         );
     }
 
+    /// Current participants and their weights over the validator Merkle
+    /// party tree, plus the total weight they carry. Shared by certificate
+    /// construction and verification so both sides agree on the tree.
+    /// Builds the certificate signer set from the top `MAX_VALIDATOR_SLOTS`
+    /// accounts by stake, ties broken by address so every node derives the
+    /// same active set from the same ledger. Bounding the set here (rather
+    /// than admitting every staked account ever seen) keeps certificate
+    /// size and signature-collection cost constant as the validator
+    /// population grows.
+    fn party_tree(&self) -> (Vec<Participant>, Vec<u8>, u64) {
+        let mut active: Vec<&Account> = self.validator.state.accounts.iter().collect();
+        active.sort_by(|a, b| {
+            let weight_a = self.validator.state.balances.get(*a).cloned().unwrap_or(0.0);
+            let weight_b = self.validator.state.balances.get(*b).cloned().unwrap_or(0.0);
+            weight_b
+                .partial_cmp(&weight_a)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        active.truncate(MAX_VALIDATOR_SLOTS);
+
+        let mut total_weight = 0u64;
+        let participants: Vec<Participant> = active
+            .iter()
+            .map(|a| {
+                let weight = self.validator.state.balances.get(*a).cloned().unwrap_or(0.0) as u64;
+                total_weight += weight;
+                Participant {
+                    public_key: a.address.clone(),
+                    weight,
+                    key_schedule_root: None,
+                    weight_commitment: None,
+                }
+            })
+            .collect();
+
+        let mut tree = MerkleTreeBuilder::new();
+        tree.build(&participants).expect("Failed to build Merkle tree");
+        let party_tree_root = tree.root();
+        (participants, party_tree_root, total_weight)
+    }
+
     pub fn collect_block_signature(&mut self, block_sig: BlockSignature) {
-        let expected = self.validator.state.accounts.len();
-        let (should_build, block_id, block_hash) = {
+        let (_, _, total_weight) = self.party_tree();
+        let proven_weight = finality_threshold(total_weight);
+
+        self.pending_signature_started_at
+            .entry(block_sig.block_id)
+            .or_insert(self.current_tick);
+
+        let (block_id, signed_weight) = {
             let sigs = self
                 .pending_signatures
                 .entry(block_sig.block_id)
-                .or_insert(vec![]);
+                .or_insert_with(Vec::new);
             let sender_address = block_sig.sender.address.clone();
             if !sigs.iter().any(|s| s.sender.address == sender_address) {
                 sigs.push(block_sig);
             }
-            let should_build = sigs.len() >= expected;
             let block_id = sigs[0].block_id;
-            let block_hash = sigs[0].block_hash.clone();
-            (should_build, block_id, block_hash)
+            let signed_weight: u64 = sigs
+                .iter()
+                .filter_map(|s| {
+                    let account = Account {
+                        address: s.sender.address.clone(),
+                    };
+                    self.validator.state.balances.get(&account).cloned()
+                })
+                .map(|w| w as u64)
+                .sum();
+            (block_id, signed_weight)
         };
 
-        if should_build {
-            let mut params = Params {
-                msg: block_hash.as_bytes().to_vec(),
-                proven_weight: 0,
-                security_param: 128,
-            };
-            // Compute proven_weight while building participants.
-            let participants: Vec<Participant> = self
-                .validator
-                .state
-                .accounts
+        if proven_weight > 0 && signed_weight >= proven_weight {
+            if let Err(e) = self.finalize_block(block_id) {
+                error!("Could not finalize block {}: {}", block_id, e);
+            }
+        }
+    }
+
+    /// Builds a compact finality certificate for `block_id` from the
+    /// signatures collected so far, proving that validators holding at
+    /// least 2/3 of total staked weight signed the block hash. Fails if
+    /// not enough weight has signed yet.
+    pub fn finalize_block(&mut self, block_id: usize) -> Result<Certificate, String> {
+        let collected_sigs = self
+            .pending_signatures
+            .remove(&block_id)
+            .ok_or_else(|| format!("no signatures collected for block {}", block_id))?;
+        self.pending_signature_started_at.remove(&block_id);
+        let block_hash = collected_sigs
+            .first()
+            .map(|s| s.block_hash.clone())
+            .ok_or_else(|| format!("no signatures collected for block {}", block_id))?;
+
+        let (participants, party_tree_root, total_weight) = self.party_tree();
+        let params = Params {
+            msg: block_hash.as_bytes().to_vec(),
+            proven_weight: finality_threshold(total_weight),
+            security_param: 128,
+            epoch: 0,
+        };
+
+        let mut builder = CertBuilder::new(params, participants.clone(), party_tree_root);
+        for sig in collected_sigs {
+            if let Some(idx) = participants
                 .iter()
-                .map(|a| {
-                    let weight =
-                        self.validator.state.balances.get(a).cloned().unwrap_or(0.0) as u64;
-                    params.proven_weight += weight;
-                    Participant {
-                        public_key: a.address.clone(),
-                        weight,
-                    }
-                })
-                .collect();
+                .position(|p| p.public_key == sig.sender.address)
+            {
+                let fixed_sig: [u8; 2420] = sig
+                    .signature
+                    .try_into()
+                    .map_err(|_| "signature length does not match expected size".to_string())?;
+                let _ = builder.add_signature(idx, fixed_sig);
+            }
+        }
 
-            let collected_sigs = self
-                .pending_signatures
-                .remove(&block_id)
-                .unwrap_or_else(Vec::new);
-            // Build the party tree from participants as in the test.
-            let mut tree = MerkleTreeBuilder::new();
-            tree.build(&participants)
-                .expect("Failed to build Merkle tree");
-            let party_tree_root = tree.root();
-            let mut builder = CertBuilder::new(params, participants.clone(), party_tree_root);
-            // For each collected block signature, add the signature to the builder.
-            for sig in collected_sigs {
-                if let Some(idx) = participants
-                    .iter()
-                    .position(|p| p.public_key == sig.sender.address)
-                {
-                    let fixed_sig: [u8; 2420] = sig
-                        .signature
-                        .try_into()
-                        .expect("Signature length does not match expected size");
-                    let _ = builder.add_signature(idx, fixed_sig);
-                }
+        let certificate = builder.build()?;
+        info!(
+            "🔐 Finalized block {} with a certificate proving >= 2/3 stake: {:?}",
+            block_id,
+            certificate.proof_size()
+        );
+        self.last_certificate = Some((block_id, certificate.clone()));
+        Ok(certificate)
+    }
+
+    /// A block is final (irreversible by fork-choice) once the certificate
+    /// attached to its successor verifies against the current validator
+    /// party tree, proving that at least 2/3 of total staked weight signed
+    /// its hash.
+    pub fn is_block_final(&self, block_id: usize) -> bool {
+        let Some(finalized_block) = self.chain.iter().find(|b| b.id == block_id) else {
+            return false;
+        };
+        let Some(certifying_block) = self.chain.iter().find(|b| b.id == block_id + 1) else {
+            return false;
+        };
+        let Some(certificate) = &certifying_block.certificate else {
+            return false;
+        };
+
+        let (_, party_tree_root, total_weight) = self.party_tree();
+        let params = Params {
+            msg: hex::encode(finalized_block.hash).into_bytes(),
+            proven_weight: finality_threshold(total_weight),
+            security_param: 128,
+            epoch: 0,
+        };
+        certificate
+            .verify(&params, &party_tree_root)
+            .unwrap_or(false)
+    }
+
+    /// Advances this node's liveness clock by one tick, representing one
+    /// elapsed `BLOCK_INTERVAL` in the driving event loop. `check_liveness`
+    /// measures stalls in ticks rather than wall-clock time so tests can
+    /// drive the watchdog deterministically instead of sleeping.
+    pub fn tick(&mut self) {
+        self.current_tick += 1;
+    }
+
+    /// How many ticks have elapsed since `self.chain` last grew.
+    pub fn tip_age_ticks(&self) -> u64 {
+        self.current_tick.saturating_sub(self.last_tip_progress_tick)
+    }
+
+    /// Tip age in ticks, plus the height the tip is stuck at if it's stalled
+    /// at all (`None` once it's freshly advanced). Exposed so node operators
+    /// and tests can observe a stall directly instead of just timing out.
+    pub fn liveness_status(&self) -> (u64, Option<usize>) {
+        let tip_age = self.tip_age_ticks();
+        let stuck_at = if tip_age > 0 {
+            self.chain.last().map(|b| b.id)
+        } else {
+            None
+        };
+        (tip_age, stuck_at)
+    }
+
+    /// Liveness watchdog for finality: fires if either the chain tip hasn't
+    /// advanced for `max_tip_stall_intervals` ticks, or the next height's
+    /// pending-signature set has been open for `max_signature_wait_intervals`
+    /// ticks without reaching the signed-weight threshold. Either way, the
+    /// stuck height is the one past the tip, so it was never pushed onto
+    /// `self.chain`, and clearing its stale bookkeeping here is enough to
+    /// let it be re-proposed from scratch on the next slot.
+    pub fn check_liveness(
+        &mut self,
+        max_tip_stall_intervals: u64,
+        max_signature_wait_intervals: u64,
+    ) -> Option<StallEvent> {
+        let next_height = self.chain.last().map(|b| b.id + 1).unwrap_or(1);
+
+        if self.tip_age_ticks() >= max_tip_stall_intervals {
+            self.clear_stalled_height(next_height);
+            return Some(StallEvent {
+                height: next_height,
+                reason: StallReason::TipNotAdvancing,
+            });
+        }
+
+        if let Some(&started_at) = self.pending_signature_started_at.get(&next_height) {
+            if self.current_tick.saturating_sub(started_at) >= max_signature_wait_intervals {
+                self.clear_stalled_height(next_height);
+                return Some(StallEvent {
+                    height: next_height,
+                    reason: StallReason::SignatureDeadlineExceeded,
+                });
             }
-            let certificate = match builder.build() {
-                Ok(cert) => cert,
-                Err(e) => {
-                    error!("Error building certificate: {}", e);
-                    return;
-                }
-            };
+        }
 
-            info!("ðŸ” Certificate computed for block {}: {:?}", block_id, certificate.proof_size());
-            self.last_certificate = Some((block_id, certificate));
+        None
+    }
+
+    /// Drops `height`'s in-flight certificate-collection state and resets
+    /// the tip-stall clock, so the watchdog gives the re-proposed height a
+    /// fresh window before firing again.
+    fn clear_stalled_height(&mut self, height: usize) {
+        self.pending_signatures.remove(&height);
+        self.pending_signature_started_at.remove(&height);
+        if matches!(&self.last_certificate, Some((id, _)) if *id == height) {
+            self.last_certificate = None;
         }
+        self.last_tip_progress_tick = self.current_tick;
+    }
+
+    /// Registers `block` as the candidate this node will execute if its
+    /// height's `RoundState` reaches a Precommit quorum on its hash. Votes
+    /// may arrive (and even reach quorum) before or after this call; either
+    /// order works, since `record_bft_vote` re-checks `bft_pending_blocks`
+    /// every time it sees a fresh `Committed` outcome.
+    pub fn register_bft_proposal(&mut self, block: Block) {
+        self.bft_pending_blocks.insert(block.id, block);
+    }
+
+    /// Feeds one gossiped vote into this height's round-voting tally and,
+    /// on a Precommit quorum, executes the matching pending block (if one
+    /// was registered via `register_bft_proposal`) right away rather than
+    /// waiting on the eager `handle_incoming_block` path. Returns the raw
+    /// `VoteOutcome` so callers (e.g. `p2p::process_message`) can log or
+    /// react to a fresh Prevote lock as well.
+    ///
+    /// Re-verifies `vote.signature` before touching any tally — without
+    /// this, `voter_weight` below would be looked up from `vote.sender`, an
+    /// entirely attacker-controlled field, letting any peer gossip a vote
+    /// "from" any staked address and push a height straight to quorum. An
+    /// unverifiable vote is treated as if it were never cast.
+    pub fn record_bft_vote(&mut self, vote: &VoteMessage) -> VoteOutcome {
+        if !vote.verify() {
+            return VoteOutcome::Pending;
+        }
+
+        let (_, _, total_weight) = self.party_tree();
+        let voter_weight = self
+            .validator
+            .state
+            .balances
+            .get(&vote.sender)
+            .cloned()
+            .unwrap_or(0.0) as u64;
+
+        let outcome = self
+            .bft_rounds
+            .entry(vote.height)
+            .or_insert_with(RoundState::new)
+            .record_vote(
+                vote.round,
+                vote.step,
+                vote.block_hash.clone(),
+                vote.sender.address.clone(),
+                voter_weight,
+                total_weight,
+            );
+
+        if let VoteOutcome::Committed { hash } = &outcome {
+            if let Some(block) = self.bft_pending_blocks.get(&vote.height) {
+                if hex::encode(block.hash) == *hash {
+                    let block = self.bft_pending_blocks.remove(&vote.height).unwrap();
+                    self.execute_block(block);
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// Moves `height`'s round-voting state to the next round after a
+    /// timeout with no quorum, returning the new round number so the caller
+    /// knows which round to stamp onto its next `VoteMessage`.
+    pub fn advance_bft_round(&mut self, height: usize) -> u64 {
+        let round_state = self.bft_rounds.entry(height).or_insert_with(RoundState::new);
+        round_state.advance_round();
+        round_state.round
+    }
+
+    /// The round `height`'s voting is currently on, or round 0 if no votes
+    /// have been recorded for it yet.
+    pub fn bft_round(&self, height: usize) -> u64 {
+        self.bft_rounds.get(&height).map(|r| r.round).unwrap_or(0)
+    }
+
+    /// The hash this node is locked onto for `height`, if a Prevote quorum
+    /// has already formed there.
+    pub fn bft_locked_hash(&self, height: usize) -> Option<String> {
+        self.bft_rounds
+            .get(&height)
+            .and_then(|r| r.locked_hash())
+            .map(str::to_string)
+    }
+
+    /// The validator this node's own view of the weighted-hash lottery
+    /// selects to propose `height`'s current round, per
+    /// `utils::select_round_proposer`.
+    pub fn round_proposer(&self, seed: Seed, height: usize) -> Option<Account> {
+        select_round_proposer(seed, self.bft_round(height), &self.validator)
     }
 }
 
+/// Algorand-style 2/3 majority threshold (rounded down): a certificate is
+/// only valid once signatures cover at least this much of `total_weight`.
+fn finality_threshold(total_weight: u64) -> u64 {
+    (total_weight * 2) / 3
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::consensus::VoteStep;
 
     fn setup_blockchain() -> Blockchain {
         let wallet = Wallet::new().unwrap();
@@ -376,67 +1275,684 @@ mod tests {
     }
 
     #[test]
-    fn test_select_block_proposer() {
+    fn test_open_restores_chain_and_state_from_disk() {
+        let db_path = format!(
+            "/tmp/niropok_test_{}.db",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        );
+
+        {
+            let wallet = Wallet::new().unwrap();
+            let mut blockchain =
+                Blockchain::open(wallet, &db_path).expect("failed to open fresh blockchain storage");
+            let proposer_address = Account {
+                address: blockchain.wallet.get_public_key(),
+            };
+            let block = blockchain.propose_block(
+                "proposer-hash".to_string(),
+                proposer_address,
+                vec![],
+                Seed { seed: [0u8; 32] },
+                None,
+            );
+            blockchain.execute_block(block);
+            assert_eq!(blockchain.chain.len(), 1);
+        }
+
+        let wallet = Wallet::new().unwrap();
+        let reopened =
+            Blockchain::open(wallet, &db_path).expect("failed to reopen blockchain storage");
+        assert_eq!(reopened.chain.len(), 1, "restarted node should replay the persisted block");
+
+        std::fs::remove_file(&db_path).ok();
+    }
+
+    #[test]
+    fn test_validate_block_accepts_genesis_block() {
+        let blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let genesis_block = Block::new(
+            1,
+            [0u8; 32],
+            0,
+            vec![],
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(blockchain
+            .validate_block(&genesis_block, Seed { seed: [0u8; 32] })
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_block_rejects_wrong_height() {
+        let blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let skipped_block = Block::new(
+            2,
+            [0u8; 32],
+            0,
+            vec![],
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(blockchain
+            .validate_block(&skipped_block, Seed { seed: [0u8; 32] })
+            .is_err());
+    }
+
+    #[test]
+    fn test_fork_choice_accepts_next_height_and_rejects_worse_fork() {
         let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let tip = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address.clone(),
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+        blockchain.execute_block(tip.clone());
+
+        let next = Block::new(
+            2,
+            tip.hash,
+            1,
+            vec![],
+            proposer_address.clone(),
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(blockchain.fork_choice(&next), ForkChoice::Accept);
+
+        // A competing block at the same height with a strictly greater hash
+        // loses the deterministic tie-break.
+        let mut worse_fork = tip.clone();
+        worse_fork.hash = [0xffu8; 32];
+        assert_eq!(blockchain.fork_choice(&worse_fork), ForkChoice::Reject);
+    }
 
-        let mut wallet1 = Wallet::new().unwrap();
-        let validator1 = Account {
-            address: wallet1.get_public_key(),
+    #[test]
+    fn test_finalize_block_and_verify_on_successor() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
         };
-        let mut wallet2 = Wallet::new().unwrap();
-        let validator2 = Account {
-            address: wallet2.get_public_key(),
+
+        let first = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address.clone(),
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+        blockchain.execute_block(first.clone());
+        assert!(
+            blockchain.last_certificate.is_some(),
+            "the sole validator's self-signature already clears the 2/3 threshold"
+        );
+
+        let second = blockchain.propose_block(
+            "proposer-hash-2".to_string(),
+            proposer_address,
+            vec![],
+            Seed { seed: [1u8; 32] },
+            None,
+        );
+        blockchain.execute_block(second);
+
+        assert!(
+            blockchain.is_block_final(first.id),
+            "block 1 should be final once its successor carries a verifying certificate"
+        );
+        assert!(!blockchain.is_block_final(999), "a block with no successor can't be final yet");
+    }
+
+    #[test]
+    fn test_collect_block_signature_finalizes_on_quorum_not_unanimity() {
+        let mut blockchain = setup_blockchain();
+
+        // Three more validators, each staking the same weight as the
+        // genesis proposer, so the four of them carry equal weight.
+        let extra_wallets: Vec<Wallet> = (0..3).map(|_| Wallet::new().unwrap()).collect();
+        for wallet in &extra_wallets {
+            let account = Account {
+                address: wallet.get_public_key(),
+            };
+            let mut signer = Wallet::new().unwrap();
+            let key_ownership_proof = crate::zkid::prove_key_ownership(wallet);
+            let stake_txn = Transaction::new(
+                &mut signer,
+                account.clone(),
+                account.clone(),
+                100.00,
+                0,
+                0,
+                TransactionType::STAKE,
+                Some(key_ownership_proof),
+            )
+            .unwrap();
+            blockchain
+                .validator
+                .add_validator(account, stake_txn)
+                .unwrap();
+        }
+
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address,
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+        assert!(
+            blockchain.last_certificate.is_none(),
+            "the proposer's lone signature is only 1 of 4 equal shares, well under 2/3"
+        );
+
+        // A second validator's signature brings signed weight to 2/4 (50%),
+        // still short of the 2/3 threshold.
+        let second_signer = &extra_wallets[0];
+        let second_sig = BlockSignature {
+            block_id: block.id,
+            block_hash: hex::encode(block.hash),
+            sender: Account {
+                address: second_signer.get_public_key(),
+            },
+            signature: second_signer.sign_message(hex::encode(block.hash).as_bytes()).to_vec(),
         };
+        blockchain.collect_block_signature(second_sig);
+        assert!(
+            blockchain.last_certificate.is_none(),
+            "2 of 4 equal-weight validators is only half the stake, not yet 2/3"
+        );
 
-        let stake_txn1 = Transaction::new(
-            &mut wallet1,
-            validator1.clone(),
-            validator1.clone(),
-            100.0,
+        // A third validator's signature brings signed weight to 3/4 (75%),
+        // which clears 2/3 without every validator having signed.
+        let third_signer = &extra_wallets[1];
+        let third_sig = BlockSignature {
+            block_id: block.id,
+            block_hash: hex::encode(block.hash),
+            sender: Account {
+                address: third_signer.get_public_key(),
+            },
+            signature: third_signer.sign_message(hex::encode(block.hash).as_bytes()).to_vec(),
+        };
+        blockchain.collect_block_signature(third_sig);
+
+        assert!(
+            blockchain.last_certificate.is_some(),
+            "3 of 4 equal-weight validators clears the 2/3 quorum even though the 4th never signed"
+        );
+    }
+
+    #[test]
+    fn test_check_liveness_fires_once_tip_stalls_and_clears_pending_state() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address,
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+
+        assert!(
+            blockchain.check_liveness(5, 5).is_none(),
+            "a tip that's only just been proposed hasn't stalled yet"
+        );
+
+        for _ in 0..5 {
+            blockchain.tick();
+        }
+
+        let event = blockchain
+            .check_liveness(5, 5)
+            .expect("the tip should be reported stalled after 5 ticks with no progress");
+        assert_eq!(event.height, block.id);
+        assert_eq!(event.reason, StallReason::TipNotAdvancing);
+        assert!(
+            !blockchain.pending_signatures.contains_key(&block.id),
+            "a stalled height's pending signatures must be dropped so it can be re-proposed"
+        );
+
+        let (tip_age, _) = blockchain.liveness_status();
+        assert_eq!(tip_age, 0, "clearing a stall resets the tip-progress clock");
+    }
+
+    #[test]
+    fn test_check_liveness_fires_on_signature_deadline_before_tip_stall_threshold() {
+        let mut blockchain = setup_blockchain();
+        // A second validator holding 3/4 of the stake keeps the proposer's
+        // lone signature under 2/3, so the height's bucket stays open.
+        let heavy_validator = Account { address: "heavy-validator".to_string() };
+        blockchain.validator.state.add_account(heavy_validator.clone());
+        blockchain.validator.state.balances.insert(heavy_validator, 300.0);
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address,
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+
+        for _ in 0..3 {
+            blockchain.tick();
+        }
+
+        // A generous tip-stall budget (100) but a tight signature deadline
+        // (3): the signature-collection deadline should fire first.
+        let event = blockchain
+            .check_liveness(100, 3)
+            .expect("the unfulfilled pending-signature bucket should trip its own deadline");
+        assert_eq!(event.height, block.id);
+        assert_eq!(event.reason, StallReason::SignatureDeadlineExceeded);
+    }
+
+    #[test]
+    fn test_check_liveness_does_not_fire_once_the_tip_advances() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address,
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+
+        for _ in 0..4 {
+            blockchain.tick();
+        }
+        blockchain.execute_block(block);
+
+        assert_eq!(
+            blockchain.tip_age_ticks(),
+            0,
+            "executing a block resets the tip-progress clock to the current tick"
+        );
+        assert!(
+            blockchain.check_liveness(5, 5).is_none(),
+            "a tip that just advanced hasn't stalled, regardless of how many ticks preceded it"
+        );
+    }
+
+    #[test]
+    fn test_record_bft_vote_executes_pending_block_on_precommit_quorum() {
+        let mut blockchain = setup_blockchain();
+
+        let extra_wallets: Vec<Wallet> = (0..3).map(|_| Wallet::new().unwrap()).collect();
+        for wallet in &extra_wallets {
+            let account = Account {
+                address: wallet.get_public_key(),
+            };
+            let mut signer = Wallet::new().unwrap();
+            let key_ownership_proof = crate::zkid::prove_key_ownership(wallet);
+            let stake_txn = Transaction::new(
+                &mut signer,
+                account.clone(),
+                account.clone(),
+                100.00,
+                0,
+                0,
+                TransactionType::STAKE,
+                Some(key_ownership_proof),
+            )
+            .unwrap();
+            blockchain
+                .validator
+                .add_validator(account, stake_txn)
+                .unwrap();
+        }
+
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block = Block::new(
+            1,
+            [0u8; 32],
             0,
-            TransactionType::STAKE,
+            vec![],
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
         )
         .unwrap();
-        let stake_txn2 = Transaction::new(
-            &mut wallet2,
-            validator2.clone(),
-            validator2.clone(),
-            200.0,
+        let block_hash = hex::encode(block.hash);
+        blockchain.register_bft_proposal(block.clone());
+        assert!(blockchain.chain.is_empty());
+
+        let cast_vote = |blockchain: &mut Blockchain, signer: &Wallet| {
+            let mut vote = VoteMessage {
+                height: block.id,
+                round: 0,
+                step: VoteStep::Precommit,
+                block_hash: Some(block_hash.clone()),
+                sender: Account {
+                    address: signer.get_public_key(),
+                },
+                signature: Vec::new(),
+            };
+            vote.signature = signer.sign_message(&vote.signing_bytes()).to_vec();
+            blockchain.record_bft_vote(&vote)
+        };
+
+        assert_eq!(cast_vote(&mut blockchain, &extra_wallets[0]), VoteOutcome::Pending);
+        assert_eq!(cast_vote(&mut blockchain, &extra_wallets[1]), VoteOutcome::Pending);
+        let outcome = cast_vote(&mut blockchain, &extra_wallets[2]);
+        assert_eq!(outcome, VoteOutcome::Committed { hash: block_hash });
+        assert_eq!(
+            blockchain.chain.len(),
+            1,
+            "a Precommit quorum should execute the registered pending block"
+        );
+        assert!(
+            !blockchain.bft_pending_blocks.contains_key(&block.id),
+            "the executed block should be removed from the pending set"
+        );
+    }
+
+    #[test]
+    fn test_advance_bft_round_increments_round_and_is_visible_via_bft_round() {
+        let mut blockchain = setup_blockchain();
+        assert_eq!(blockchain.bft_round(1), 0);
+        assert_eq!(blockchain.advance_bft_round(1), 1);
+        assert_eq!(blockchain.bft_round(1), 1);
+    }
+
+    #[test]
+    fn test_handle_chain_response_rejects_a_non_contiguous_chain() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block1 = Block::new(
+            1,
+            [0u8; 32],
             0,
-            TransactionType::STAKE,
+            vec![],
+            proposer_address.clone(),
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        let block3 = Block::new(
+            3,
+            block1.hash,
+            0,
+            vec![],
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
         )
         .unwrap();
 
-        blockchain.handle_stake(stake_txn1);
-        blockchain.handle_stake(stake_txn2);
-        blockchain.end_of_epoch();
-        // Hash chain
-        let hash_chain_validator1 = HashChain::new();
-        let hash_chain_validator2 = HashChain::new();
+        assert!(
+            blockchain.handle_chain_response(vec![block1, block3]).is_err(),
+            "a chain response skipping block 2 must be rejected before touching state"
+        );
+        assert!(blockchain.chain.is_empty(), "a rejected response must not mutate the local chain");
+    }
 
-        let val1_account = Account {
-            address: validator1.address.clone(),
+    #[test]
+    fn test_handle_chain_response_ignores_a_chain_no_longer_than_local() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
         };
-        let val2_account = Account {
-            address: validator2.address.clone(),
+        let block = blockchain.propose_block(
+            "proposer-hash".to_string(),
+            proposer_address,
+            vec![],
+            Seed { seed: [0u8; 32] },
+            None,
+        );
+        blockchain.execute_block(block.clone());
+
+        let result = blockchain
+            .handle_chain_response(vec![block])
+            .expect("a same-length response is valid, just not adopted");
+        assert!(!result, "a response no longer than the local chain must not be adopted");
+    }
+
+    #[test]
+    fn test_handle_chain_response_adopts_a_strictly_longer_valid_chain() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
         };
 
+        // `handle_chain_response` checks block 2's `proposer_hash` against
+        // this registered commitment's `hash_chain_index`; at epoch timestamp 0
+        // the check degenerates to a direct string match (see
+        // `verify_hash_chain_index`'s now-empty iteration range), so
+        // reusing the same hex string for both satisfies it honestly.
+        let hash_chain_index = hex::encode("epoch-0-commitment");
         blockchain.validator.update_validator_com(
-            val1_account.clone(),
-            hash_chain_validator1.get_hash(EPOCH_DURATION as usize, val1_account.clone()),
+            proposer_address.clone(),
+            crate::hashchain::HashChainCom {
+                hash_chain_index: hash_chain_index.clone(),
+                sender: proposer_address.clone(),
+                coin_commitment: [0u8; 32],
+            },
         );
-        blockchain.validator.update_validator_com(
-            val2_account.clone(),
-            hash_chain_validator2.get_hash(EPOCH_DURATION as usize, val2_account.clone()),
+
+        let block1 = Block::new(
+            1,
+            [0u8; 32],
+            0,
+            vec![],
+            proposer_address.clone(),
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        let block2 = Block::new(
+            2,
+            block1.hash,
+            0,
+            vec![],
+            proposer_address,
+            hash_chain_index,
+            Seed { seed: [1u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+
+        let result = blockchain
+            .handle_chain_response(vec![block1, block2])
+            .expect("a contiguous, hashchain-passing chain should be accepted");
+        assert!(result, "a strictly longer valid chain must be adopted");
+        assert_eq!(blockchain.chain.len(), 2);
+    }
+
+    #[test]
+    fn test_end_of_epoch_anchors_new_blocks_when_a_checkpointer_is_configured() {
+        use crate::settlement::{L1Config, LoggingL1Checkpointer};
+
+        let mut blockchain = setup_blockchain();
+        blockchain.set_l1_checkpointer(Box::new(LoggingL1Checkpointer::new(L1Config {
+            rpc_url: "http://localhost:8545".to_string(),
+            contract_address: "0x0000000000000000000000000000000000dEaD".to_string(),
+            signing_key: "test-signing-key".to_string(),
+        })));
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block1 = Block::new(
+            1,
+            [0u8; 32],
+            0,
+            vec![],
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        blockchain.chain.push(block1);
+
+        blockchain.end_of_epoch();
+
+        assert_eq!(
+            blockchain.last_checkpointed_height, 1,
+            "the one block on chain should now be covered by a checkpoint"
         );
+        assert_eq!(blockchain.anchored_epoch_count, 1);
+        assert!(
+            blockchain.latest_finalized_checkpoint().is_some(),
+            "a submitted checkpoint should be queryable back out"
+        );
+    }
+
+    #[test]
+    fn test_end_of_epoch_is_a_no_op_without_a_configured_checkpointer() {
+        let mut blockchain = setup_blockchain();
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block1 = Block::new(
+            1,
+            [0u8; 32],
+            0,
+            vec![],
+            proposer_address,
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        blockchain.chain.push(block1);
 
-        let seed = blockchain.new_epoch();
-        let proposer = blockchain.select_block_proposer(seed);
-        if proposer.address == validator1.address {
-            println!("Validator 1 is proposer");
-        } else if proposer.address == validator2.address {
-            println!("Validator 2 is proposer");
+        blockchain.end_of_epoch();
+
+        assert_eq!(blockchain.last_checkpointed_height, 0);
+        assert_eq!(blockchain.anchored_epoch_count, 0);
+        assert!(blockchain.latest_finalized_checkpoint().is_none());
+    }
+
+    #[test]
+    fn test_anchor_checkpoint_only_covers_blocks_added_since_the_last_checkpoint() {
+        use crate::settlement::{L1Config, LoggingL1Checkpointer};
+
+        let mut blockchain = setup_blockchain();
+        blockchain.set_l1_checkpointer(Box::new(LoggingL1Checkpointer::new(L1Config {
+            rpc_url: "http://localhost:8545".to_string(),
+            contract_address: "0x0000000000000000000000000000000000dEaD".to_string(),
+            signing_key: "test-signing-key".to_string(),
+        })));
+        let proposer_address = Account {
+            address: blockchain.wallet.get_public_key(),
+        };
+        let block1 = Block::new(
+            1,
+            [0u8; 32],
+            0,
+            vec![],
+            proposer_address.clone(),
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        blockchain.chain.push(block1.clone());
+        blockchain.end_of_epoch();
+        assert_eq!(blockchain.anchored_epoch_count, 1);
+
+        let block2 = Block::new(
+            2,
+            block1.hash,
+            0,
+            vec![],
+            proposer_address,
+            "proposer-hash-2".to_string(),
+            Seed { seed: [1u8; 32] },
+            None,
+            None,
+        )
+        .unwrap();
+        blockchain.chain.push(block2);
+        blockchain.end_of_epoch();
+
+        assert_eq!(
+            blockchain.anchored_epoch_count, 2,
+            "a second epoch with a new block must produce a second checkpoint"
+        );
+        assert_eq!(blockchain.last_checkpointed_height, 2);
+    }
+
+    #[test]
+    fn test_party_tree_caps_the_signer_set_at_max_validator_slots() {
+        let mut blockchain = setup_blockchain();
+        for i in 0..(MAX_VALIDATOR_SLOTS + 10) {
+            let account = Account { address: format!("validator-{:03}", i) };
+            blockchain.validator.state.add_account(account.clone());
+            blockchain
+                .validator
+                .state
+                .balances
+                .insert(account, (i + 1) as f64);
         }
-        assert!(proposer.address == validator1.address || proposer.address == validator2.address);
+
+        let (participants, _, _) = blockchain.party_tree();
+
+        assert_eq!(
+            participants.len(),
+            MAX_VALIDATOR_SLOTS,
+            "the certificate signer set must never exceed MAX_VALIDATOR_SLOTS"
+        );
+        assert!(
+            participants.iter().any(|p| p.public_key == "validator-019"),
+            "the highest-staked accounts must be the ones selected"
+        );
+        assert!(
+            !participants.iter().any(|p| p.public_key == "validator-000"),
+            "the lowest-staked accounts must be dropped once the set is over capacity"
+        );
     }
 }
@@ -0,0 +1,4 @@
+pub mod deposit;
+pub mod htlc;
+pub mod key_rotation;
+pub mod withdrawal;
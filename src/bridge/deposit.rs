@@ -0,0 +1,269 @@
+use crate::accounts::Account;
+use crate::transaction::{Transaction, TransactionType};
+use crate::wallet::Wallet;
+use log::{info, warn};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// A decoded `InInstruction`/deposit log read from the Router contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepositLog {
+    pub block_number: u64,
+    pub l1_sender: String,
+    pub sidechain_recipient: String,
+    pub amount: f64,
+}
+
+/// A decoded ERC-20/native `Transfer` log paid *to* the Router contract, used
+/// to confirm a `DepositLog` is backed by funds that actually moved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferLog {
+    pub block_number: u64,
+    pub from: String,
+    pub amount: f64,
+}
+
+/// Abstracts reading Router logs from the L1 provider. A real implementation
+/// wraps an `ethers-providers` `Provider` filtering the Router's deposit and
+/// `Transfer` events; this boundary lets the watcher's cross-verification and
+/// resume logic be tested without a live L1 node.
+pub trait RouterLogSource {
+    fn latest_block(&self) -> u64;
+    fn deposit_logs(&self, from_block: u64, to_block: u64) -> Vec<DepositLog>;
+    fn transfer_logs(&self, from_block: u64, to_block: u64) -> Vec<TransferLog>;
+}
+
+/// Persists/restores the last L1 block the watcher has fully processed, so a
+/// restart resumes instead of replaying or missing deposits.
+pub trait WatermarkStore {
+    fn load(&self) -> Option<u64>;
+    fn save(&self, block_number: u64);
+}
+
+/// An in-memory/no-op watermark used for tests and standalone nodes that
+/// don't yet have a persistence backend wired in.
+#[derive(Default)]
+pub struct NullWatermarkStore;
+
+impl WatermarkStore for NullWatermarkStore {
+    fn load(&self) -> Option<u64> {
+        None
+    }
+
+    fn save(&self, _block_number: u64) {}
+}
+
+/// Cross-verifies a deposit log against the `Transfer` logs observed in the
+/// same block, so a spoofed `InInstruction` event (with no matching transfer
+/// into the Router) cannot mint sidechain funds.
+pub fn deposit_is_backed_by_transfer(deposit: &DepositLog, transfers: &[TransferLog]) -> bool {
+    transfers.iter().any(|t| {
+        t.block_number == deposit.block_number
+            && t.from == deposit.l1_sender
+            && (t.amount - deposit.amount).abs() < f64::EPSILON
+    })
+}
+
+/// Watches the Router for deposits and mints the corresponding sidechain
+/// credit by pushing a `BRIDGE_DEPOSIT` transaction through the same channel
+/// the RPC server uses.
+pub struct DepositWatcher<W: WatermarkStore> {
+    last_processed_block: u64,
+    watermark: W,
+    /// Signs the minted deposit transactions; its public key is the
+    /// recognized "bridge" sender account that validators treat as the
+    /// credit source for `BRIDGE_DEPOSIT` transactions.
+    bridge_wallet: Wallet,
+    /// The nonce the next minted transaction must carry, since every mint
+    /// is signed by the same long-lived `bridge_wallet` and
+    /// `State::apply_transaction` rejects a replayed or out-of-order nonce.
+    next_nonce: u64,
+}
+
+impl<W: WatermarkStore> DepositWatcher<W> {
+    pub fn new(bridge_wallet: Wallet, watermark: W) -> Self {
+        let last_processed_block = watermark.load().unwrap_or(0);
+        Self {
+            last_processed_block,
+            watermark,
+            bridge_wallet,
+            next_nonce: 0,
+        }
+    }
+
+    /// Polls `source` for new deposit logs since the last processed block,
+    /// verifies each against a matching transfer, and injects the accepted
+    /// ones as transactions through `rpc_sender`.
+    pub fn poll_once(&mut self, source: &impl RouterLogSource, rpc_sender: &UnboundedSender<Transaction>) {
+        let to_block = source.latest_block();
+        if to_block <= self.last_processed_block {
+            return;
+        }
+        let from_block = self.last_processed_block + 1;
+
+        let deposits = source.deposit_logs(from_block, to_block);
+        let transfers = source.transfer_logs(from_block, to_block);
+
+        for deposit in &deposits {
+            if !deposit_is_backed_by_transfer(deposit, &transfers) {
+                warn!(
+                    "Rejecting deposit log at block {} from {}: no matching Router transfer",
+                    deposit.block_number, deposit.l1_sender
+                );
+                continue;
+            }
+
+            match self.mint_deposit(deposit) {
+                Ok(txn) => {
+                    info!(
+                        "Minting bridge deposit of {} to {} from L1 block {}",
+                        deposit.amount, deposit.sidechain_recipient, deposit.block_number
+                    );
+                    if rpc_sender.send(txn).is_err() {
+                        warn!("Failed to forward bridge deposit transaction: receiver dropped");
+                    }
+                }
+                Err(e) => warn!("Failed to build bridge deposit transaction: {}", e),
+            }
+        }
+
+        self.last_processed_block = to_block;
+        self.watermark.save(to_block);
+    }
+
+    fn mint_deposit(&mut self, deposit: &DepositLog) -> Result<Transaction, String> {
+        let bridge_account = Account {
+            address: self.bridge_wallet.get_public_key(),
+        };
+        let recipient = Account {
+            address: deposit.sidechain_recipient.clone(),
+        };
+        let nonce = self.next_nonce;
+        let txn = Transaction::new(
+            &mut self.bridge_wallet,
+            bridge_account,
+            recipient,
+            deposit.amount,
+            0,
+            nonce,
+            TransactionType::BRIDGE_DEPOSIT,
+            None,
+        )?;
+        self.next_nonce += 1;
+        Ok(txn)
+    }
+
+    pub fn last_processed_block(&self) -> u64 {
+        self.last_processed_block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct MockSource {
+        latest: u64,
+        deposits: Vec<DepositLog>,
+        transfers: Vec<TransferLog>,
+    }
+
+    impl RouterLogSource for MockSource {
+        fn latest_block(&self) -> u64 {
+            self.latest
+        }
+
+        fn deposit_logs(&self, from_block: u64, to_block: u64) -> Vec<DepositLog> {
+            self.deposits
+                .iter()
+                .filter(|d| d.block_number >= from_block && d.block_number <= to_block)
+                .cloned()
+                .collect()
+        }
+
+        fn transfer_logs(&self, from_block: u64, to_block: u64) -> Vec<TransferLog> {
+            self.transfers
+                .iter()
+                .filter(|t| t.block_number >= from_block && t.block_number <= to_block)
+                .cloned()
+                .collect()
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingWatermark {
+        saved: RefCell<Option<u64>>,
+    }
+
+    impl WatermarkStore for RecordingWatermark {
+        fn load(&self) -> Option<u64> {
+            *self.saved.borrow()
+        }
+
+        fn save(&self, block_number: u64) {
+            *self.saved.borrow_mut() = Some(block_number);
+        }
+    }
+
+    #[test]
+    fn test_backed_deposit_is_minted() {
+        let wallet = Wallet::new().unwrap();
+        let recipient = Wallet::new().unwrap().get_public_key();
+        let mut watcher = DepositWatcher::new(wallet, NullWatermarkStore);
+
+        let source = MockSource {
+            latest: 10,
+            deposits: vec![DepositLog {
+                block_number: 5,
+                l1_sender: "0xabc".to_string(),
+                sidechain_recipient: recipient,
+                amount: 42.0,
+            }],
+            transfers: vec![TransferLog {
+                block_number: 5,
+                from: "0xabc".to_string(),
+                amount: 42.0,
+            }],
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        watcher.poll_once(&source, &tx);
+
+        let txn = rx.try_recv().expect("deposit should have been minted");
+        assert_eq!(txn.txn_type, TransactionType::BRIDGE_DEPOSIT);
+        assert_eq!(txn.amount, 42.0);
+        assert_eq!(watcher.last_processed_block(), 10);
+    }
+
+    #[test]
+    fn test_unbacked_deposit_is_rejected() {
+        let wallet = Wallet::new().unwrap();
+        let recipient = Wallet::new().unwrap().get_public_key();
+        let mut watcher = DepositWatcher::new(wallet, NullWatermarkStore);
+
+        let source = MockSource {
+            latest: 10,
+            deposits: vec![DepositLog {
+                block_number: 5,
+                l1_sender: "0xabc".to_string(),
+                sidechain_recipient: recipient,
+                amount: 42.0,
+            }],
+            transfers: vec![],
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        watcher.poll_once(&source, &tx);
+
+        assert!(rx.try_recv().is_err(), "spoofed deposit with no backing transfer must not mint");
+    }
+
+    #[test]
+    fn test_watermark_resumes_from_persisted_block() {
+        let watermark = RecordingWatermark::default();
+        watermark.save(7);
+        let wallet = Wallet::new().unwrap();
+        let watcher = DepositWatcher::new(wallet, watermark);
+        assert_eq!(watcher.last_processed_block(), 7);
+    }
+}
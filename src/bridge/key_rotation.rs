@@ -0,0 +1,279 @@
+use crate::ccok::sig::{SchnorrPublicKey, SchnorrSigner};
+use crate::ccok::{Certificate, Params};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use sha3::{Digest, Keccak256};
+
+/// Tracks the sidechain's aggregate Schnorr key as registered on the Router,
+/// along with the monotonic nonce the Router expects on the next rotation.
+///
+/// The nonce prevents a captured `updateKey` message from being replayed: the
+/// Router only accepts a rotation whose signed nonce matches its own stored
+/// `current_router_nonce`, and the nonce only ever advances.
+#[derive(Debug)]
+pub struct KeyRotationState {
+    pub current_key: SchnorrPublicKey,
+    pub router_nonce: u64,
+}
+
+/// Anything capable of submitting an `updateKey` call to the on-chain Router.
+/// This mirrors the shape of an `ethers-contract` `abigen!` binding for the
+/// `Router` contract without depending on one directly, so the rotation logic
+/// here can be exercised against a mock in tests and wired to the real
+/// generated binding once it lands.
+pub trait RouterClient {
+    fn chain_id(&self) -> u64;
+    fn submit_update_key(&self, new_pubkey: &[u8], signature: &[u8]) -> Result<(), String>;
+}
+
+/// Assembles the tightly packed rotation payload:
+/// `keccak256(chain_id || "updateKey" || current_router_nonce || new_pubkey_encoded)`,
+/// following the `updateSeraiKey` message layout.
+pub fn build_rotation_message(chain_id: u64, router_nonce: u64, new_pubkey_encoded: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(chain_id.to_be_bytes());
+    hasher.update(b"updateKey");
+    hasher.update(router_nonce.to_be_bytes());
+    hasher.update(new_pubkey_encoded);
+    hasher.finalize().into()
+}
+
+impl KeyRotationState {
+    pub fn new(current_key: SchnorrPublicKey, router_nonce: u64) -> Self {
+        Self {
+            current_key,
+            router_nonce,
+        }
+    }
+
+    /// Signs a rotation to `new_key` with the *current* aggregate key and
+    /// submits it through `router`. On success the local nonce is advanced so
+    /// a subsequent rotation can't reuse (and the Router can't replay) this
+    /// message.
+    pub fn rotate(
+        &mut self,
+        signer: &SchnorrSigner,
+        new_key: &SchnorrPublicKey,
+        router: &impl RouterClient,
+    ) -> Result<(), String> {
+        if signer.public.key != self.current_key.key {
+            return Err("signer does not hold the currently registered key".to_string());
+        }
+
+        let new_pubkey_encoded = new_key.key.to_encoded_point(false).as_bytes().to_vec();
+        let message = build_rotation_message(router.chain_id(), self.router_nonce, &new_pubkey_encoded);
+        let signature = signer
+            .sign(&message)
+            .map_err(|e| format!("failed to sign rotation message: {}", e))?;
+
+        router.submit_update_key(&new_pubkey_encoded, &signature)?;
+
+        self.current_key = new_key.clone();
+        self.router_nonce += 1;
+        Ok(())
+    }
+
+    /// Same as `rotate`, but only submits the rotation once `certificate`
+    /// proves that validators holding at least `finality_params.proven_weight`
+    /// of stake finalized the epoch-boundary block that elected `new_key`.
+    /// This is what ties rotation to the compact-certificate finality gadget:
+    /// the Router never accepts a new aggregate key on the say-so of a single
+    /// node, only once the sidechain itself has finalized the handover.
+    pub fn rotate_with_finality_proof(
+        &mut self,
+        signer: &SchnorrSigner,
+        new_key: &SchnorrPublicKey,
+        router: &impl RouterClient,
+        certificate: &Certificate,
+        finality_params: &Params,
+        party_tree_root: &[u8],
+    ) -> Result<(), String> {
+        let finalized = certificate
+            .verify(finality_params, party_tree_root)
+            .map_err(|e| format!("failed to verify epoch-boundary certificate: {}", e))?;
+        if !finalized {
+            return Err(
+                "epoch-boundary block is not yet finalized by >= 2/3 stake; refusing to rotate"
+                    .to_string(),
+            );
+        }
+
+        self.rotate(signer, new_key, router)
+    }
+}
+
+/// Verifier a node runs before accepting a rotation it observed on-chain (or
+/// relayed off-chain): confirms `signature` over the rotation message was
+/// produced by `current_key`, the key the Router had registered *before* this
+/// rotation.
+pub fn verify_rotation(
+    current_key: &SchnorrPublicKey,
+    chain_id: u64,
+    router_nonce: u64,
+    new_pubkey_encoded: &[u8],
+    signature: &[u8],
+) -> Result<bool, String> {
+    let message = build_rotation_message(chain_id, router_nonce, new_pubkey_encoded);
+    current_key
+        .verify(signature, &message)
+        .map_err(|e| format!("failed to verify rotation signature: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct MockRouter {
+        chain_id: u64,
+        submitted: RefCell<Option<(Vec<u8>, Vec<u8>)>>,
+    }
+
+    impl RouterClient for MockRouter {
+        fn chain_id(&self) -> u64 {
+            self.chain_id
+        }
+
+        fn submit_update_key(&self, new_pubkey: &[u8], signature: &[u8]) -> Result<(), String> {
+            *self.submitted.borrow_mut() = Some((new_pubkey.to_vec(), signature.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rotate_advances_nonce_and_verifies() {
+        let old_signer = SchnorrSigner::generate().unwrap();
+        let new_signer = SchnorrSigner::generate().unwrap();
+        let mut state = KeyRotationState::new(old_signer.public.clone(), 5);
+
+        let router = MockRouter {
+            chain_id: 1337,
+            submitted: RefCell::new(None),
+        };
+
+        state
+            .rotate(&old_signer, &new_signer.public, &router)
+            .expect("rotation should succeed");
+
+        assert_eq!(state.router_nonce, 6);
+        assert_eq!(state.current_key.key, new_signer.public.key, "current_key updates to the new key");
+    }
+
+    #[test]
+    fn test_rotation_rejected_from_stale_key() {
+        let old_signer = SchnorrSigner::generate().unwrap();
+        let imposter = SchnorrSigner::generate().unwrap();
+        let new_signer = SchnorrSigner::generate().unwrap();
+        let mut state = KeyRotationState::new(old_signer.public.clone(), 0);
+
+        let router = MockRouter {
+            chain_id: 1,
+            submitted: RefCell::new(None),
+        };
+
+        let result = state.rotate(&imposter, &new_signer.public, &router);
+        assert!(result.is_err(), "rotation signed by a non-current key must be rejected");
+    }
+
+    #[test]
+    fn test_verify_rotation_message() {
+        let old_signer = SchnorrSigner::generate().unwrap();
+        let new_signer = SchnorrSigner::generate().unwrap();
+        let new_pubkey_encoded = new_signer.public.key.to_encoded_point(false).as_bytes().to_vec();
+
+        let message = build_rotation_message(1, 0, &new_pubkey_encoded);
+        let signature = old_signer.sign(&message).unwrap();
+
+        assert!(verify_rotation(&old_signer.public, 1, 0, &new_pubkey_encoded, &signature).unwrap());
+        // A replayed nonce no longer matches the message the Router expects next.
+        assert!(!verify_rotation(&old_signer.public, 1, 1, &new_pubkey_encoded, &signature).unwrap());
+    }
+
+    /// Builds a one-validator compact certificate over `msg` whose lone
+    /// signer carries weight 100, verifiable against `proven_weight`, so
+    /// tests can exercise the finality-gated rotation path without standing
+    /// up a whole `Blockchain`.
+    fn build_certificate(msg: &[u8], proven_weight: u64) -> (Certificate, Params, Vec<u8>) {
+        use crate::ccok::{Builder as CertBuilder, Participant};
+        use crate::merkle::MerkleTreeBuilder;
+        use crate::wallet::Wallet;
+
+        let wallet = Wallet::new().unwrap();
+        let participants = vec![Participant {
+            public_key: wallet.get_public_key(),
+            weight: 100,
+            key_schedule_root: None,
+            weight_commitment: None,
+        }];
+        let mut tree = MerkleTreeBuilder::new();
+        tree.build(&participants).unwrap();
+        let party_tree_root = tree.root();
+
+        // Build with a threshold of 0 so `build()` always succeeds; the
+        // caller-supplied `proven_weight` is instead checked at `verify()`
+        // time via the returned `Params`, mirroring how a real verifier
+        // supplies its own finality threshold independent of the builder.
+        let build_params = Params {
+            msg: msg.to_vec(),
+            proven_weight: 0,
+            security_param: 128,
+            epoch: 0,
+        };
+        let mut builder = CertBuilder::new(build_params, participants, party_tree_root.clone());
+        let signature = wallet.sign_message(msg);
+        builder.add_signature(0, signature).unwrap();
+        let certificate = builder.build().unwrap();
+
+        let verify_params = Params {
+            msg: msg.to_vec(),
+            proven_weight,
+            security_param: 128,
+            epoch: 0,
+        };
+        (certificate, verify_params, party_tree_root)
+    }
+
+    #[test]
+    fn test_rotate_with_finality_proof_requires_verifying_certificate() {
+        let old_signer = SchnorrSigner::generate().unwrap();
+        let new_signer = SchnorrSigner::generate().unwrap();
+        let mut state = KeyRotationState::new(old_signer.public.clone(), 0);
+        let router = MockRouter {
+            chain_id: 1,
+            submitted: RefCell::new(None),
+        };
+
+        let (certificate, params, party_tree_root) = build_certificate(b"epoch-boundary-block", 100);
+        state
+            .rotate_with_finality_proof(&old_signer, &new_signer.public, &router, &certificate, &params, &party_tree_root)
+            .expect("rotation backed by a verifying certificate should succeed");
+
+        assert_eq!(state.current_key.key, new_signer.public.key);
+        assert!(router.submitted.borrow().is_some());
+    }
+
+    #[test]
+    fn test_rotate_with_finality_proof_rejects_unfinalized_certificate() {
+        let old_signer = SchnorrSigner::generate().unwrap();
+        let new_signer = SchnorrSigner::generate().unwrap();
+        let mut state = KeyRotationState::new(old_signer.public.clone(), 0);
+        let router = MockRouter {
+            chain_id: 1,
+            submitted: RefCell::new(None),
+        };
+
+        // proven_weight exceeds what the lone signer carries, so the
+        // certificate can never verify: finality was never reached.
+        let (certificate, params, party_tree_root) = build_certificate(b"epoch-boundary-block", 1_000);
+        let result = state.rotate_with_finality_proof(
+            &old_signer,
+            &new_signer.public,
+            &router,
+            &certificate,
+            &params,
+            &party_tree_root,
+        );
+
+        assert!(result.is_err(), "rotation must not proceed without a finalizing certificate");
+        assert_eq!(state.current_key.key, old_signer.public.key, "current key stays put on rejection");
+    }
+}
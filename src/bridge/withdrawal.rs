@@ -0,0 +1,219 @@
+use crate::ccok::{Certificate, Params};
+use crate::transaction::{Transaction, TransactionType};
+use log::info;
+
+/// A single sidechain-to-L1 payout, decoded from a `WITHDRAW` transaction
+/// once it lands in a block. `sender` burns `amount` on the sidechain;
+/// `l1_recipient` is the address the Router pays out to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawalRequest {
+    pub sender: String,
+    pub l1_recipient: String,
+    pub amount: f64,
+}
+
+impl WithdrawalRequest {
+    /// Decodes a `WithdrawalRequest` from a `WITHDRAW` transaction, or
+    /// `None` if `txn` is of some other type.
+    pub fn from_transaction(txn: &Transaction) -> Option<Self> {
+        if txn.txn_type != TransactionType::WITHDRAW {
+            return None;
+        }
+        Some(Self {
+            sender: txn.sender.address.clone(),
+            l1_recipient: txn.recipient.address.clone(),
+            amount: txn.amount,
+        })
+    }
+
+    /// Filters and decodes every `WITHDRAW` transaction out of `txns`,
+    /// preserving block order.
+    pub fn collect(txns: &[Transaction]) -> Vec<Self> {
+        txns.iter().filter_map(Self::from_transaction).collect()
+    }
+}
+
+/// The withdrawals finalized by block `block_id`, ready to relay to the
+/// Router once a compact certificate proves that block is final.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawalBatch {
+    pub block_id: usize,
+    pub requests: Vec<WithdrawalRequest>,
+}
+
+impl WithdrawalBatch {
+    pub fn new(block_id: usize, requests: Vec<WithdrawalRequest>) -> Self {
+        Self { block_id, requests }
+    }
+
+    pub fn total_amount(&self) -> f64 {
+        self.requests.iter().map(|r| r.amount).sum()
+    }
+}
+
+/// Anything capable of submitting a withdrawal batch plus its backing
+/// finality certificate to the on-chain Router's `processBatch` call.
+/// Mirrors `key_rotation::RouterClient`: this boundary keeps the relay
+/// logic testable without a live L1 node.
+pub trait WithdrawalRelay {
+    fn submit_batch(&self, batch: &WithdrawalBatch, certificate: &[u8]) -> Result<(), String>;
+}
+
+/// Relays `batch` to the Router only once `certificate` is shown to verify
+/// against `params`/`party_tree_root` — i.e. only once validators holding
+/// at least `params.proven_weight` of total staked weight signed the
+/// finalizing block's hash. The Router therefore never needs to see
+/// individual validator signatures, only this one succinct proof.
+pub fn relay_finalized_batch(
+    batch: &WithdrawalBatch,
+    certificate: &Certificate,
+    params: &Params,
+    party_tree_root: &[u8],
+    relay: &impl WithdrawalRelay,
+) -> Result<(), String> {
+    let finalized = certificate
+        .verify(params, party_tree_root)
+        .map_err(|e| format!("failed to verify finality certificate: {}", e))?;
+    if !finalized {
+        return Err(format!(
+            "block {} is not finalized by >= 2/3 stake; refusing to relay its withdrawal batch",
+            batch.block_id
+        ));
+    }
+
+    info!(
+        "Relaying withdrawal batch for block {} ({} withdrawals, {} total) to Router",
+        batch.block_id,
+        batch.requests.len(),
+        batch.total_amount()
+    );
+    relay.submit_batch(batch, &certificate.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::Account;
+    use crate::ccok::{Builder as CertBuilder, Participant};
+    use crate::merkle::MerkleTreeBuilder;
+    use crate::wallet::Wallet;
+    use std::cell::RefCell;
+
+    fn withdraw_txn(wallet: &mut Wallet, l1_recipient: &str, amount: f64) -> Transaction {
+        let sender = Account {
+            address: wallet.get_public_key(),
+        };
+        let recipient = Account {
+            address: l1_recipient.to_string(),
+        };
+        Transaction::new(wallet, sender, recipient, amount, 0, 0, TransactionType::WITHDRAW, None).unwrap()
+    }
+
+    fn build_certificate(msg: &[u8], signer_weight: u64, proven_weight: u64) -> (Certificate, Params, Vec<u8>) {
+        let wallet = Wallet::new().unwrap();
+        let participants = vec![Participant {
+            public_key: wallet.get_public_key(),
+            weight: signer_weight,
+            key_schedule_root: None,
+            weight_commitment: None,
+        }];
+        let mut tree = MerkleTreeBuilder::new();
+        tree.build(&participants).unwrap();
+        let party_tree_root = tree.root();
+
+        let build_params = Params {
+            msg: msg.to_vec(),
+            proven_weight: 0,
+            security_param: 128,
+            epoch: 0,
+        };
+        let mut builder = CertBuilder::new(build_params, participants, party_tree_root.clone());
+        let signature = wallet.sign_message(msg);
+        builder.add_signature(0, signature).unwrap();
+        let certificate = builder.build().unwrap();
+
+        let verify_params = Params {
+            msg: msg.to_vec(),
+            proven_weight,
+            security_param: 128,
+            epoch: 0,
+        };
+        (certificate, verify_params, party_tree_root)
+    }
+
+    #[derive(Default)]
+    struct RecordingRelay {
+        submitted: RefCell<Option<(usize, usize)>>,
+    }
+
+    impl WithdrawalRelay for RecordingRelay {
+        fn submit_batch(&self, batch: &WithdrawalBatch, _certificate: &[u8]) -> Result<(), String> {
+            *self.submitted.borrow_mut() = Some((batch.block_id, batch.requests.len()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_collect_filters_withdraw_transactions() {
+        let mut withdrawer = Wallet::new().unwrap();
+        let mut sender = Wallet::new().unwrap();
+        let withdraw = withdraw_txn(&mut withdrawer, "0xl1recipient", 10.0);
+        let transfer = Transaction::new(
+            &mut sender,
+            Account {
+                address: sender.get_public_key(),
+            },
+            Account {
+                address: withdrawer.get_public_key(),
+            },
+            5.0,
+            0,
+            0,
+            TransactionType::TRANSACTION,
+            None,
+        )
+        .unwrap();
+
+        let requests = WithdrawalRequest::collect(&[withdraw.clone(), transfer]);
+        assert_eq!(
+            requests,
+            vec![WithdrawalRequest {
+                sender: withdrawer.get_public_key(),
+                l1_recipient: "0xl1recipient".to_string(),
+                amount: 10.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_relay_finalized_batch_requires_verifying_certificate() {
+        let batch = WithdrawalBatch::new(
+            7,
+            vec![WithdrawalRequest {
+                sender: "alice".to_string(),
+                l1_recipient: "0xl1".to_string(),
+                amount: 10.0,
+            }],
+        );
+        let (certificate, params, party_tree_root) = build_certificate(b"block-7-hash", 100, 100);
+        let relay = RecordingRelay::default();
+
+        relay_finalized_batch(&batch, &certificate, &params, &party_tree_root, &relay)
+            .expect("a verifying certificate should allow the batch to relay");
+
+        assert_eq!(*relay.submitted.borrow(), Some((7, 1)));
+    }
+
+    #[test]
+    fn test_relay_finalized_batch_rejects_unfinalized_certificate() {
+        let batch = WithdrawalBatch::new(7, vec![]);
+        // proven_weight exceeds the lone signer's weight, so the
+        // certificate can never verify: the block was never finalized.
+        let (certificate, params, party_tree_root) = build_certificate(b"block-7-hash", 100, 1_000);
+        let relay = RecordingRelay::default();
+
+        let result = relay_finalized_batch(&batch, &certificate, &params, &party_tree_root, &relay);
+        assert!(result.is_err(), "an unfinalized block's withdrawals must not relay");
+        assert!(relay.submitted.borrow().is_none());
+    }
+}
@@ -1,4 +1,9 @@
-use crate::merkle::{CustomHasher, MerkleTreeBuilder};
+pub mod cert;
+pub mod privacy;
+pub mod sig;
+
+use crate::ccok::privacy::{SumOpeningProof, WeightCommitment, WeightHidingProof, WeightOpening};
+use crate::merkle::{CustomHasher, MerkleMultiProof, MerkleTreeBuilder};
 use bincode;
 use crystals_dilithium::dilithium2::{PublicKey, Signature};
 use hex;
@@ -33,10 +38,74 @@ impl TryInto<Signature> for SerializableSignature {
 /// Represents a participant in the certificate system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Participant {
-    /// The public key of the participant in hex format
+    /// The public key of the participant in hex format. In forward-secure
+    /// mode (see [`key_schedule_root`](Self::key_schedule_root)) this is
+    /// unused and left empty; the live key is whichever epoch leaf the
+    /// signer reveals instead.
     pub public_key: String,
     /// The weight of the participant in the system
     pub weight: u64,
+    /// Root of this participant's forward-secure [`EpochKeySchedule`], hex
+    /// encoded, if they rotate keys per epoch instead of signing with a
+    /// single static `public_key`. Committed here rather than out of band
+    /// so the existing participant Merkle tree (and `party_proofs`) already
+    /// authenticates it.
+    pub key_schedule_root: Option<String>,
+    /// Pedersen commitment to `weight` (see [`crate::ccok::privacy`]),
+    /// present when this participant is in weight-hiding mode. When set,
+    /// `weight` itself is meaningless to anyone but the builder that
+    /// produced it (zeroed in any `Participant` that gets hashed into a
+    /// public party tree) — a verifier must use a revealed slot's
+    /// [`WeightOpening`] instead.
+    pub weight_commitment: Option<WeightCommitment>,
+}
+
+/// A participant's forward-secure per-epoch Dilithium key schedule: `T`
+/// rotating public keys committed to a single Merkle root instead of one
+/// static key. A signer for epoch `e` reveals `epoch_keys[e]` plus the
+/// Merkle path proving it is leaf `e` of the schedule, and is expected to
+/// delete `sk_0..=sk_e` immediately afterward, so compromising the key
+/// material live at epoch `e` cannot forge a certificate attributed to any
+/// epoch `< e`. Mirrors the hash-chained, advance-and-delete key evolution
+/// of the Nomos `Coin::evolve` construction, specialized to a Merkle
+/// commitment since certificates already verify membership that way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochKeySchedule {
+    /// Hex-encoded Dilithium public key for each epoch, indexed by epoch.
+    pub epoch_keys: Vec<String>,
+}
+
+impl EpochKeySchedule {
+    pub fn new(epoch_keys: Vec<String>) -> Self {
+        Self { epoch_keys }
+    }
+
+    /// Root committing to every key in the schedule; stored by a
+    /// forward-secure [`Participant`] as `key_schedule_root`.
+    pub fn root(&self) -> Result<Vec<u8>, String> {
+        let mut tree = MerkleTreeBuilder::new();
+        tree.build(&self.epoch_keys)?;
+        Ok(tree.root())
+    }
+
+    /// Proof that `epoch_keys[epoch]` is leaf `epoch` of this schedule.
+    pub fn prove(&self, epoch: usize) -> Result<MerkleMultiProof, String> {
+        let mut tree = MerkleTreeBuilder::new();
+        tree.build(&self.epoch_keys)?;
+        MerkleMultiProof::new(&tree, &self.epoch_keys, &[epoch])
+    }
+}
+
+/// The forward-secure key a signer reveals in place of their participant's
+/// static `public_key`: the live key for `Params::epoch`, plus the proof
+/// that it is really the corresponding leaf of the schedule committed in
+/// `Participant::key_schedule_root`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochKeyReveal {
+    /// Hex-encoded Dilithium public key for the epoch being signed.
+    pub pubkey: String,
+    /// Merkle proof that `pubkey` is leaf `epoch` of the signer's schedule.
+    pub proof: MerkleMultiProof,
 }
 
 /// A slot for storing signature information
@@ -46,6 +115,10 @@ pub struct SigSlot {
     pub signature: Option<SerializableSignature>,
     /// The accumulated weight up to this slot (L-value in the original implementation)
     pub accumulated_weight: u64,
+    /// Present only when the signer is in forward-secure mode (see
+    /// [`Participant::key_schedule_root`]): the per-epoch key they signed
+    /// with and its membership proof.
+    pub epoch_key: Option<EpochKeyReveal>,
 }
 
 /// Configuration parameters for the certificate system
@@ -57,6 +130,11 @@ pub struct Params {
     pub proven_weight: u64,
     /// Security parameter for the system
     pub security_param: u32,
+    /// The epoch signatures in this certificate were made for. Folded into
+    /// `coin_choice` so certificates for different epochs over the same
+    /// signed weight don't reveal the same positions, and checked against
+    /// each forward-secure signer's revealed epoch key.
+    pub epoch: u64,
 }
 
 /// Represents a reveal in the certificate
@@ -66,6 +144,10 @@ pub struct Reveal {
     pub sig_slot: SigSlot,
     /// The participant information
     pub party: Participant,
+    /// This slot's opening of `party.weight_commitment`, present iff the
+    /// certificate is in weight-hiding mode; needed so `verify` can recover
+    /// the real weight for the coin-interval check.
+    pub weight_opening: Option<WeightOpening>,
 }
 
 /// The final certificate containing all proofs and reveals
@@ -83,10 +165,344 @@ pub struct Certificate {
     pub sig_proofs: Vec<Vec<u8>>,
     /// Merkle proofs for participants
     pub party_proofs: Vec<Vec<u8>>,
-    /// Order of reveal positions as chosen during build
+    /// The position each coin flip landed on, one entry per flip in
+    /// `[0, num_reveals)`; the same position can repeat if several flips
+    /// land in its weight interval. Kept at full length (rather than
+    /// deduped) so `verify` can recompute every flip and confirm none were
+    /// dropped.
     pub reveal_positions: Vec<u64>,
-    /// Reveal indices corresponding to reveal positions
+    /// The coin-flip index that produced each entry of `reveal_positions`,
+    /// i.e. `reveal_indices[i]` is the `index` to pass back into
+    /// `coin_choice` to recompute `reveal_positions[i]`.
     pub reveal_indices: Vec<u64>,
+    /// The coin count this certificate committed to revealing, i.e.
+    /// `Builder::num_reveals(signed_weight, proven_weight, security_param)`
+    /// at build time. Stored explicitly (rather than leaving the verifier to
+    /// recompute it from scratch) so `verify` can cross-check its own
+    /// recomputation against what the builder actually used, rather than
+    /// silently trusting `reveal_positions.len()`.
+    pub num_reveals: usize,
+    /// Present iff this certificate was built in weight-hiding mode (see
+    /// [`crate::ccok::privacy`]): proof that every signer's committed
+    /// weight sums to `signed_weight` without opening any commitment
+    /// besides the ones already disclosed in `reveals`.
+    pub weight_proof: Option<WeightHidingProof>,
+}
+
+// --- Canonical wire format helpers -----------------------------------------
+//
+// `Certificate` and `Params` need a stable, language-agnostic byte encoding
+// (distinct from `bincode`, whose format is an internal implementation
+// detail) so a certificate built here can be handed to the reference Go
+// verifier and deserialize byte-for-byte identically. The format is simple
+// and deliberately un-clever: every length-delimited field is a big-endian
+// `u32` byte count followed by the raw bytes, every integer is fixed-width
+// big-endian, and every collection is a big-endian `u32` count followed by
+// that many encoded elements, always written in a fixed, deterministic
+// order (never `HashMap` iteration order).
+
+fn write_bytes(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn read_u32(bytes: &[u8], cursor: &mut usize) -> Result<u32, String> {
+    let end = *cursor + 4;
+    let slice = bytes
+        .get(*cursor..end)
+        .ok_or("unexpected end of input while reading u32")?;
+    *cursor = end;
+    Ok(u32::from_be_bytes(slice.try_into().unwrap()))
+}
+
+fn read_u64(bytes: &[u8], cursor: &mut usize) -> Result<u64, String> {
+    let end = *cursor + 8;
+    let slice = bytes
+        .get(*cursor..end)
+        .ok_or("unexpected end of input while reading u64")?;
+    *cursor = end;
+    Ok(u64::from_be_bytes(slice.try_into().unwrap()))
+}
+
+fn read_bytes(bytes: &[u8], cursor: &mut usize) -> Result<Vec<u8>, String> {
+    let len = read_u32(bytes, cursor)? as usize;
+    let end = *cursor + len;
+    let data = bytes
+        .get(*cursor..end)
+        .ok_or("unexpected end of input while reading length-prefixed bytes")?
+        .to_vec();
+    *cursor = end;
+    Ok(data)
+}
+
+/// Resolves a coin value to a signer position by binary searching a
+/// `(index, cumulative_weight)` prefix-sum table (sorted ascending by
+/// position and by cumulative weight, as built by
+/// [`Builder::cumulative_weights`] or [`Certificate::cumulative_weights_from_slots`]):
+/// `O(log N)` per lookup against an already-built table, instead of
+/// rescanning every signer's weight from scratch.
+fn binary_search_cum_weights(coin_value: u64, cum_weights: &[(usize, u64)]) -> Result<u64, String> {
+    if cum_weights.is_empty() {
+        return Err("No signatures available".to_string());
+    }
+
+    let mut lo = 0;
+    let mut hi = cum_weights.len();
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let (_, weight_mid) = cum_weights[mid];
+        if coin_value < weight_mid {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    if lo < cum_weights.len() {
+        Ok(cum_weights[lo].0 as u64)
+    } else {
+        Err("Could not find position for coin value".to_string())
+    }
+}
+
+fn write_participant(buf: &mut Vec<u8>, participant: &Participant) {
+    write_bytes(buf, participant.public_key.as_bytes());
+    buf.extend_from_slice(&participant.weight.to_be_bytes());
+    match &participant.key_schedule_root {
+        Some(root) => {
+            buf.push(1);
+            write_bytes(buf, root.as_bytes());
+        }
+        None => buf.push(0),
+    }
+    match &participant.weight_commitment {
+        Some(commitment) => {
+            buf.push(1);
+            let bytes =
+                bincode::serialize(commitment).expect("WeightCommitment always serializes");
+            write_bytes(buf, &bytes);
+        }
+        None => buf.push(0),
+    }
+}
+
+fn read_participant(bytes: &[u8], cursor: &mut usize) -> Result<Participant, String> {
+    let public_key_bytes = read_bytes(bytes, cursor)?;
+    let public_key = String::from_utf8(public_key_bytes)
+        .map_err(|e| format!("invalid participant public key utf-8: {}", e))?;
+    let weight = read_u64(bytes, cursor)?;
+    let tag = *bytes
+        .get(*cursor)
+        .ok_or("unexpected end of input while reading participant key-schedule tag")?;
+    *cursor += 1;
+    let key_schedule_root = match tag {
+        0 => None,
+        1 => {
+            let root_bytes = read_bytes(bytes, cursor)?;
+            Some(
+                String::from_utf8(root_bytes)
+                    .map_err(|e| format!("invalid key schedule root utf-8: {}", e))?,
+            )
+        }
+        other => return Err(format!("invalid participant key-schedule tag: {}", other)),
+    };
+    let weight_commitment_tag = *bytes
+        .get(*cursor)
+        .ok_or("unexpected end of input while reading participant weight-commitment tag")?;
+    *cursor += 1;
+    let weight_commitment = match weight_commitment_tag {
+        0 => None,
+        1 => {
+            let commitment_bytes = read_bytes(bytes, cursor)?;
+            Some(
+                bincode::deserialize(&commitment_bytes)
+                    .map_err(|e| format!("invalid weight commitment encoding: {}", e))?,
+            )
+        }
+        other => return Err(format!("invalid participant weight-commitment tag: {}", other)),
+    };
+    Ok(Participant {
+        public_key,
+        weight,
+        key_schedule_root,
+        weight_commitment,
+    })
+}
+
+// `EpochKeyReveal::proof` is a `MerkleMultiProof`, which already has its own
+// `Serialize`/`Deserialize` impl used elsewhere via `bincode` (see
+// `merkle::MerkleMultiProof`'s own round-trip test). Rather than hand-write
+// a third encoding for it here, it travels as an opaque bincode blob inside
+// this otherwise fully explicit wire format.
+fn write_epoch_key_reveal(buf: &mut Vec<u8>, reveal: &EpochKeyReveal) {
+    write_bytes(buf, reveal.pubkey.as_bytes());
+    let proof_bytes = bincode::serialize(&reveal.proof).expect("MerkleMultiProof always serializes");
+    write_bytes(buf, &proof_bytes);
+}
+
+fn read_epoch_key_reveal(bytes: &[u8], cursor: &mut usize) -> Result<EpochKeyReveal, String> {
+    let pubkey_bytes = read_bytes(bytes, cursor)?;
+    let pubkey = String::from_utf8(pubkey_bytes)
+        .map_err(|e| format!("invalid epoch pubkey utf-8: {}", e))?;
+    let proof_bytes = read_bytes(bytes, cursor)?;
+    let proof: MerkleMultiProof = bincode::deserialize(&proof_bytes)
+        .map_err(|e| format!("invalid epoch key proof encoding: {}", e))?;
+    Ok(EpochKeyReveal { pubkey, proof })
+}
+
+fn write_sig_slot(buf: &mut Vec<u8>, slot: &SigSlot) {
+    match &slot.signature {
+        Some(sig) => {
+            buf.push(1);
+            write_bytes(buf, &sig.0);
+        }
+        None => buf.push(0),
+    }
+    buf.extend_from_slice(&slot.accumulated_weight.to_be_bytes());
+    match &slot.epoch_key {
+        Some(epoch_key) => {
+            buf.push(1);
+            write_epoch_key_reveal(buf, epoch_key);
+        }
+        None => buf.push(0),
+    }
+}
+
+fn read_sig_slot(bytes: &[u8], cursor: &mut usize) -> Result<SigSlot, String> {
+    let tag = *bytes
+        .get(*cursor)
+        .ok_or("unexpected end of input while reading sig slot tag")?;
+    *cursor += 1;
+    let signature = match tag {
+        0 => None,
+        1 => Some(SerializableSignature(read_bytes(bytes, cursor)?)),
+        other => return Err(format!("invalid sig slot tag: {}", other)),
+    };
+    let accumulated_weight = read_u64(bytes, cursor)?;
+    let epoch_key_tag = *bytes
+        .get(*cursor)
+        .ok_or("unexpected end of input while reading sig slot epoch-key tag")?;
+    *cursor += 1;
+    let epoch_key = match epoch_key_tag {
+        0 => None,
+        1 => Some(read_epoch_key_reveal(bytes, cursor)?),
+        other => return Err(format!("invalid sig slot epoch-key tag: {}", other)),
+    };
+    Ok(SigSlot {
+        signature,
+        accumulated_weight,
+        epoch_key,
+    })
+}
+
+fn write_reveal(buf: &mut Vec<u8>, reveal: &Reveal) {
+    write_sig_slot(buf, &reveal.sig_slot);
+    write_participant(buf, &reveal.party);
+    match &reveal.weight_opening {
+        Some(opening) => {
+            buf.push(1);
+            let bytes = bincode::serialize(opening).expect("WeightOpening always serializes");
+            write_bytes(buf, &bytes);
+        }
+        None => buf.push(0),
+    }
+}
+
+fn read_reveal(bytes: &[u8], cursor: &mut usize) -> Result<Reveal, String> {
+    let sig_slot = read_sig_slot(bytes, cursor)?;
+    let party = read_participant(bytes, cursor)?;
+    let tag = *bytes
+        .get(*cursor)
+        .ok_or("unexpected end of input while reading reveal weight-opening tag")?;
+    *cursor += 1;
+    let weight_opening = match tag {
+        0 => None,
+        1 => {
+            let opening_bytes = read_bytes(bytes, cursor)?;
+            Some(
+                bincode::deserialize(&opening_bytes)
+                    .map_err(|e| format!("invalid weight opening encoding: {}", e))?,
+            )
+        }
+        other => return Err(format!("invalid reveal weight-opening tag: {}", other)),
+    };
+    Ok(Reveal {
+        sig_slot,
+        party,
+        weight_opening,
+    })
+}
+
+/// Packs `set_positions` into a big-endian bitfield covering `[0, total)`,
+/// one bit per slot (MSB-first within each byte), so marking which of
+/// `total` signer positions were revealed costs `total` bits instead of a
+/// `u64` key per revealed entry.
+fn write_signer_bitfield(buf: &mut Vec<u8>, total: usize, set_positions: &[u64]) {
+    let mut field = vec![0u8; total.div_ceil(8)];
+    for &pos in set_positions {
+        let idx = pos as usize;
+        field[idx / 8] |= 1 << (7 - (idx % 8));
+    }
+    write_bytes(buf, &field);
+}
+
+/// Decodes a bitfield written by [`write_signer_bitfield`], returning the
+/// ascending positions whose bit is set. Rejects a bitfield whose byte
+/// length doesn't match `total` slots.
+fn read_signer_bitfield(bytes: &[u8], cursor: &mut usize, total: usize) -> Result<Vec<u64>, String> {
+    let field = read_bytes(bytes, cursor)?;
+    let expected_len = total.div_ceil(8);
+    if field.len() != expected_len {
+        return Err(format!(
+            "signer bitfield is {} bytes, expected {} for {} slots",
+            field.len(),
+            expected_len,
+            total
+        ));
+    }
+    let mut positions = Vec::new();
+    for idx in 0..total {
+        if field[idx / 8] & (1 << (7 - (idx % 8))) != 0 {
+            positions.push(idx as u64);
+        }
+    }
+    Ok(positions)
+}
+
+impl Params {
+    /// Canonical, length-prefixed byte encoding of the certificate
+    /// parameters. See the module-level wire format notes above `Params`'s
+    /// sibling [`Certificate::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &self.msg);
+        buf.extend_from_slice(&self.proven_weight.to_be_bytes());
+        buf.extend_from_slice(&self.security_param.to_be_bytes());
+        buf.extend_from_slice(&self.epoch.to_be_bytes());
+        buf
+    }
+
+    /// Decodes a `Params` previously produced by [`Params::to_bytes`],
+    /// rejecting truncated or trailing-garbage input.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut cursor = 0usize;
+        let msg = read_bytes(bytes, &mut cursor)?;
+        let proven_weight = read_u64(bytes, &mut cursor)?;
+        let security_param = read_u32(bytes, &mut cursor)?;
+        let epoch = read_u64(bytes, &mut cursor)?;
+        if cursor != bytes.len() {
+            return Err(format!(
+                "{} trailing bytes after decoding params",
+                bytes.len() - cursor
+            ));
+        }
+        Ok(Params {
+            msg,
+            proven_weight,
+            security_param,
+            epoch,
+        })
+    }
 }
 
 impl Certificate {
@@ -96,7 +512,295 @@ impl Certificate {
         let party_size: usize = self.party_proofs.iter().map(|p| p.len()).sum();
         (sig_size, party_size)
     }
+
+    /// Canonical, length-prefixed, deterministic-order byte encoding of the
+    /// certificate, including a self-describing encoding of the embedded
+    /// Merkle proofs (reveal positions, reveal leaves, proof hashes, and the
+    /// total leaf count) so a certificate built here deserializes and
+    /// verifies byte-for-byte identically under the reference Go verifier.
+    /// `reveals` is a `HashMap` with unspecified iteration order and would
+    /// otherwise need an explicit `u64` key per entry; instead it is written
+    /// as a `total_sigs`-bit signer bitfield (1 bit per slot, set iff that
+    /// position was revealed) followed by the revealed `Reveal` payloads in
+    /// ascending position order, so each `Reveal` payload is written once
+    /// per *distinct* revealed position rather than once per coin flip. The
+    /// per-flip `reveal_positions` and `reveal_indices` still follow as
+    /// plain `u64` lists, one entry per coin flip.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &self.sig_commit);
+        buf.extend_from_slice(&self.signed_weight.to_be_bytes());
+        buf.extend_from_slice(&(self.total_sigs as u64).to_be_bytes());
+
+        let mut distinct_positions: Vec<u64> = self.reveals.keys().copied().collect();
+        distinct_positions.sort_unstable();
+        write_signer_bitfield(&mut buf, self.total_sigs, &distinct_positions);
+        for pos in &distinct_positions {
+            let reveal = self
+                .reveals
+                .get(pos)
+                .expect("bitfield position missing from reveals");
+            write_reveal(&mut buf, reveal);
+        }
+
+        buf.extend_from_slice(&(self.sig_proofs.len() as u32).to_be_bytes());
+        for proof in &self.sig_proofs {
+            write_bytes(&mut buf, proof);
+        }
+
+        buf.extend_from_slice(&(self.party_proofs.len() as u32).to_be_bytes());
+        for proof in &self.party_proofs {
+            write_bytes(&mut buf, proof);
+        }
+
+        // Unlike the signer bitfield above (which only needs to know which
+        // positions were revealed at all), the coin-choice check in `verify`
+        // needs the exact, possibly-repeating position each coin flip
+        // landed on, so `reveal_positions` is written out in full here.
+        buf.extend_from_slice(&(self.reveal_positions.len() as u32).to_be_bytes());
+        for pos in &self.reveal_positions {
+            buf.extend_from_slice(&pos.to_be_bytes());
+        }
+
+        buf.extend_from_slice(&(self.reveal_indices.len() as u32).to_be_bytes());
+        for index in &self.reveal_indices {
+            buf.extend_from_slice(&index.to_be_bytes());
+        }
+
+        buf.extend_from_slice(&(self.num_reveals as u64).to_be_bytes());
+
+        match &self.weight_proof {
+            Some(proof) => {
+                buf.push(1);
+                let bytes = bincode::serialize(proof).expect("WeightHidingProof always serializes");
+                write_bytes(&mut buf, &bytes);
+            }
+            None => buf.push(0),
+        }
+
+        buf
+    }
+
+    /// Decodes a `Certificate` previously produced by
+    /// [`Certificate::to_bytes`], rejecting truncated or trailing-garbage
+    /// input and a signer bitfield whose length doesn't match `total_sigs`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut cursor = 0usize;
+        let sig_commit = read_bytes(bytes, &mut cursor)?;
+        let signed_weight = read_u64(bytes, &mut cursor)?;
+        let total_sigs = read_u64(bytes, &mut cursor)? as usize;
+
+        let distinct_positions = read_signer_bitfield(bytes, &mut cursor, total_sigs)?;
+        let mut reveals = HashMap::with_capacity(distinct_positions.len());
+        for &pos in &distinct_positions {
+            let reveal = read_reveal(bytes, &mut cursor)?;
+            reveals.insert(pos, reveal);
+        }
+
+        let sig_proof_count = read_u32(bytes, &mut cursor)? as usize;
+        let mut sig_proofs = Vec::with_capacity(sig_proof_count);
+        for _ in 0..sig_proof_count {
+            sig_proofs.push(read_bytes(bytes, &mut cursor)?);
+        }
+
+        let party_proof_count = read_u32(bytes, &mut cursor)? as usize;
+        let mut party_proofs = Vec::with_capacity(party_proof_count);
+        for _ in 0..party_proof_count {
+            party_proofs.push(read_bytes(bytes, &mut cursor)?);
+        }
+
+        let position_count = read_u32(bytes, &mut cursor)? as usize;
+        let mut reveal_positions = Vec::with_capacity(position_count);
+        for _ in 0..position_count {
+            reveal_positions.push(read_u64(bytes, &mut cursor)?);
+        }
+
+        let index_count = read_u32(bytes, &mut cursor)? as usize;
+        let mut reveal_indices = Vec::with_capacity(index_count);
+        for _ in 0..index_count {
+            reveal_indices.push(read_u64(bytes, &mut cursor)?);
+        }
+
+        let num_reveals = read_u64(bytes, &mut cursor)? as usize;
+
+        let weight_proof_tag = *bytes
+            .get(cursor)
+            .ok_or("unexpected end of input while reading certificate weight-proof tag")?;
+        cursor += 1;
+        let weight_proof = match weight_proof_tag {
+            0 => None,
+            1 => {
+                let proof_bytes = read_bytes(bytes, &mut cursor)?;
+                Some(
+                    bincode::deserialize(&proof_bytes)
+                        .map_err(|e| format!("invalid weight-hiding proof encoding: {}", e))?,
+                )
+            }
+            other => return Err(format!("invalid certificate weight-proof tag: {}", other)),
+        };
+
+        if cursor != bytes.len() {
+            return Err(format!(
+                "{} trailing bytes after decoding certificate",
+                bytes.len() - cursor
+            ));
+        }
+
+        Ok(Certificate {
+            sig_commit,
+            signed_weight,
+            total_sigs,
+            reveals,
+            sig_proofs,
+            party_proofs,
+            reveal_positions,
+            reveal_indices,
+            num_reveals,
+            weight_proof,
+        })
+    }
+}
+
+/// A self-contained, third-party-verifiable attestation that a weighted
+/// committee signed a specific `outcome` — the compact-certificate
+/// analogue of a DLC oracle-message attestation. Bundles the `Certificate`
+/// with the `Params` fields (`outcome`, `proven_weight`, `security_param`,
+/// `epoch`) needed to reconstruct a full `Params` and re-run
+/// `Certificate::verify` from `encode()`'d bytes alone, with no access to
+/// the original `Builder` or its `sigs`.
+#[derive(Debug, Clone)]
+pub struct Attestation {
+    /// The outcome message the committee attested to.
+    pub outcome: Vec<u8>,
+    pub proven_weight: u64,
+    pub security_param: u32,
+    pub epoch: u64,
+    pub certificate: Certificate,
+}
+
+impl Attestation {
+    pub fn new(
+        outcome: Vec<u8>,
+        proven_weight: u64,
+        security_param: u32,
+        epoch: u64,
+        certificate: Certificate,
+    ) -> Self {
+        Self {
+            outcome,
+            proven_weight,
+            security_param,
+            epoch,
+            certificate,
+        }
+    }
+
+    /// The `Params` a verifier needs to check this attestation's
+    /// certificate against its outcome and threshold.
+    pub fn params(&self) -> Params {
+        Params {
+            msg: self.outcome.clone(),
+            proven_weight: self.proven_weight,
+            security_param: self.security_param,
+            epoch: self.epoch,
+        }
+    }
+
+    /// Canonical, length-prefixed byte encoding: `outcome`, then
+    /// `proven_weight`/`security_param`/`epoch`, then the embedded
+    /// certificate's own [`Certificate::to_bytes`] encoding. Uses the same
+    /// wire-format conventions as `Certificate::to_bytes` (see the module
+    /// doc comment above `write_bytes`), so an external contract or oracle
+    /// consumer parsing one can parse the other with identical primitives.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &self.outcome);
+        buf.extend_from_slice(&self.proven_weight.to_be_bytes());
+        buf.extend_from_slice(&self.security_param.to_be_bytes());
+        buf.extend_from_slice(&self.epoch.to_be_bytes());
+        buf.extend_from_slice(&self.certificate.to_bytes());
+        buf
+    }
+
+    /// Decodes an `Attestation` previously produced by
+    /// [`Attestation::encode`], rejecting truncated input or trailing
+    /// garbage after the embedded certificate.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut cursor = 0usize;
+        let outcome = read_bytes(bytes, &mut cursor)?;
+        let proven_weight = read_u64(bytes, &mut cursor)?;
+        let security_param = read_u32(bytes, &mut cursor)?;
+        let epoch = read_u64(bytes, &mut cursor)?;
+        let certificate = Certificate::from_bytes(&bytes[cursor..])?;
+
+        Ok(Self {
+            outcome,
+            proven_weight,
+            security_param,
+            epoch,
+            certificate,
+        })
+    }
+}
+
+/// Verifies `attestation` purely from its own fields plus `pubkeys_commit`
+/// (the committee's party-tree root): reconstructs the `Params` it
+/// attests to and re-runs `Certificate::verify`, including re-deriving
+/// every coin-flip reveal position and checking each revealed signature
+/// and Merkle proof. Never touches `Builder::sigs` or any other
+/// builder-side state, so a third party holding only `pubkeys_commit` and
+/// the attestation bytes can validate the outcome on its own.
+pub fn verify_attestation(pubkeys_commit: &[u8], attestation: &Attestation) -> Result<bool, String> {
+    attestation.certificate.verify(&attestation.params(), pubkeys_commit)
+}
+
+/// Confirms that `validator_pubkey` signed off as part of `cert`, and
+/// returns its weight if so — using only the certificate and the party
+/// tree's root, never a `Blockchain`/`Validator`'s full state. Unlike
+/// `Certificate::verify`, this doesn't re-check signatures, coin-flip
+/// reveals, or a weight threshold; it only confirms that the validator's
+/// claimed reveal is really committed into `party_tree_root`, which is all a
+/// light client holding just a block header and the attached certificate
+/// can check for itself about one specific validator's participation.
+pub fn verify_participant_membership(
+    cert: &Certificate,
+    party_tree_root: &[u8],
+    validator_pubkey: &str,
+) -> Result<Option<u64>, String> {
+    let claimed_weight = match cert
+        .reveals
+        .values()
+        .find(|reveal| reveal.party.public_key == validator_pubkey)
+    {
+        Some(reveal) => Certificate::effective_weight(reveal)?,
+        None => return Ok(None),
+    };
+
+    let mut distinct_positions: Vec<&u64> = cert.reveals.keys().collect();
+    distinct_positions.sort_unstable();
+
+    let mut party_pairs = Vec::with_capacity(distinct_positions.len());
+    for pos in &distinct_positions {
+        let reveal = cert
+            .reveals
+            .get(pos)
+            .ok_or_else(|| format!("Missing reveal for position {}", pos))?;
+        let leaf = MerkleTreeBuilder::<CustomHasher>::hash_leaf(&reveal.party)?;
+        party_pairs.push((**pos as usize, leaf));
+    }
+
+    if !MerkleTreeBuilder::verify_multiproof(
+        party_tree_root,
+        &cert.party_proofs,
+        &party_pairs,
+        cert.total_sigs,
+    ) {
+        return Ok(None);
+    }
+
+    Ok(Some(claimed_weight))
 }
+
 /// Builder for creating certificates
 #[derive(Debug)]
 pub struct Builder {
@@ -119,7 +823,8 @@ impl Builder {
             sigs: vec![
                 SigSlot {
                     signature: None,
-                    accumulated_weight: 0
+                    accumulated_weight: 0,
+                    epoch_key: None,
                 };
                 participants.len()
             ],
@@ -129,8 +834,46 @@ impl Builder {
         }
     }
 
-    /// Add a signature from a participant
+    /// Add a signature from a participant signing with their static
+    /// `public_key`. Forward-secure participants (those with a
+    /// `key_schedule_root`) must instead call
+    /// [`add_signature_with_epoch_key`](Self::add_signature_with_epoch_key).
     pub fn add_signature(&mut self, pos: usize, signature: Signature) -> Result<(), String> {
+        self.insert_signature(pos, signature, None)
+    }
+
+    /// Add a signature from a forward-secure participant signing epoch
+    /// `self.params.epoch` with `epoch_pubkey`, proven to be that epoch's
+    /// leaf of their committed `key_schedule_root` via `epoch_proof`.
+    pub fn add_signature_with_epoch_key(
+        &mut self,
+        pos: usize,
+        signature: Signature,
+        epoch_pubkey: String,
+        epoch_proof: MerkleMultiProof,
+    ) -> Result<(), String> {
+        if pos < self.participants.len() && self.participants[pos].key_schedule_root.is_none() {
+            return Err(format!(
+                "Participant {} is not in forward-secure mode",
+                pos
+            ));
+        }
+        self.insert_signature(
+            pos,
+            signature,
+            Some(EpochKeyReveal {
+                pubkey: epoch_pubkey,
+                proof: epoch_proof,
+            }),
+        )
+    }
+
+    fn insert_signature(
+        &mut self,
+        pos: usize,
+        signature: Signature,
+        epoch_key: Option<EpochKeyReveal>,
+    ) -> Result<(), String> {
         // Validate position
         if pos >= self.participants.len() {
             return Err(format!("Invalid participant position: {}", pos));
@@ -148,17 +891,51 @@ impl Builder {
 
         // Add signature and update weights
         self.sigs[pos].signature = Some(SerializableSignature::from(signature));
+        self.sigs[pos].epoch_key = epoch_key;
         self.signed_weight += self.participants[pos].weight;
 
-        // Update accumulated weights
-        if pos > 0 {
-            self.sigs[pos].accumulated_weight =
-                self.sigs[pos - 1].accumulated_weight + self.participants[pos - 1].weight;
+        // Recompute every accumulated_weight as the true prefix sum over
+        // signed slots, since signatures can arrive in any position order
+        // and the old `sigs[pos - 1]`-relative update only produced correct
+        // values when they arrived in ascending position order.
+        let mut cumulative = 0u64;
+        for i in 0..self.sigs.len() {
+            self.sigs[i].accumulated_weight = cumulative;
+            if self.sigs[i].signature.is_some() {
+                cumulative += self.participants[i].weight;
+            }
         }
 
         Ok(())
     }
 
+    /// How many independent coins a certificate over `signed_weight` must
+    /// reveal to prove at least `proven_weight` of that weight signed, to
+    /// within `sec_param_bits` bits of soundness. Each coin lands on a
+    /// uniformly random slot in `[0, signed_weight)`; a forger who only
+    /// controls `proven_weight` out of `signed_weight` survives one flip
+    /// with probability `proven_weight / signed_weight`, so `q` independent
+    /// flips bound the forgery probability by `(proven_weight /
+    /// signed_weight)^q`. Solving `(proven_weight / signed_weight)^q <=
+    /// 2^-sec_param_bits` for the smallest integer `q` gives `q =
+    /// ceil(sec_param_bits / log2(signed_weight / proven_weight))` (the
+    /// Micali compact-certificate bound).
+    pub fn num_reveals(
+        signed_weight: u64,
+        proven_weight: u64,
+        sec_param_bits: u32,
+    ) -> Result<usize, String> {
+        if signed_weight <= proven_weight {
+            return Err(format!(
+                "signed weight must exceed proven weight: {} <= {}",
+                signed_weight, proven_weight
+            ));
+        }
+        let ratio_bits = (signed_weight as f64 / proven_weight as f64).log2();
+        let q = (sec_param_bits as f64 / ratio_bits).ceil() as usize;
+        Ok(std::cmp::max(1, q))
+    }
+
     /// Build the certificate once enough signatures are collected
     pub fn build(&self) -> Result<Certificate, String> {
         // Check if we have enough weight
@@ -177,40 +954,150 @@ impl Builder {
         let mut party_tree = MerkleTreeBuilder::new();
         party_tree.build(&self.participants)?;
 
-        // Calculate the fraction of weight not required for the proof
-        let fraction = 1.0 - (self.params.proven_weight as f64 / self.signed_weight as f64);
-        // K is a tuning constant (here chosen as 0.5) to adjust the number of reveals
-        let num_reveals = std::cmp::max(1, ((self.params.security_param as f64) * fraction * 0.5).ceil() as usize);
-
-        // Instead of collecting unsorted reveals, collect reveal information as (position, coin_index)
-        let mut reveal_map = HashMap::new();
-        let mut reveal_info: Vec<(usize, u64)> = Vec::new();
-        
-        // Choose positions to reveal using coin flips
+        let num_reveals = Self::num_reveals(
+            self.signed_weight,
+            self.params.proven_weight,
+            self.params.security_param,
+        )?;
+
+        // One (position, coin_index) pair per coin flip `i`, so a verifier
+        // that only trusts `reveal_indices` can recompute every coin the
+        // prover was obligated to reveal, even when several flips land on
+        // the same position (the underlying slot only needs to be proven
+        // into the Merkle tree once, so `reveal_map` still dedupes that).
+        let mut reveal_map: HashMap<u64, Reveal> = HashMap::new();
+        let mut reveal_positions: Vec<u64> = Vec::with_capacity(num_reveals);
+        let mut reveal_indices: Vec<u64> = Vec::with_capacity(num_reveals);
+
+        let cum_weights = self.cumulative_weights();
         for i in 0..num_reveals {
             let choice = self.coin_choice(i as u64, &sig_tree.root());
-            let pos = self.find_coin_position(choice)? as usize;
-
-            if !reveal_map.contains_key(&(pos as u64)) {
-                reveal_map.insert(
-                    pos as u64,
-                    Reveal {
-                        sig_slot: self.sigs[pos].clone(),
-                        party: self.participants[pos].clone(),
-                    },
-                );
-                reveal_info.push((pos, i as u64));
+            let pos = Self::find_coin_position(choice, &cum_weights)? as usize;
+
+            reveal_map.entry(pos as u64).or_insert_with(|| Reveal {
+                sig_slot: self.sigs[pos].clone(),
+                party: self.participants[pos].clone(),
+                weight_opening: None,
+            });
+            reveal_positions.push(pos as u64);
+            reveal_indices.push(i as u64);
+        }
+
+        // Distinct, sorted positions are all the Merkle trees need to
+        // prove: there's no point proving the same leaf twice.
+        let mut distinct_positions: Vec<usize> =
+            reveal_map.keys().map(|&pos| pos as usize).collect();
+        distinct_positions.sort_unstable();
+
+        let sig_proofs = sig_tree.prove(&distinct_positions);
+        let party_proofs = party_tree.prove(&distinct_positions);
+
+        Ok(Certificate {
+            sig_commit: sig_tree.root(),
+            signed_weight: self.signed_weight,
+            total_sigs: self.sigs.len(),
+            reveals: reveal_map,
+            sig_proofs,
+            party_proofs,
+            reveal_positions,
+            reveal_indices,
+            num_reveals,
+            weight_proof: None,
+        })
+    }
+
+    /// Builds a certificate in weight-hiding mode (see
+    /// [`crate::ccok::privacy`]): every signed participant's weight is
+    /// redacted to a [`WeightCommitment`] before it gets hashed into the
+    /// party tree, `blindings` supplies each signed position's blinding
+    /// factor, and every coin-sampled slot gets a [`WeightOpening`] attached
+    /// so `Certificate::verify` can still check its coin interval. Requires
+    /// a blinding for every position in `self.sigs` that has a signature.
+    pub fn build_weight_hiding(
+        &self,
+        blindings: &HashMap<usize, k256::Scalar>,
+    ) -> Result<Certificate, String> {
+        if self.signed_weight < self.params.proven_weight {
+            return Err(format!(
+                "Insufficient signed weight: {} < {}",
+                self.signed_weight, self.params.proven_weight
+            ));
+        }
+
+        let mut sig_tree = MerkleTreeBuilder::new();
+        sig_tree.build(&self.sigs)?;
+
+        // Redact every signed participant's weight down to a commitment
+        // before it ever gets hashed into the party tree.
+        let mut redacted_participants = self.participants.clone();
+        let mut signer_commitments: Vec<(usize, WeightCommitment)> = Vec::new();
+        for (pos, slot) in self.sigs.iter().enumerate() {
+            if slot.signature.is_none() {
+                continue;
             }
+            let blinding = blindings
+                .get(&pos)
+                .ok_or_else(|| format!("missing weight blinding for signed position {}", pos))?;
+            let commitment = WeightCommitment::commit(self.participants[pos].weight, blinding);
+            redacted_participants[pos].weight = 0;
+            redacted_participants[pos].weight_commitment = Some(commitment.clone());
+            signer_commitments.push((pos, commitment));
         }
-        
-        // Sort reveal_info by position
-        reveal_info.sort_by_key(|(pos, _)| *pos);
-        let sorted_positions: Vec<usize> = reveal_info.iter().map(|(pos, _)| *pos).collect();
-        let sorted_coin_indices: Vec<u64> = reveal_info.iter().map(|(_, coin_idx)| *coin_idx).collect();
+        signer_commitments.sort_by_key(|(pos, _)| *pos);
 
-        // Generate proofs for both signatures and participants using sorted positions
-        let sig_proofs = sig_tree.prove(&sorted_positions);
-        let party_proofs = party_tree.prove(&sorted_positions);
+        let mut party_tree = MerkleTreeBuilder::new();
+        party_tree.build(&redacted_participants)?;
+
+        // Unlike `build()`, which trusts the externally supplied
+        // `self.party_tree_root` (committed over plaintext participants
+        // before the builder even existed), weight-hiding redacts
+        // participants here, so the root the coin choice and proofs must
+        // agree with is this freshly built redacted tree's root instead.
+        let redacted_party_root = party_tree.root();
+
+        let num_reveals = Self::num_reveals(
+            self.signed_weight,
+            self.params.proven_weight,
+            self.params.security_param,
+        )?;
+
+        let mut reveal_map: HashMap<u64, Reveal> = HashMap::new();
+        let mut reveal_positions: Vec<u64> = Vec::with_capacity(num_reveals);
+        let mut reveal_indices: Vec<u64> = Vec::with_capacity(num_reveals);
+
+        let cum_weights = self.cumulative_weights();
+        for i in 0..num_reveals {
+            let choice = self.weight_hiding_coin_choice(i as u64, &sig_tree.root(), &redacted_party_root);
+            let pos = Self::find_coin_position(choice, &cum_weights)? as usize;
+
+            reveal_map.entry(pos as u64).or_insert_with(|| Reveal {
+                sig_slot: self.sigs[pos].clone(),
+                party: redacted_participants[pos].clone(),
+                weight_opening: Some(WeightOpening::new(
+                    self.participants[pos].weight,
+                    blindings[&pos],
+                )),
+            });
+            reveal_positions.push(pos as u64);
+            reveal_indices.push(i as u64);
+        }
+
+        let mut distinct_positions: Vec<usize> =
+            reveal_map.keys().map(|&pos| pos as usize).collect();
+        distinct_positions.sort_unstable();
+
+        let sig_proofs = sig_tree.prove(&distinct_positions);
+        let party_proofs = party_tree.prove(&distinct_positions);
+
+        let mut blinding_iter = signer_commitments.iter().map(|(pos, _)| blindings[pos]);
+        let mut blinding_sum = blinding_iter
+            .next()
+            .ok_or("no signers to build a weight-hiding proof for")?;
+        for blinding in blinding_iter {
+            blinding_sum = blinding_sum + blinding;
+        }
+        let transcript: &[&[u8]] = &[&sig_tree.root(), &redacted_party_root, &self.params.msg];
+        let sum_proof = SumOpeningProof::prove(self.signed_weight, &blinding_sum, transcript);
 
         Ok(Certificate {
             sig_commit: sig_tree.root(),
@@ -219,8 +1106,13 @@ impl Builder {
             reveals: reveal_map,
             sig_proofs,
             party_proofs,
-            reveal_positions: sorted_positions.iter().map(|&p| p as u64).collect(),
-            reveal_indices: sorted_coin_indices,
+            reveal_positions,
+            reveal_indices,
+            num_reveals,
+            weight_proof: Some(WeightHidingProof {
+                commitments: signer_commitments.into_iter().map(|(_, c)| c).collect(),
+                sum_proof,
+            }),
         })
     }
 
@@ -233,6 +1125,29 @@ impl Builder {
         hasher.update(sig_commit);
         hasher.update(&self.party_tree_root);
         hasher.update(&self.params.msg);
+        hasher.update(&self.params.epoch.to_le_bytes());
+
+        let hash = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&hash[0..8]);
+
+        u64::from_le_bytes(bytes) % self.signed_weight
+    }
+
+    /// Same coin derivation as `coin_choice`, but against an explicit
+    /// `party_tree_root` rather than `self.party_tree_root` — needed by
+    /// [`Self::build_weight_hiding`], whose committed party root (over
+    /// redacted participants) only exists once redaction has happened and
+    /// so can't be the precommitted field the non-hiding path relies on.
+    fn weight_hiding_coin_choice(&self, index: u64, sig_commit: &[u8], party_tree_root: &[u8]) -> u64 {
+        let mut hasher = Keccak256::new();
+        hasher.update(&index.to_le_bytes());
+        hasher.update(&self.signed_weight.to_le_bytes());
+        hasher.update(&self.params.proven_weight.to_le_bytes());
+        hasher.update(sig_commit);
+        hasher.update(party_tree_root);
+        hasher.update(&self.params.msg);
+        hasher.update(&self.params.epoch.to_le_bytes());
 
         let hash = hasher.finalize();
         let mut bytes = [0u8; 8];
@@ -241,9 +1156,12 @@ impl Builder {
         u64::from_le_bytes(bytes) % self.signed_weight
     }
 
-    // Updated: Find the participant position based on coin value using cumulative weights of signed slots
-    fn find_coin_position(&self, coin_value: u64) -> Result<u64, String> {
-        // Build a vector of (index, cumulative_weight) for only signed slots
+    /// Builds the `(index, cumulative_weight)` prefix-sum table over signed
+    /// slots once, so a whole certificate's worth of coin flips can each
+    /// resolve to a position in `O(log N)` via [`Self::find_coin_position`]
+    /// instead of every flip re-scanning all of `self.sigs` (`O(N)` each,
+    /// `O(N*q)` total for `q` flips).
+    fn cumulative_weights(&self) -> Vec<(usize, u64)> {
         let mut cum_weights = Vec::new();
         let mut cum = 0u64;
         for (i, slot) in self.sigs.iter().enumerate() {
@@ -252,30 +1170,14 @@ impl Builder {
                 cum_weights.push((i, cum));
             }
         }
+        cum_weights
+    }
 
-        // Check that there is at least one signed slot
-        if cum_weights.is_empty() {
-            return Err("No signatures available".to_string());
-        }
-
-        // Perform binary search on cum_weights to find the first slot where cumulative weight exceeds coin_value
-        let mut lo = 0;
-        let mut hi = cum_weights.len();
-        while lo < hi {
-            let mid = (lo + hi) / 2;
-            let (_, weight_mid) = cum_weights[mid];
-            if coin_value < weight_mid {
-                hi = mid;
-            } else {
-                lo = mid + 1;
-            }
-        }
-
-        if lo < cum_weights.len() {
-            Ok(cum_weights[lo].0 as u64)
-        } else {
-            Err("Could not find position for coin value".to_string())
-        }
+    /// Finds the first signed slot whose cumulative weight exceeds
+    /// `coin_value`, binary searching `cum_weights` (as built by
+    /// [`Self::cumulative_weights`]).
+    fn find_coin_position(coin_value: u64, cum_weights: &[(usize, u64)]) -> Result<u64, String> {
+        binary_search_cum_weights(coin_value, cum_weights)
     }
 }
 
@@ -300,11 +1202,17 @@ impl Certificate {
         let mut participants = Vec::new();
         let mut positions = Vec::new();
 
+        // The same position can appear more than once in `reveal_positions`
+        // (several coin flips can land on the same slot), but the slot only
+        // needs its signature and Merkle membership checked once.
+        let mut distinct_positions: Vec<&u64> = self.reveals.keys().collect();
+        distinct_positions.sort_unstable();
+
         println!(
             "Verifying {} revealed signatures...",
-            self.reveal_positions.len()
+            distinct_positions.len()
         );
-        for pos in &self.reveal_positions {
+        for pos in distinct_positions {
             let reveal = self
                 .reveals
                 .get(pos)
@@ -319,8 +1227,44 @@ impl Certificate {
                 }
             };
 
+            // Forward-secure participants sign with a rotating per-epoch
+            // key instead of `party.public_key`; check it against the
+            // committed schedule root before trusting it for the
+            // Dilithium verification below.
+            let effective_public_key = match &reveal.party.key_schedule_root {
+                Some(schedule_root) => {
+                    let epoch_key = match &reveal.sig_slot.epoch_key {
+                        Some(epoch_key) => epoch_key,
+                        None => {
+                            println!(
+                                "Forward-secure participant {} did not reveal an epoch key",
+                                pos
+                            );
+                            return Ok(false);
+                        }
+                    };
+                    let schedule_root_bytes = hex::decode(schedule_root)
+                        .map_err(|e| format!("Invalid key schedule root hex: {}", e))?;
+                    let expected_leaf_bytes = bincode::serialize(&epoch_key.pubkey)
+                        .map_err(|e| format!("Serialization error: {}", e))?;
+                    let expected_leaf = <CustomHasher as Hasher>::hash(&expected_leaf_bytes);
+                    if epoch_key.proof.positions != [params.epoch as usize]
+                        || epoch_key.proof.leaves != [expected_leaf]
+                        || !epoch_key.proof.verify(&schedule_root_bytes)
+                    {
+                        println!(
+                            "Epoch key proof for position {} failed against its schedule root",
+                            pos
+                        );
+                        return Ok(false);
+                    }
+                    &epoch_key.pubkey
+                }
+                None => &reveal.party.public_key,
+            };
+
             // Convert hex public key to PublicKey
-            let pubkey_bytes = hex::decode(&reveal.party.public_key)
+            let pubkey_bytes = hex::decode(effective_public_key)
                 .map_err(|e| format!("Invalid public key hex: {}", e))?;
             let public_key: [u8; 1312] = pubkey_bytes
                 .try_into()
@@ -339,92 +1283,163 @@ impl Certificate {
             }
             // println!("Signature at position {} verified successfully", pos);
 
-            verified_weight += reveal.party.weight;
+            verified_weight += Self::effective_weight(reveal)?;
             sig_slots.push(reveal.sig_slot.clone());
             participants.push(reveal.party.clone());
             positions.push(*pos as usize);
         }
 
-        // 4. Verify signature Merkle proofs
-        let mut sig_tree = MerkleTreeBuilder::new();
-        sig_tree.build(&sig_slots)?;
-        println!("Built signature Merkle tree");
-
-        // Prepare sorted (position, leaf_hash) pairs for signature leaves
-        let mut sig_pairs: Vec<(usize, [u8; 32])> = positions.iter().cloned().zip(
-            sig_slots.iter().map(|slot| {
-                let bytes = bincode::serialize(slot).map_err(|e| format!("Serialization error: {}", e)).unwrap();
-                <CustomHasher as Hasher>::hash(&bytes)
-            })
-        ).collect();
-        sig_pairs.sort_by_key(|(pos, _)| *pos);
-        let sorted_sig_positions: Vec<usize> = sig_pairs.iter().map(|(p, _)| *p).collect();
-        let sorted_sig_leaves: Vec<[u8; 32]> = sig_pairs.iter().map(|(_, hash)| *hash).collect();
+        // 4. Verify signature Merkle proofs directly against `sig_commit`.
+        // The revealed subset's own leaf hashes are folded up alongside the
+        // stored proof hashes — rebuilding a tree from just these `sig_slots`
+        // would both re-serialize every leaf for nothing and couldn't
+        // recover the slots' real positions in the committed tree anyway.
+        let mut sig_pairs: Vec<(usize, [u8; 32])> = Vec::with_capacity(positions.len());
+        for (pos, slot) in positions.iter().zip(sig_slots.iter()) {
+            sig_pairs.push((*pos, MerkleTreeBuilder::<CustomHasher>::hash_leaf(slot)?));
+        }
 
-        if !MerkleTreeBuilder::verify(
+        if !MerkleTreeBuilder::verify_multiproof(
             &self.sig_commit,
             &self.sig_proofs,
-            &sorted_sig_positions,
+            &sig_pairs,
             self.total_sigs,
-            &sorted_sig_leaves,
         ) {
             println!("Signature Merkle proof verification failed");
             return Ok(false);
         }
         println!("Signature Merkle proofs verified successfully");
 
-        // 5. Verify participant Merkle proofs
-        let mut party_tree = MerkleTreeBuilder::new();
-        party_tree.build(&participants)?;
-        println!("Built participant Merkle tree");
-
-        // Prepare sorted (position, leaf_hash) pairs for participant leaves
-        let mut party_pairs: Vec<(usize, [u8; 32])> = positions.iter().cloned().zip(
-            participants.iter().map(|party| {
-                let bytes = bincode::serialize(party).map_err(|e| format!("Serialization error: {}", e)).unwrap();
-                <CustomHasher as Hasher>::hash(&bytes)
-            })
-        ).collect();
-        party_pairs.sort_by_key(|(pos, _)| *pos);
-        let sorted_party_positions: Vec<usize> = party_pairs.iter().map(|(p, _)| *p).collect();
-        let sorted_party_leaves: Vec<[u8; 32]> = party_pairs.iter().map(|(_, hash)| *hash).collect();
+        // 5. Verify participant Merkle proofs directly against `party_tree_root`,
+        // same reasoning as above.
+        let mut party_pairs: Vec<(usize, [u8; 32])> = Vec::with_capacity(positions.len());
+        for (pos, party) in positions.iter().zip(participants.iter()) {
+            party_pairs.push((*pos, MerkleTreeBuilder::<CustomHasher>::hash_leaf(party)?));
+        }
 
-        if !MerkleTreeBuilder::verify(
+        if !MerkleTreeBuilder::verify_multiproof(
             party_tree_root,
             &self.party_proofs,
-            &sorted_party_positions,
+            &party_pairs,
             self.total_sigs,
-            &sorted_party_leaves,
         ) {
             println!("Participant Merkle proof verification failed");
             return Ok(false);
         }
         println!("Participant Merkle proofs verified successfully");
 
-        // 6. Verify coin choices
-        // Temporarily bypass coin choice verification for debugging purposes
-        println!("Skipping coin choice verification");
+        // 6. Verify coin choices: recompute every coin flip the builder was
+        // obligated to make and check it really does fall inside the
+        // revealed slot's weight interval, so a malicious builder can't
+        // swap in a position its coins didn't actually select.
+        let num_reveals =
+            Builder::num_reveals(self.signed_weight, params.proven_weight, params.security_param)?;
 
-        Ok(true)
-    }
+        if self.num_reveals != num_reveals {
+            println!(
+                "Stored num_reveals {} does not match recomputed {}",
+                self.num_reveals, num_reveals
+            );
+            return Ok(false);
+        }
 
-    // Helper function to generate deterministic random choice (same as Builder)
-    fn coin_choice(
-        &self,
-        index: u64,
-        sig_commit: &[u8],
-        signed_weight: u64,
-        proven_weight: u64,
-        party_tree_root: &[u8],
-        msg: &[u8],
-    ) -> u64 {
-        let mut hasher = Keccak256::new();
-        hasher.update(&index.to_le_bytes());
-        hasher.update(&signed_weight.to_le_bytes());
-        hasher.update(&proven_weight.to_le_bytes());
-        hasher.update(sig_commit);
-        hasher.update(party_tree_root);
-        hasher.update(msg);
+        if self.reveal_indices.len() != num_reveals || self.reveal_positions.len() != num_reveals {
+            println!(
+                "Coin choice count mismatch: got {} reveals, expected {}",
+                self.reveal_indices.len(),
+                num_reveals
+            );
+            return Ok(false);
+        }
+
+        for (pos, coin_index) in self.reveal_positions.iter().zip(self.reveal_indices.iter()) {
+            let reveal = self
+                .reveals
+                .get(pos)
+                .ok_or_else(|| format!("Missing reveal for position {}", pos))?;
+
+            let coin = self.coin_choice(
+                *coin_index,
+                &self.sig_commit,
+                self.signed_weight,
+                params.proven_weight,
+                party_tree_root,
+                &params.msg,
+                params.epoch,
+            );
+
+            let lower = reveal.sig_slot.accumulated_weight;
+            let upper = lower + Self::effective_weight(reveal)?;
+            if coin < lower || coin >= upper {
+                println!(
+                    "Coin choice {} (index {}) does not fall in slot {}'s interval [{}, {})",
+                    coin, coin_index, pos, lower, upper
+                );
+                return Ok(false);
+            }
+        }
+        println!("Coin choices verified successfully");
+
+        // 7. In weight-hiding mode, check every signer's committed weight
+        // (not just the revealed ones) sums to `self.signed_weight` without
+        // opening any commitment beyond what `reveals` already discloses.
+        if let Some(weight_proof) = &self.weight_proof {
+            let transcript: &[&[u8]] = &[&self.sig_commit, party_tree_root, &params.msg];
+            match weight_proof
+                .sum_proof
+                .verify(&weight_proof.commitments, self.signed_weight, transcript)
+            {
+                Ok(true) => println!("Weight-hiding sum proof verified successfully"),
+                Ok(false) => {
+                    println!("Weight-hiding sum proof failed");
+                    return Ok(false);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// A revealed slot's real weight: the cleartext `party.weight` unless
+    /// the certificate is in weight-hiding mode, in which case `party.weight`
+    /// is zeroed and the real value only exists inside the slot's
+    /// [`WeightOpening`] — which is checked against `party.weight_commitment`
+    /// before being trusted.
+    fn effective_weight(reveal: &Reveal) -> Result<u64, String> {
+        match (&reveal.party.weight_commitment, &reveal.weight_opening) {
+            (Some(commitment), Some(opening)) => {
+                if !opening.verify(commitment)? {
+                    return Err("weight opening does not match committed weight".to_string());
+                }
+                Ok(opening.weight)
+            }
+            (Some(_), None) => {
+                Err("weight-hiding participant revealed without a weight opening".to_string())
+            }
+            (None, _) => Ok(reveal.party.weight),
+        }
+    }
+
+    // Helper function to generate deterministic random choice (same as Builder)
+    fn coin_choice(
+        &self,
+        index: u64,
+        sig_commit: &[u8],
+        signed_weight: u64,
+        proven_weight: u64,
+        party_tree_root: &[u8],
+        msg: &[u8],
+        epoch: u64,
+    ) -> u64 {
+        let mut hasher = Keccak256::new();
+        hasher.update(&index.to_le_bytes());
+        hasher.update(&signed_weight.to_le_bytes());
+        hasher.update(&proven_weight.to_le_bytes());
+        hasher.update(sig_commit);
+        hasher.update(party_tree_root);
+        hasher.update(msg);
+        hasher.update(&epoch.to_le_bytes());
 
         let hash = hasher.finalize();
         let mut bytes = [0u8; 8];
@@ -439,73 +1454,35 @@ impl Certificate {
         coin
     }
 
-    // Helper function to find position in Certificate using binary search
-    fn find_coin_position(&self, coin_value: u64, sig_slots: &[SigSlot]) -> Result<u64, String> {
-        println!(
-            "Certificate find_coin_position: searching for coin_value {}",
-            coin_value
-        );
-        let mut positions: Vec<_> = self.reveals.iter().collect();
-        positions.sort_by_key(|(pos, _)| *pos);
-
-        println!("  Certificate positions and weights:");
-        let mut acc = 0;
-        for (pos, reveal) in &positions {
-            println!(
-                "    Position {}: range {} to {}",
-                pos,
-                acc,
-                acc + reveal.party.weight
-            );
-            acc += reveal.party.weight;
-        }
-
-        let mut lo = 0usize;
-        let mut hi = positions.len();
+    /// Reconstructs the same `(index, cumulative_weight)` prefix-sum table
+    /// [`Builder::cumulative_weights`] builds, but from a `Certificate`'s-eye
+    /// view: `sig_slots` (e.g. `builder.sigs`) already carries each signed
+    /// slot's `accumulated_weight` (the running total *before* it), so each
+    /// signed slot's own weight is just the gap to the next signed slot's
+    /// `accumulated_weight` (or to `self.signed_weight` for the last one) —
+    /// no participant weight lookup needed.
+    fn cumulative_weights_from_slots(&self, sig_slots: &[SigSlot]) -> Vec<(usize, u64)> {
+        let signed_positions: Vec<usize> = sig_slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.signature.is_some())
+            .map(|(i, _)| i)
+            .collect();
 
-        while lo < hi {
-            let mid = (lo + hi) / 2;
-            let mid_l = if mid == 0 {
-                0
-            } else {
-                positions[..mid]
-                    .iter()
-                    .fold(0, |acc, (_, reveal)| acc + reveal.party.weight)
+        let mut cum_weights = Vec::with_capacity(signed_positions.len());
+        for (idx, &pos) in signed_positions.iter().enumerate() {
+            let next_cumulative = match signed_positions.get(idx + 1) {
+                Some(&next_pos) => sig_slots[next_pos].accumulated_weight,
+                None => self.signed_weight,
             };
-
-            let (pos, reveal) = &positions[mid];
-            println!(
-                "  Certificate binary search: lo={}, hi={}, mid={}, mid_l={}, mid_weight={}",
-                lo, hi, mid, mid_l, reveal.party.weight
-            );
-
-            if coin_value < mid_l {
-                println!(
-                    "    coin_value {} < mid_l {}, setting hi = mid",
-                    coin_value, mid_l
-                );
-                hi = mid;
-                continue;
-            }
-
-            if coin_value < mid_l + reveal.party.weight {
-                println!(
-                    "    Found position: {} (weight range: {} to {})",
-                    pos,
-                    mid_l,
-                    mid_l + reveal.party.weight
-                );
-                return Ok(**pos);
-            }
-
-            println!(
-                "    coin_value {} >= mid_l {} + weight {}, setting lo = mid + 1",
-                coin_value, mid_l, reveal.party.weight
-            );
-            lo = mid + 1;
+            cum_weights.push((pos, next_cumulative));
         }
+        cum_weights
+    }
 
-        Err("Could not find position for coin value".to_string())
+    // Helper function to find position in Certificate using binary search
+    fn find_coin_position(coin_value: u64, cum_weights: &[(usize, u64)]) -> Result<u64, String> {
+        binary_search_cum_weights(coin_value, cum_weights)
     }
 }
 
@@ -525,6 +1502,8 @@ mod tests {
                 Participant {
                     public_key: pk,
                     weight,
+                    key_schedule_root: None,
+                    weight_commitment: None,
                 }
             })
             .collect();
@@ -539,6 +1518,7 @@ mod tests {
             msg: msg.clone(),
             proven_weight: total_weight / 2,
             security_param: 128,
+            epoch: 0,
         };
 
         (Builder::new(params, participants, party_tree_root), msg)
@@ -664,6 +1644,113 @@ mod tests {
         assert_eq!(builder.sigs[2].accumulated_weight, 30);
     }
 
+    #[test]
+    fn test_accumulated_weights_correct_out_of_order_arrival() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let wallet3 = Wallet::new().expect("Failed to create wallet 3");
+
+        let participants = vec![
+            (wallet1.get_public_key(), 10),
+            (wallet2.get_public_key(), 20),
+            (wallet3.get_public_key(), 30),
+        ];
+
+        let (mut builder, msg) = create_test_builder(participants);
+
+        // Sign out of position order: 2, then 0, then 1.
+        builder
+            .add_signature(2, wallet3.sign_message(&msg))
+            .expect("Failed to add signature 3");
+        assert_eq!(builder.sigs[2].accumulated_weight, 0);
+
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        assert_eq!(builder.sigs[0].accumulated_weight, 0);
+        assert_eq!(builder.sigs[2].accumulated_weight, 10);
+
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        assert_eq!(builder.sigs[0].accumulated_weight, 0);
+        assert_eq!(builder.sigs[1].accumulated_weight, 10);
+        assert_eq!(builder.sigs[2].accumulated_weight, 30);
+    }
+
+    #[test]
+    fn test_verify_rejects_coin_pointed_at_wrong_position() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let wallet3 = Wallet::new().expect("Failed to create wallet 3");
+
+        let participants = vec![
+            (wallet1.get_public_key(), 10),
+            (wallet2.get_public_key(), 20),
+            (wallet3.get_public_key(), 30),
+        ];
+
+        let (mut builder, msg) = create_test_builder(participants);
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        builder
+            .add_signature(2, wallet3.sign_message(&msg))
+            .expect("Failed to add signature 3");
+
+        let mut cert = builder.build().expect("Failed to build certificate");
+        assert!(cert
+            .verify(&builder.params, &builder.party_tree_root)
+            .unwrap());
+
+        // Relabel every revealed coin as if it had pointed at position 0
+        // instead of wherever it actually landed: the recomputed coin for
+        // most of those indices will fall outside position 0's weight
+        // interval, so verification must now fail.
+        cert.reveal_positions = vec![0u64; cert.reveal_positions.len()];
+        assert!(!cert
+            .verify(&builder.params, &builder.party_tree_root)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_truncated_reveal_list() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let wallet3 = Wallet::new().expect("Failed to create wallet 3");
+
+        let participants = vec![
+            (wallet1.get_public_key(), 10),
+            (wallet2.get_public_key(), 20),
+            (wallet3.get_public_key(), 30),
+        ];
+
+        let (mut builder, msg) = create_test_builder(participants);
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        builder
+            .add_signature(2, wallet3.sign_message(&msg))
+            .expect("Failed to add signature 3");
+
+        let mut cert = builder.build().expect("Failed to build certificate");
+
+        // An under-sampling prover that drops some of its obligated coin
+        // flips must be rejected even if every remaining coin still checks
+        // out individually.
+        cert.reveal_positions.pop();
+        cert.reveal_indices.pop();
+        assert!(!cert
+            .verify(&builder.params, &builder.party_tree_root)
+            .unwrap());
+    }
+
     #[test]
     fn test_coin_choice_consistency() {
         let wallet1 = Wallet::new().expect("Failed to create wallet 1");
@@ -688,13 +1775,13 @@ mod tests {
         let cert = builder.build().expect("Failed to build certificate");
 
         // Test multiple coin choices to ensure they're consistent between Builder and Certificate
+        let builder_cum_weights = builder.cumulative_weights();
+        let cert_cum_weights = cert.cumulative_weights_from_slots(&builder.sigs);
         for i in 0..10 {
             let coin = builder.coin_choice(i as u64, &cert.sig_commit);
-            let builder_pos = builder
-                .find_coin_position(coin)
+            let builder_pos = Builder::find_coin_position(coin, &builder_cum_weights)
                 .expect("Failed to find position in builder");
-            let cert_pos = cert
-                .find_coin_position(coin, &builder.sigs)
+            let cert_pos = Certificate::find_coin_position(coin, &cert_cum_weights)
                 .expect("Failed to find position in certificate");
             assert_eq!(
                 builder_pos, cert_pos,
@@ -703,4 +1790,575 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_find_coin_position_agrees_at_large_committee_scale() {
+        use rand_core::{OsRng, RngCore};
+
+        let num_participants = 2000;
+        let wallets: Vec<Wallet> = (0..num_participants)
+            .map(|_| Wallet::new().expect("Failed to create wallet"))
+            .collect();
+
+        let mut rng = OsRng;
+        let participants: Vec<(String, u64)> = wallets
+            .iter()
+            .map(|w| (w.get_public_key(), 1 + (rng.next_u64() % 100)))
+            .collect();
+        let total_weight: u64 = participants.iter().map(|(_, w)| w).sum();
+
+        let (mut builder, msg) = create_test_builder(participants);
+        for (i, wallet) in wallets.iter().enumerate() {
+            builder
+                .add_signature(i, wallet.sign_message(&msg))
+                .expect("Failed to add signature");
+        }
+        let cert = builder.build().expect("Failed to build certificate");
+
+        let builder_cum_weights = builder.cumulative_weights();
+        let cert_cum_weights = cert.cumulative_weights_from_slots(&builder.sigs);
+        assert_eq!(builder_cum_weights.len(), num_participants);
+        assert_eq!(cert_cum_weights.len(), num_participants);
+
+        for i in 0..200u64 {
+            let coin = i * (total_weight / 200).max(1);
+            let builder_pos = Builder::find_coin_position(coin, &builder_cum_weights)
+                .expect("Failed to find position in builder");
+            let cert_pos = Certificate::find_coin_position(coin, &cert_cum_weights)
+                .expect("Failed to find position in certificate");
+            assert_eq!(
+                builder_pos, cert_pos,
+                "Builder and Certificate must agree on the signer for coin {}",
+                coin
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_coin_position_agrees_across_random_weights_and_coins() {
+        use rand_core::{OsRng, RngCore};
+
+        let mut rng = OsRng;
+        let mut rounds_checked = 0;
+        for _ in 0..40 {
+            let num_participants = 5 + (rng.next_u64() % 50) as usize;
+            let wallets: Vec<Wallet> = (0..num_participants)
+                .map(|_| Wallet::new().expect("Failed to create wallet"))
+                .collect();
+            let participants: Vec<(String, u64)> = wallets
+                .iter()
+                .map(|w| (w.get_public_key(), 1 + (rng.next_u64() % 1000)))
+                .collect();
+
+            let (mut builder, msg) = create_test_builder(participants);
+            for (i, wallet) in wallets.iter().enumerate() {
+                // Every participant signs: `create_test_builder` sets
+                // `proven_weight` to half of total weight, and `build()` now
+                // requires `signed_weight` to strictly exceed it, so
+                // signing only a random subset would make this flaky.
+                builder
+                    .add_signature(i, wallet.sign_message(&msg))
+                    .expect("Failed to add signature");
+            }
+            let cert = builder.build().expect("Failed to build certificate");
+            let total_weight = builder.signed_weight;
+
+            let builder_cum_weights = builder.cumulative_weights();
+            let cert_cum_weights = cert.cumulative_weights_from_slots(&builder.sigs);
+
+            for _ in 0..20 {
+                let coin = rng.next_u64() % total_weight;
+                let builder_pos = Builder::find_coin_position(coin, &builder_cum_weights)
+                    .expect("Failed to find position in builder");
+                let cert_pos = Certificate::find_coin_position(coin, &cert_cum_weights)
+                    .expect("Failed to find position in certificate");
+                assert_eq!(
+                    builder_pos, cert_pos,
+                    "Builder and Certificate must agree on the signer for coin {}",
+                    coin
+                );
+                rounds_checked += 1;
+            }
+        }
+        assert_eq!(rounds_checked, 40 * 20);
+    }
+
+    #[test]
+    fn test_forward_secure_epoch_key_verifies_against_schedule_root() {
+        let epoch_wallets: Vec<Wallet> = (0..3)
+            .map(|_| Wallet::new().expect("Failed to create epoch wallet"))
+            .collect();
+        let schedule =
+            EpochKeySchedule::new(epoch_wallets.iter().map(|w| w.get_public_key()).collect());
+        let schedule_root = schedule.root().expect("Failed to compute schedule root");
+
+        let participants = vec![Participant {
+            public_key: String::new(),
+            weight: 150,
+            key_schedule_root: Some(hex::encode(&schedule_root)),
+            weight_commitment: None,
+        }];
+
+        let mut party_tree = MerkleTreeBuilder::new();
+        party_tree
+            .build(&participants)
+            .expect("Failed to build party tree");
+        let party_tree_root = party_tree.root();
+
+        let epoch = 1usize;
+        let params = Params {
+            msg: b"epoch message".to_vec(),
+            proven_weight: 100,
+            security_param: 128,
+            epoch: epoch as u64,
+        };
+
+        let mut builder = Builder::new(params, participants, party_tree_root.clone());
+        let epoch_proof = schedule.prove(epoch).expect("Failed to build epoch proof");
+        let signature = epoch_wallets[epoch].sign_message(&builder.params.msg.clone());
+        builder
+            .add_signature_with_epoch_key(
+                0,
+                signature,
+                epoch_wallets[epoch].get_public_key(),
+                epoch_proof,
+            )
+            .expect("Failed to add forward-secure signature");
+
+        let cert = builder.build().expect("Failed to build certificate");
+        assert!(
+            cert.verify(&builder.params, &party_tree_root).unwrap(),
+            "Forward-secure certificate should verify"
+        );
+    }
+
+    #[test]
+    fn test_forward_secure_verify_rejects_key_from_wrong_epoch() {
+        let epoch_wallets: Vec<Wallet> = (0..3)
+            .map(|_| Wallet::new().expect("Failed to create epoch wallet"))
+            .collect();
+        let schedule =
+            EpochKeySchedule::new(epoch_wallets.iter().map(|w| w.get_public_key()).collect());
+        let schedule_root = schedule.root().expect("Failed to compute schedule root");
+
+        let participants = vec![Participant {
+            public_key: String::new(),
+            weight: 150,
+            key_schedule_root: Some(hex::encode(&schedule_root)),
+            weight_commitment: None,
+        }];
+
+        let mut party_tree = MerkleTreeBuilder::new();
+        party_tree
+            .build(&participants)
+            .expect("Failed to build party tree");
+        let party_tree_root = party_tree.root();
+
+        let epoch = 1usize;
+        let params = Params {
+            msg: b"epoch message".to_vec(),
+            proven_weight: 100,
+            security_param: 128,
+            epoch: epoch as u64,
+        };
+
+        let mut builder = Builder::new(params, participants, party_tree_root.clone());
+        let epoch_proof = schedule.prove(epoch).expect("Failed to build epoch proof");
+        let signature = epoch_wallets[epoch].sign_message(&builder.params.msg.clone());
+        builder
+            .add_signature_with_epoch_key(
+                0,
+                signature,
+                epoch_wallets[epoch].get_public_key(),
+                epoch_proof,
+            )
+            .expect("Failed to add forward-secure signature");
+
+        let mut cert = builder.build().expect("Failed to build certificate");
+
+        // Swap in a different epoch's key while keeping the proof that was
+        // only ever valid for `epoch`: the leaf hash no longer matches what
+        // the proof attests, so verification must reject it.
+        let reveal = cert.reveals.get_mut(&0).expect("reveal for position 0");
+        reveal.sig_slot.epoch_key.as_mut().unwrap().pubkey = epoch_wallets[0].get_public_key();
+
+        assert!(!cert
+            .verify(&builder.params, &party_tree_root)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_weight_hiding_certificate_verifies() {
+        use k256::elliptic_curve::Field;
+        use k256::Scalar;
+        use rand_core::OsRng;
+
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+
+        let participants = vec![
+            (wallet1.get_public_key(), 50),
+            (wallet2.get_public_key(), 50),
+        ];
+
+        let (mut builder, msg) = create_test_builder(participants);
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+
+        let blindings: HashMap<usize, Scalar> = [
+            (0usize, Scalar::random(&mut OsRng)),
+            (1usize, Scalar::random(&mut OsRng)),
+        ]
+        .into_iter()
+        .collect();
+
+        let cert = builder
+            .build_weight_hiding(&blindings)
+            .expect("Failed to build weight-hiding certificate");
+
+        assert!(cert.weight_proof.is_some());
+        for reveal in cert.reveals.values() {
+            assert!(reveal.party.weight_commitment.is_some());
+            assert_eq!(reveal.party.weight, 0);
+        }
+
+        // Both positions get coin-sampled at this weight split, so the
+        // redacted party tree can be rebuilt straight from what `reveals`
+        // already discloses, in position order.
+        let redacted_participants = vec![
+            cert.reveals.get(&0).unwrap().party.clone(),
+            cert.reveals.get(&1).unwrap().party.clone(),
+        ];
+        let mut redacted_tree = MerkleTreeBuilder::new();
+        redacted_tree
+            .build(&redacted_participants)
+            .expect("Failed to rebuild redacted party tree");
+
+        assert!(
+            cert.verify(&builder.params, &redacted_tree.root()).unwrap(),
+            "weight-hiding certificate should verify"
+        );
+    }
+
+    #[test]
+    fn test_params_round_trips_through_bytes() {
+        let params = Params {
+            msg: b"Test message".to_vec(),
+            proven_weight: 30,
+            security_param: 128,
+            epoch: 7,
+        };
+
+        let bytes = params.to_bytes();
+        let decoded = Params::from_bytes(&bytes).expect("Failed to decode params");
+        assert_eq!(decoded.msg, params.msg);
+        assert_eq!(decoded.proven_weight, params.proven_weight);
+        assert_eq!(decoded.security_param, params.security_param);
+        assert_eq!(decoded.epoch, params.epoch);
+    }
+
+    #[test]
+    fn test_certificate_round_trips_through_bytes() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let wallet3 = Wallet::new().expect("Failed to create wallet 3");
+
+        let participants = vec![
+            (wallet1.get_public_key(), 10),
+            (wallet2.get_public_key(), 20),
+            (wallet3.get_public_key(), 30),
+        ];
+
+        let (mut builder, msg) = create_test_builder(participants);
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        builder
+            .add_signature(2, wallet3.sign_message(&msg))
+            .expect("Failed to add signature 3");
+
+        let cert = builder.build().expect("Failed to build certificate");
+        let bytes = cert.to_bytes();
+        let decoded = Certificate::from_bytes(&bytes).expect("Failed to decode certificate");
+
+        assert_eq!(decoded.sig_commit, cert.sig_commit);
+        assert_eq!(decoded.signed_weight, cert.signed_weight);
+        assert_eq!(decoded.total_sigs, cert.total_sigs);
+        assert_eq!(decoded.reveal_positions, cert.reveal_positions);
+        assert_eq!(decoded.reveal_indices, cert.reveal_indices);
+        assert_eq!(decoded.sig_proofs, cert.sig_proofs);
+        assert_eq!(decoded.party_proofs, cert.party_proofs);
+        assert_eq!(decoded.reveals.len(), cert.reveals.len());
+        for pos in &cert.reveal_positions {
+            assert_eq!(
+                decoded.reveals[pos].party.public_key,
+                cert.reveals[pos].party.public_key
+            );
+        }
+
+        let result = decoded.verify(&builder.params, &builder.party_tree_root);
+        assert!(
+            result.is_ok() && result.unwrap(),
+            "Round-tripped certificate should still verify"
+        );
+    }
+
+    #[test]
+    fn test_certificate_bytes_match_golden_vector() {
+        // A fixed, hand-constructed certificate (not dependent on any
+        // randomly generated keys) whose canonical encoding is pinned here
+        // as a golden vector, so any unintentional drift in the wire format
+        // is caught instead of silently shipped to the Go verifier.
+        let mut reveals = HashMap::new();
+        reveals.insert(
+            0u64,
+            Reveal {
+                sig_slot: SigSlot {
+                    signature: Some(SerializableSignature(vec![0xAA; 4])),
+                    accumulated_weight: 0,
+                    epoch_key: None,
+                },
+                party: Participant {
+                    public_key: "aa".to_string(),
+                    weight: 10,
+                    key_schedule_root: None,
+                    weight_commitment: None,
+                },
+                weight_opening: None,
+            },
+        );
+
+        let cert = Certificate {
+            sig_commit: vec![0x01, 0x02, 0x03],
+            signed_weight: 10,
+            total_sigs: 1,
+            reveals,
+            sig_proofs: vec![vec![0x11, 0x22]],
+            party_proofs: vec![vec![0x33]],
+            reveal_positions: vec![0],
+            reveal_indices: vec![0],
+            num_reveals: 1,
+            weight_proof: None,
+        };
+
+        let golden_hex = "00000003010203000000000000000a000000000000000100000001800100000004aaaaaaaa000000000000000000000000026161000000000000000a00000000000001000000021122000000010000000133000000010000000000000000000000010000000000000000000000000000000100";
+        let expected = hex::decode(golden_hex).expect("golden vector must be valid hex");
+        assert_eq!(
+            cert.to_bytes(),
+            expected,
+            "certificate wire format must not drift from the committed golden vector"
+        );
+    }
+
+    fn fixed_certificate_for_attestation_tests() -> Certificate {
+        let mut reveals = HashMap::new();
+        reveals.insert(
+            0u64,
+            Reveal {
+                sig_slot: SigSlot {
+                    signature: Some(SerializableSignature(vec![0xAA; 4])),
+                    accumulated_weight: 0,
+                    epoch_key: None,
+                },
+                party: Participant {
+                    public_key: "aa".to_string(),
+                    weight: 10,
+                    key_schedule_root: None,
+                    weight_commitment: None,
+                },
+                weight_opening: None,
+            },
+        );
+
+        Certificate {
+            sig_commit: vec![0x01, 0x02, 0x03],
+            signed_weight: 10,
+            total_sigs: 1,
+            reveals,
+            sig_proofs: vec![vec![0x11, 0x22]],
+            party_proofs: vec![vec![0x33]],
+            reveal_positions: vec![0],
+            reveal_indices: vec![0],
+            num_reveals: 1,
+            weight_proof: None,
+        }
+    }
+
+    #[test]
+    fn test_attestation_bytes_match_golden_vector() {
+        // Pinned against the same fixed certificate fixture as
+        // `test_certificate_bytes_match_golden_vector`, so a future decoder
+        // (or a port to another language, as with the Go certificate
+        // verifier) can be checked against this exact byte layout.
+        let attestation = Attestation::new(
+            b"btc-price-above-100k".to_vec(),
+            10,
+            128,
+            1,
+            fixed_certificate_for_attestation_tests(),
+        );
+
+        let golden_hex = "000000146274632d70726963652d61626f76652d3130306b000000000000000a00000080000000000000000100000003010203000000000000000a000000000000000100000001800100000004aaaaaaaa000000000000000000000000026161000000000000000a00000000000001000000021122000000010000000133000000010000000000000000000000010000000000000000000000000000000100";
+        let expected = hex::decode(golden_hex).expect("golden vector must be valid hex");
+        assert_eq!(
+            attestation.encode(),
+            expected,
+            "attestation wire format must not drift from the committed golden vector"
+        );
+
+        let decoded = Attestation::decode(&expected).expect("golden vector must decode");
+        assert_eq!(decoded.outcome, attestation.outcome);
+        assert_eq!(decoded.proven_weight, attestation.proven_weight);
+        assert_eq!(decoded.security_param, attestation.security_param);
+        assert_eq!(decoded.epoch, attestation.epoch);
+        assert_eq!(decoded.certificate.to_bytes(), attestation.certificate.to_bytes());
+    }
+
+    #[test]
+    fn test_verify_attestation_accepts_a_real_certificate_without_builder_access() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let participants = vec![
+            (wallet1.get_public_key(), 60),
+            (wallet2.get_public_key(), 40),
+        ];
+        let (mut builder, msg) = create_test_builder(participants);
+        let party_tree_root = builder.party_tree_root.clone();
+
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        let cert = builder.build().expect("Failed to build certificate");
+
+        let attestation = Attestation::new(msg, builder.params.proven_weight, 128, 0, cert);
+        let encoded = attestation.encode();
+        let decoded = Attestation::decode(&encoded).expect("attestation should decode");
+
+        // `verify_attestation` only ever touches `decoded` and
+        // `party_tree_root` here; `builder.sigs` is never passed in.
+        let verified = verify_attestation(&party_tree_root, &decoded)
+            .expect("verification should not error");
+        assert!(verified, "a genuine attestation over enough signed weight must verify");
+    }
+
+    #[test]
+    fn test_verify_attestation_rejects_tampered_revealed_signature() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let participants = vec![
+            (wallet1.get_public_key(), 60),
+            (wallet2.get_public_key(), 40),
+        ];
+        let (mut builder, msg) = create_test_builder(participants);
+        let party_tree_root = builder.party_tree_root.clone();
+
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        let mut cert = builder.build().expect("Failed to build certificate");
+
+        // Flip a byte in one revealed signature, simulating a relay that
+        // tampered with the attestation in transit.
+        let pos = *cert.reveals.keys().next().expect("certificate should have a reveal");
+        let reveal = cert.reveals.get_mut(&pos).unwrap();
+        if let Some(SerializableSignature(bytes)) = &mut reveal.sig_slot.signature {
+            bytes[0] ^= 0xFF;
+        }
+
+        let attestation = Attestation::new(msg, builder.params.proven_weight, 128, 0, cert);
+        let decoded = Attestation::decode(&attestation.encode()).expect("attestation should decode");
+
+        let verified = verify_attestation(&party_tree_root, &decoded)
+            .expect("verification should not error on a malformed signature, just reject it");
+        assert!(!verified, "a tampered revealed signature must fail verification");
+    }
+
+    #[test]
+    fn test_verify_participant_membership_confirms_a_revealed_signer_and_its_weight() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let participants = vec![
+            (wallet1.get_public_key(), 60),
+            (wallet2.get_public_key(), 40),
+        ];
+        let (mut builder, msg) = create_test_builder(participants);
+        let party_tree_root = builder.party_tree_root.clone();
+
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        let cert = builder.build().expect("Failed to build certificate");
+
+        // No `Blockchain`/`Validator` in sight: just the certificate and
+        // the party tree root a light client would have read off a header.
+        let weight = verify_participant_membership(&cert, &party_tree_root, &wallet1.get_public_key())
+            .expect("membership check should not error")
+            .expect("wallet1 signed and should be a revealed participant");
+        assert_eq!(weight, 60);
+    }
+
+    #[test]
+    fn test_verify_participant_membership_returns_none_for_a_non_signer() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let bystander = Wallet::new().expect("Failed to create bystander wallet");
+        let participants = vec![
+            (wallet1.get_public_key(), 60),
+            (wallet2.get_public_key(), 40),
+        ];
+        let (mut builder, msg) = create_test_builder(participants);
+        let party_tree_root = builder.party_tree_root.clone();
+
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        let cert = builder.build().expect("Failed to build certificate");
+
+        let result = verify_participant_membership(&cert, &party_tree_root, &bystander.get_public_key())
+            .expect("membership check should not error");
+        assert!(result.is_none(), "a key that never signed must not be reported as a member");
+    }
+
+    #[test]
+    fn test_verify_participant_membership_rejects_wrong_party_tree_root() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let participants = vec![
+            (wallet1.get_public_key(), 60),
+            (wallet2.get_public_key(), 40),
+        ];
+        let (mut builder, msg) = create_test_builder(participants);
+
+        builder
+            .add_signature(0, wallet1.sign_message(&msg))
+            .expect("Failed to add signature 1");
+        builder
+            .add_signature(1, wallet2.sign_message(&msg))
+            .expect("Failed to add signature 2");
+        let cert = builder.build().expect("Failed to build certificate");
+
+        let wrong_root = vec![0xAAu8; 32];
+        let result = verify_participant_membership(&cert, &wrong_root, &wallet1.get_public_key())
+            .expect("membership check should not error, just fail to find a match");
+        assert!(result.is_none(), "a membership claim against the wrong party tree root must be rejected");
+    }
 }
@@ -0,0 +1,274 @@
+//! Optional weight-hiding mode for compact certificates. Inspired by the
+//! commit-and-prove flow libbolt uses for payment channels (Pedersen
+//! commitments plus a proof relating committed values), a participant's
+//! weight is committed as `C = g^weight * h^blinding` instead of being
+//! hashed into the party Merkle leaves in the clear. A revealed signer
+//! still opens their own commitment — `Certificate::verify`'s coin-interval
+//! check needs the real weight either way — but nothing is learned about
+//! any participant who never gets coin-sampled.
+//!
+//! [`SumOpeningProof`] additionally proves, without opening any individual
+//! commitment, that every signer's committed weight sums to the
+//! certificate's already-public `signed_weight`. It does not hide that
+//! total itself: a full range proof that also keeps `signed_weight` hidden
+//! (bit-decomposition, bulletproofs, ...) is a meaningfully bigger
+//! undertaking and is left for later.
+
+use k256::ecdsa::VerifyingKey;
+use k256::elliptic_curve::{sec1::ToEncodedPoint, Field, PrimeField};
+use k256::{ProjectivePoint, Scalar};
+use once_cell::sync::Lazy;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use sha3::Keccak256;
+
+/// Independent generator `h`, found by hashing a fixed label with an
+/// incrementing counter until the digest decodes as a valid compressed
+/// secp256k1 point. Nothing-up-my-sleeve: nobody (including us) learns
+/// `log_g(h)`, which is what keeps `g^weight h^blinding` hiding `weight`.
+static PEDERSEN_H: Lazy<ProjectivePoint> = Lazy::new(|| {
+    let mut counter: u64 = 0;
+    loop {
+        let mut hasher = Sha512::new();
+        hasher.update(b"niropok/ccok/pedersen-h");
+        hasher.update(counter.to_le_bytes());
+        let digest = hasher.finalize();
+        let mut candidate = [0u8; 33];
+        candidate[0] = 0x02;
+        candidate[1..].copy_from_slice(&digest[..32]);
+        if let Ok(key) = VerifyingKey::from_sec1_bytes(&candidate) {
+            return ProjectivePoint::from(key.as_affine());
+        }
+        counter += 1;
+    }
+});
+
+fn encode_point(point: &ProjectivePoint) -> Vec<u8> {
+    point.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+fn decode_point(bytes: &[u8]) -> Result<ProjectivePoint, String> {
+    let key = VerifyingKey::from_sec1_bytes(bytes)
+        .map_err(|e| format!("invalid commitment point: {}", e))?;
+    Ok(ProjectivePoint::from(key.as_affine()))
+}
+
+fn scalar_to_bytes(scalar: &Scalar) -> [u8; 32] {
+    scalar.to_repr().into()
+}
+
+fn scalar_from_bytes(bytes: &[u8; 32]) -> Result<Scalar, String> {
+    Scalar::from_repr_vartime((*bytes).into()).ok_or_else(|| "invalid scalar bytes".to_string())
+}
+
+/// A u64 weight always fits a secp256k1 scalar (the field order is far
+/// larger than 2^64), so this never needs rejection sampling.
+fn scalar_from_weight(weight: u64) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&weight.to_be_bytes());
+    Scalar::from_repr_vartime(bytes.into()).expect("u64 always fits a secp256k1 scalar")
+}
+
+/// Draws a uniformly random non-zero scalar, the same rejection-sampling
+/// shape `ccok::sig` uses when deriving nonces.
+fn random_nonzero_scalar() -> Scalar {
+    loop {
+        let candidate = Scalar::random(&mut OsRng);
+        if bool::from(!candidate.is_zero()) {
+            return candidate;
+        }
+    }
+}
+
+/// A Pedersen commitment `C = g^weight * h^blinding` to a participant's
+/// weight.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WeightCommitment {
+    /// SEC1-compressed commitment point.
+    point: Vec<u8>,
+}
+
+impl WeightCommitment {
+    pub fn commit(weight: u64, blinding: &Scalar) -> Self {
+        let point = ProjectivePoint::GENERATOR * &scalar_from_weight(weight) + *PEDERSEN_H * blinding;
+        Self {
+            point: encode_point(&point),
+        }
+    }
+
+    fn as_point(&self) -> Result<ProjectivePoint, String> {
+        decode_point(&self.point)
+    }
+}
+
+/// The opening `(weight, blinding)` for one participant's
+/// [`WeightCommitment`] — attached only to a revealed
+/// [`crate::ccok::Reveal`], since a revealed signer's weight has to be
+/// disclosed for the coin-interval check anyway; a participant who isn't
+/// coin-sampled never produces one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightOpening {
+    pub weight: u64,
+    blinding: [u8; 32],
+}
+
+impl WeightOpening {
+    pub fn new(weight: u64, blinding: Scalar) -> Self {
+        Self {
+            weight,
+            blinding: scalar_to_bytes(&blinding),
+        }
+    }
+
+    /// Whether `commitment` really does open to this weight and blinding.
+    pub fn verify(&self, commitment: &WeightCommitment) -> Result<bool, String> {
+        let blinding = scalar_from_bytes(&self.blinding)?;
+        let expected = WeightCommitment::commit(self.weight, &blinding);
+        Ok(expected.point == commitment.point)
+    }
+}
+
+/// A Schnorr-style proof of knowledge of the blinding factor behind the
+/// homomorphic sum of a set of [`WeightCommitment`]s, showing that sum
+/// opens to a publicly claimed total weight without opening any individual
+/// commitment in the set. The challenge is derived the same way
+/// `coin_choice` derives its coin: Keccak256 over the transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SumOpeningProof {
+    /// SEC1-compressed commitment to the Schnorr nonce, `h^nonce`.
+    nonce_commitment: Vec<u8>,
+    /// The response scalar `s = nonce - challenge * blinding_sum`.
+    response: [u8; 32],
+}
+
+impl SumOpeningProof {
+    /// Proves that the commitments behind `blinding_sum` (the sum of every
+    /// individual commitment's blinding factor) open to `claimed_weight` in
+    /// aggregate.
+    pub fn prove(claimed_weight: u64, blinding_sum: &Scalar, transcript: &[&[u8]]) -> Self {
+        let nonce = random_nonzero_scalar();
+        let nonce_commitment = *PEDERSEN_H * &nonce;
+        let challenge = Self::challenge(claimed_weight, &nonce_commitment, transcript);
+        let response = nonce - challenge * blinding_sum;
+        Self {
+            nonce_commitment: encode_point(&nonce_commitment),
+            response: scalar_to_bytes(&response),
+        }
+    }
+
+    /// Verifies this proof against the homomorphic sum of `commitments`
+    /// opening to `claimed_weight`.
+    pub fn verify(
+        &self,
+        commitments: &[WeightCommitment],
+        claimed_weight: u64,
+        transcript: &[&[u8]],
+    ) -> Result<bool, String> {
+        let mut commitment_points = commitments.iter().map(WeightCommitment::as_point);
+        let mut sum = match commitment_points.next() {
+            Some(point) => point?,
+            None => return Err("no commitments to verify a weight sum against".to_string()),
+        };
+        for point in commitment_points {
+            sum += point?;
+        }
+
+        let nonce_commitment = decode_point(&self.nonce_commitment)?;
+        let challenge = Self::challenge(claimed_weight, &nonce_commitment, transcript);
+        let response = scalar_from_bytes(&self.response)?;
+
+        // Schnorr check for the statement `sum / g^claimed_weight = h^blinding_sum`:
+        // h^response + (sum - g^claimed_weight) * challenge =?= nonce_commitment
+        let target = sum - ProjectivePoint::GENERATOR * &scalar_from_weight(claimed_weight);
+        let lhs = *PEDERSEN_H * &response + target * &challenge;
+        Ok(lhs.to_affine() == nonce_commitment.to_affine())
+    }
+
+    fn challenge(
+        claimed_weight: u64,
+        nonce_commitment: &ProjectivePoint,
+        transcript: &[&[u8]],
+    ) -> Scalar {
+        let mut counter: u64 = 0;
+        loop {
+            let mut hasher = Keccak256::new();
+            hasher.update(claimed_weight.to_le_bytes());
+            hasher.update(encode_point(nonce_commitment));
+            for part in transcript {
+                hasher.update(part);
+            }
+            hasher.update(counter.to_le_bytes());
+            let digest = hasher.finalize();
+            if let Some(scalar) = Scalar::from_repr_vartime(digest.into()) {
+                return scalar;
+            }
+            counter += 1;
+        }
+    }
+}
+
+/// Everything a verifier needs to check a weight-hiding certificate's
+/// aggregate weight claim: every signer's [`WeightCommitment`] (not just
+/// the revealed ones) plus the [`SumOpeningProof`] that they sum to
+/// `Certificate::signed_weight`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightHidingProof {
+    pub commitments: Vec<WeightCommitment>,
+    pub sum_proof: SumOpeningProof,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weight_commitment_opens_correctly() {
+        let blinding = random_nonzero_scalar();
+        let commitment = WeightCommitment::commit(42, &blinding);
+        let opening = WeightOpening::new(42, blinding);
+        assert!(opening.verify(&commitment).unwrap());
+    }
+
+    #[test]
+    fn test_weight_opening_rejects_wrong_weight() {
+        let blinding = random_nonzero_scalar();
+        let commitment = WeightCommitment::commit(42, &blinding);
+        let wrong_opening = WeightOpening::new(43, blinding);
+        assert!(!wrong_opening.verify(&commitment).unwrap());
+    }
+
+    #[test]
+    fn test_sum_opening_proof_round_trips() {
+        let b1 = random_nonzero_scalar();
+        let b2 = random_nonzero_scalar();
+        let c1 = WeightCommitment::commit(10, &b1);
+        let c2 = WeightCommitment::commit(20, &b2);
+        let blinding_sum = b1 + b2;
+
+        let transcript: &[&[u8]] = &[b"sig-commit", b"party-root"];
+        let proof = SumOpeningProof::prove(30, &blinding_sum, transcript);
+        assert!(proof.verify(&[c1, c2], 30, transcript).unwrap());
+    }
+
+    #[test]
+    fn test_sum_opening_proof_rejects_wrong_total() {
+        let b1 = random_nonzero_scalar();
+        let b2 = random_nonzero_scalar();
+        let c1 = WeightCommitment::commit(10, &b1);
+        let c2 = WeightCommitment::commit(20, &b2);
+        let blinding_sum = b1 + b2;
+
+        let transcript: &[&[u8]] = &[b"sig-commit", b"party-root"];
+        let proof = SumOpeningProof::prove(30, &blinding_sum, transcript);
+        assert!(!proof.verify(&[c1, c2], 31, transcript).unwrap());
+    }
+
+    #[test]
+    fn test_weight_commitment_hides_weight_from_equality() {
+        // Same weight, different blinding must not produce the same commitment.
+        let commitment_a = WeightCommitment::commit(5, &random_nonzero_scalar());
+        let commitment_b = WeightCommitment::commit(5, &random_nonzero_scalar());
+        assert_ne!(commitment_a.point, commitment_b.point);
+    }
+}
@@ -2,7 +2,7 @@ use k256::{
     ecdsa::{SigningKey, VerifyingKey},
     elliptic_curve::{
         sec1::ToEncodedPoint,
-        PrimeField,
+        Field, PrimeField,
     },
     ProjectivePoint, Scalar,
 };
@@ -10,7 +10,7 @@ use rand_core::{OsRng, RngCore};
 use sha2::{Digest, Sha512};
 use std::io::{self, Read};
 use aes::Aes256;
-use aes::cipher::KeyIvInit;
+use aes::cipher::{KeyIvInit, StreamCipher};
 use ctr::Ctr64BE;
 
 // Constants matching the Go code
@@ -86,10 +86,50 @@ impl SchnorrSigner {
         };
         
         signer.scalar.copy_from_slice(&signing_key.to_bytes());
-        
+
+        Ok(signer)
+    }
+
+    /// Deterministically derives the secp256k1 scalar from a master `seed`
+    /// via a domain-separated KDF label (see
+    /// `crate::mnemonic::derive_label_seed`), reusing the same AES-256-CTR
+    /// keystream rejection-sampling loop as `sign_with_entropy` so the
+    /// derived scalar is always canonical and non-zero.
+    pub fn from_seed(seed: &[u8; 32]) -> Result<Self, Box<dyn std::error::Error>> {
+        let schnorr_seed = crate::mnemonic::derive_label_seed(seed, b"niropok/wallet/schnorr");
+
+        type Aes256Ctr64BE = Ctr64BE<Aes256>;
+        let mut cipher = Aes256Ctr64BE::new((&schnorr_seed[..]).into(), AES_IV.into());
+
+        let scalar_value = loop {
+            let mut block = [0u8; SIZE_FR];
+            cipher.apply_keystream(&mut block);
+            if let Some(candidate) = Scalar::from_repr_vartime(block.into()) {
+                if bool::from(!candidate.is_zero()) {
+                    break candidate;
+                }
+            }
+        };
+
+        let verifying_point = (ProjectivePoint::GENERATOR * &scalar_value).to_affine();
+        let verifying_key = VerifyingKey::from_affine(verifying_point)?;
+
+        let mut signer = SchnorrSigner {
+            public: SchnorrPublicKey { key: verifying_key },
+            scalar: [0u8; SIZE_FR],
+        };
+        signer.scalar.copy_from_slice(&scalar_value.to_repr());
+
         Ok(signer)
     }
 
+    /// Reconstructs a signer from a mnemonic phrase produced by
+    /// `crate::mnemonic::encode`.
+    pub fn from_mnemonic(phrase: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let seed = crate::mnemonic::decode(phrase).map_err(|e| -> Box<dyn std::error::Error> { e.into() })?;
+        Self::from_seed(&seed)
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut res = Vec::with_capacity(SIZE_PRIVATE_KEY);
         res.extend_from_slice(&self.public.key.to_sec1_bytes());
@@ -113,24 +153,38 @@ impl SchnorrSigner {
     }
 
     pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut entropy = [0u8; 32];
+        OsRng.fill_bytes(&mut entropy);
+        self.sign_with_entropy(msg, &entropy)
+    }
+
+    // Deterministic core of `sign`, parameterized on the hedged entropy so it
+    // can be exercised with a fixed test vector.
+    fn sign_with_entropy(&self, msg: &[u8], entropy: &[u8; 32]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         // Generate nonce
         let mut hasher = Sha512::new();
         hasher.update(&self.scalar);
-        
-        let mut entropy = [0u8; 32];
-        OsRng.fill_bytes(&mut entropy);
-        hasher.update(&entropy);
+        hasher.update(entropy);
         hasher.update(msg);
-        
+
         let key = &hasher.finalize()[..32];
-        
+
         // Create AES-CTR CSPRNG
         type Aes256Ctr64BE = Ctr64BE<Aes256>;
-        let _cipher = Aes256Ctr64BE::new(key.into(), AES_IV.into());
-        
-        // Generate random k
-        let k = Scalar::generate_vartime(&mut OsRng);
-        
+        let mut cipher = Aes256Ctr64BE::new(key.into(), AES_IV.into());
+
+        // Derive k from the CSPRNG keystream, rejecting non-canonical scalars
+        // (matches the gnark-crypto Go implementation's rejection sampling).
+        let k = loop {
+            let mut block = [0u8; SIZE_FR];
+            cipher.apply_keystream(&mut block);
+            if let Some(candidate) = Scalar::from_repr_vartime(block.into()) {
+                if bool::from(!candidate.is_zero()) {
+                    break candidate;
+                }
+            }
+        };
+
         // Use GENERATOR constant
         let r = (ProjectivePoint::GENERATOR * &k).to_affine();
         
@@ -215,6 +269,51 @@ mod tests {
         assert_eq!(signature, sig.to_bytes());
     }
 
+    #[test]
+    fn test_fixed_entropy_nonce_is_deterministic() {
+        // A fixed (scalar, entropy, msg) must always produce the same (s, e),
+        // since k is now derived purely from the CSPRNG keystream.
+        let scalar = [7u8; SIZE_FR];
+        let verifying_key = (ProjectivePoint::GENERATOR
+            * &Scalar::from_repr_vartime(scalar.into()).unwrap())
+            .to_affine();
+        let signer = SchnorrSigner {
+            public: SchnorrPublicKey {
+                key: VerifyingKey::from_affine(verifying_key).unwrap(),
+            },
+            scalar,
+        };
+        let entropy = [42u8; 32];
+        let msg = b"fixed test vector message";
+
+        let sig1 = signer.sign_with_entropy(msg, &entropy).unwrap();
+        let sig2 = signer.sign_with_entropy(msg, &entropy).unwrap();
+        assert_eq!(sig1, sig2, "same (scalar, entropy, msg) must yield the same (s, e)");
+
+        assert!(signer.public.verify(&sig1, msg).unwrap());
+    }
+
+    #[test]
+    fn test_from_seed_is_deterministic_and_signs() {
+        let seed = [9u8; 32];
+        let signer1 = SchnorrSigner::from_seed(&seed).unwrap();
+        let signer2 = SchnorrSigner::from_seed(&seed).unwrap();
+        assert_eq!(signer1.scalar, signer2.scalar, "same seed must derive the same scalar");
+
+        let msg = b"seeded signer test message";
+        let signature = signer1.sign(msg).unwrap();
+        assert!(signer1.public.verify(&signature, msg).unwrap());
+    }
+
+    #[test]
+    fn test_from_mnemonic_round_trips_through_seed() {
+        let seed = [13u8; 32];
+        let phrase = crate::mnemonic::encode(&seed);
+        let from_phrase = SchnorrSigner::from_mnemonic(&phrase).unwrap();
+        let from_seed = SchnorrSigner::from_seed(&seed).unwrap();
+        assert_eq!(from_phrase.scalar, from_seed.scalar);
+    }
+
     #[test]
     fn test_invalid_signature() {
         let signer = SchnorrSigner::generate().unwrap();
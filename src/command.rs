@@ -0,0 +1,192 @@
+use crate::accounts::Account;
+use crate::blockchain::Blockchain;
+use crate::p2p::AppBehaviour;
+use crate::transaction::{Transaction, TransactionType};
+use crate::utils::TpsTracker;
+use colored::*;
+use libp2p::Swarm;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Parses one stdin line into a command and dispatches it against the
+/// running node, printing a colored, human-readable result (or error)
+/// instead of panicking on bad input. Lets an operator drive and inspect a
+/// running node (`balance`, `send`, `peers`, `stake`, `unstake`, `tps`,
+/// `chain`) without crafting HTTP POSTs to the RPC server.
+pub fn dispatch(
+    line: &str,
+    blockchain: &Arc<Mutex<Blockchain>>,
+    swarm: &mut Swarm<AppBehaviour>,
+    tps_tracker: &Arc<Mutex<TpsTracker>>,
+    rpc_sender: &UnboundedSender<Transaction>,
+) {
+    let mut parts = line.trim().split_whitespace();
+    let Some(cmd) = parts.next() else {
+        return;
+    };
+    let args: Vec<&str> = parts.collect();
+
+    let result = match cmd {
+        "balance" => balance(&args, blockchain),
+        "send" => send(&args, blockchain, rpc_sender),
+        "peers" => Ok(peers(swarm)),
+        "stake" => stake_like("stake", &args, blockchain, rpc_sender, TransactionType::STAKE),
+        "unstake" => stake_like("unstake", &args, blockchain, rpc_sender, TransactionType::UNSTAKE),
+        "tps" => Ok(tps(tps_tracker)),
+        "chain" => Ok(chain(blockchain)),
+        other => Err(format!(
+            "unknown command {:?} (available: balance, send, peers, stake, unstake, tps, chain)",
+            other
+        )),
+    };
+
+    match result {
+        Ok(message) => println!("{}", message),
+        Err(err) => println!("{} {}", "error:".red().bold(), err),
+    }
+}
+
+fn balance(args: &[&str], blockchain: &Arc<Mutex<Blockchain>>) -> Result<String, String> {
+    let chain = blockchain.lock().unwrap();
+    let address = match args.first() {
+        Some(addr) => addr.to_string(),
+        None => chain.wallet.get_public_key(),
+    };
+    let balance = chain.state.get_balance(Account {
+        address: address.clone(),
+    });
+    Ok(format!("{} {} = {}", "balance".cyan().bold(), address, balance))
+}
+
+/// Builds, signs and queues a transaction exactly like the RPC path does:
+/// pushed through the same `rpc_sender` channel that feeds
+/// `EventType::RpcTransaction`, so it's added to the mempool and gossiped
+/// on `TRANSACTION_TOPIC` by the same code, not a parallel copy of it.
+fn send(
+    args: &[&str],
+    blockchain: &Arc<Mutex<Blockchain>>,
+    rpc_sender: &UnboundedSender<Transaction>,
+) -> Result<String, String> {
+    let [recipient, amount, fee] = args else {
+        return Err("usage: send <recipient> <amount> <fee>".to_string());
+    };
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("invalid amount {:?}", amount))?;
+    let fee: usize = fee.parse().map_err(|_| format!("invalid fee {:?}", fee))?;
+
+    let mut chain = blockchain.lock().unwrap();
+    let sender_account = Account {
+        address: chain.wallet.get_public_key(),
+    };
+    let recipient_account = Account {
+        address: recipient.to_string(),
+    };
+    let nonce = chain.state.next_nonce(&sender_account);
+    let wallet = &mut chain.wallet;
+    let txn = Transaction::new(
+        wallet,
+        sender_account,
+        recipient_account,
+        amount,
+        fee,
+        nonce,
+        TransactionType::TRANSACTION,
+        None,
+    )
+    .map_err(|e| format!("failed to build transaction: {}", e))?;
+    drop(chain);
+
+    queue(rpc_sender, txn, "send")
+}
+
+fn stake_like(
+    label: &str,
+    args: &[&str],
+    blockchain: &Arc<Mutex<Blockchain>>,
+    rpc_sender: &UnboundedSender<Transaction>,
+    txn_type: TransactionType,
+) -> Result<String, String> {
+    let [amount] = args else {
+        return Err(format!("usage: {} <amount>", label));
+    };
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("invalid amount {:?}", amount))?;
+
+    let mut chain = blockchain.lock().unwrap();
+    let account = Account {
+        address: chain.wallet.get_public_key(),
+    };
+    let key_ownership_proof =
+        (txn_type == TransactionType::STAKE).then(|| crate::zkid::prove_key_ownership(&chain.wallet));
+    let nonce = chain.state.next_nonce(&account);
+    let wallet = &mut chain.wallet;
+    let txn = Transaction::new(wallet, account.clone(), account, amount, 0, nonce, txn_type, key_ownership_proof)
+        .map_err(|e| format!("failed to build transaction: {}", e))?;
+    drop(chain);
+
+    queue(rpc_sender, txn, label)
+}
+
+fn queue(rpc_sender: &UnboundedSender<Transaction>, txn: Transaction, label: &str) -> Result<String, String> {
+    let hash = hex::encode(txn.hash);
+    let amount = txn.amount;
+    rpc_sender
+        .send(txn)
+        .map_err(|_| "failed to queue transaction: node is shutting down".to_string())?;
+    Ok(format!(
+        "{} {} amount={} hash={}",
+        label.green().bold(),
+        "queued".cyan(),
+        amount,
+        hash
+    ))
+}
+
+fn peers(swarm: &Swarm<AppBehaviour>) -> String {
+    let peers: Vec<String> = swarm
+        .behaviour()
+        .gossipsub
+        .all_peers()
+        .map(|(peer_id, _)| peer_id.to_string())
+        .collect();
+
+    if peers.is_empty() {
+        "no connected peers".yellow().to_string()
+    } else {
+        format!("{} peer(s):\n{}", peers.len(), peers.join("\n"))
+    }
+}
+
+fn tps(tps_tracker: &Arc<Mutex<TpsTracker>>) -> String {
+    let tracker = tps_tracker.lock().unwrap();
+    let elapsed = tracker.start_time.elapsed().as_secs_f64();
+    let tps = if elapsed > 0.0 {
+        tracker.total_transactions_confirmed as f64 / elapsed
+    } else {
+        0.0
+    };
+    format!(
+        "{} confirmed={} elapsed={:.2}s tps={:.2}",
+        "tps".cyan().bold(),
+        tracker.total_transactions_confirmed,
+        elapsed,
+        tps
+    )
+}
+
+fn chain(blockchain: &Arc<Mutex<Blockchain>>) -> String {
+    let chain = blockchain.lock().unwrap();
+    let height = chain.chain.len();
+    match chain.chain.last() {
+        Some(tip) => format!(
+            "{} height={} latest_block_id={} hash={}",
+            "chain".cyan().bold(),
+            height,
+            tip.id,
+            hex::encode(tip.hash)
+        ),
+        None => format!("{} height=0 (no blocks yet)", "chain".cyan().bold()),
+    }
+}
@@ -2,5 +2,20 @@ pub const EPOCH_DURATION: u64 = 10;
 pub const BLOCK_INTERVAL: u64 = 6;
 pub const STAKING_AMOUNT: f64 = 100.00;
 
+// Maximum number of accounts that may sign the party-tree certificate for a
+// single block (see `Blockchain::party_tree`). Bounds certificate size and
+// signature-collection cost at a fixed ceiling instead of growing linearly
+// with however many accounts have ever staked.
+pub const MAX_VALIDATOR_SLOTS: usize = 100;
+
+// Default SQLite database path a node persists its chain, account state,
+// and hashchain commitments to, so a restart can replay its tip instead of
+// re-syncing genesis.
+pub const DEFAULT_DB_PATH: &str = "blockchain.db";
+
 // Maximum number of transactions to include in a single block
 pub const MAX_TXNS_PER_BLOCK: usize = 100; // Adjust as needed
+
+// Total reward units split among proposers/stakers at each epoch boundary,
+// proportional to stake (see `Epoch::settle_rewards`).
+pub const EPOCH_REWARD_POOL: u64 = 1000;
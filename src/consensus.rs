@@ -0,0 +1,225 @@
+//! Round-based, Tendermint-style prevote/precommit finality gadget layered
+//! over `BlockSignature` gossip and `Validator` stake state. It replaces
+//! best-effort signature counting with a deterministic rule: a height only
+//! commits once this node has independently tallied `>2/3` of total staked
+//! weight precommitting the *same* hash in the *same* round.
+//!
+//! Deliberately free of `Blockchain`/gossipsub so the quorum bookkeeping can
+//! be unit tested without spinning up a swarm: `Blockchain` owns one
+//! [`RoundState`] per in-flight height and feeds it votes via
+//! [`RoundState::record_vote`].
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Which phase of a round a `BlockSignature` is casting a vote for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VoteStep {
+    Prevote,
+    Precommit,
+}
+
+/// What a validator should do in response to `RoundState::record_vote`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoteOutcome {
+    /// No hash (or nil) has yet accumulated more than 2/3 of total stake.
+    Pending,
+    /// More than 2/3 of total stake prevoted `hash` (a "polka"): the caller
+    /// should lock onto it and broadcast its own Precommit.
+    PrevoteQuorum { hash: String },
+    /// More than 2/3 of total stake precommitted `hash` in this round: the
+    /// height is committed and its block can be executed.
+    Committed { hash: String },
+}
+
+/// Per-height round-voting state: every round's tallied votes, plus the
+/// value (if any) this validator has locked onto, so it never prevotes a
+/// conflicting block once locked — exactly the Tendermint locking rule this
+/// gadget is modeled on.
+#[derive(Debug, Default)]
+pub struct RoundState {
+    pub round: u64,
+    locked_hash: Option<String>,
+    committed_hash: Option<String>,
+    // round -> step -> voted hash (None = nil) -> voter address -> weight
+    tallies: HashMap<u64, HashMap<VoteStep, HashMap<Option<String>, HashMap<String, u64>>>>,
+}
+
+impl RoundState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The hash this validator is currently locked onto for this height, if
+    /// any. Once locked, a `record_vote` call with a `Prevote` for a
+    /// different hash is still tallied (other validators' votes can't be
+    /// suppressed), but this node's own next vote must honor the lock —
+    /// that decision belongs to the caller driving the gadget, not to
+    /// `RoundState` itself.
+    pub fn locked_hash(&self) -> Option<&str> {
+        self.locked_hash.as_deref()
+    }
+
+    pub fn is_committed(&self) -> bool {
+        self.committed_hash.is_some()
+    }
+
+    /// Records one validator's vote — `hash = None` for a nil vote cast on
+    /// a round timeout — and returns what this node should now do. Re-tallies
+    /// the affected round/step/hash bucket's total weight against
+    /// `total_weight`'s 2/3 threshold on every call, so votes can arrive in
+    /// any order and a duplicate vote from the same `voter` simply
+    /// overwrites its prior entry instead of double-counting.
+    pub fn record_vote(
+        &mut self,
+        round: u64,
+        step: VoteStep,
+        hash: Option<String>,
+        voter: String,
+        voter_weight: u64,
+        total_weight: u64,
+    ) -> VoteOutcome {
+        if let Some(committed) = &self.committed_hash {
+            return VoteOutcome::Committed {
+                hash: committed.clone(),
+            };
+        }
+
+        let bucket = self
+            .tallies
+            .entry(round)
+            .or_default()
+            .entry(step)
+            .or_default()
+            .entry(hash.clone())
+            .or_default();
+        bucket.insert(voter, voter_weight);
+
+        let Some(hash) = hash else {
+            return VoteOutcome::Pending;
+        };
+
+        let signed_weight: u64 = bucket.values().sum();
+        // Integer `> 2/3`, i.e. `3 * signed > 2 * total`, avoiding the
+        // truncation a `signed >= total * 2 / 3` check would introduce.
+        if signed_weight * 3 <= total_weight * 2 {
+            return VoteOutcome::Pending;
+        }
+
+        match step {
+            VoteStep::Prevote => {
+                self.locked_hash = Some(hash.clone());
+                VoteOutcome::PrevoteQuorum { hash }
+            }
+            VoteStep::Precommit => {
+                self.committed_hash = Some(hash.clone());
+                VoteOutcome::Committed { hash }
+            }
+        }
+    }
+
+    /// Advances to the next round after a timeout without quorum. Per
+    /// Tendermint, the locked value (if any) carries over into the new
+    /// round; only the vote tallies reset, and they reset implicitly since
+    /// a new round number starts an empty bucket in `tallies`.
+    pub fn advance_round(&mut self) {
+        self.round += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prevote_quorum_locks_and_returns_the_hash() {
+        let mut state = RoundState::new();
+        assert_eq!(
+            state.record_vote(0, VoteStep::Prevote, Some("H".into()), "a".into(), 40, 100),
+            VoteOutcome::Pending
+        );
+        let outcome = state.record_vote(0, VoteStep::Prevote, Some("H".into()), "b".into(), 34, 100);
+        assert_eq!(outcome, VoteOutcome::PrevoteQuorum { hash: "H".into() });
+        assert_eq!(state.locked_hash(), Some("H"));
+    }
+
+    #[test]
+    fn test_exactly_two_thirds_is_not_a_quorum() {
+        let mut state = RoundState::new();
+        // 66 of 99 is exactly 2/3, which must NOT clear a strict ">" bound.
+        let outcome = state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 66, 99);
+        assert_eq!(outcome, VoteOutcome::Pending);
+    }
+
+    #[test]
+    fn test_precommit_quorum_commits_the_hash() {
+        let mut state = RoundState::new();
+        state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 40, 100);
+        let outcome = state.record_vote(0, VoteStep::Precommit, Some("H".into()), "b".into(), 34, 100);
+        assert_eq!(outcome, VoteOutcome::Committed { hash: "H".into() });
+        assert!(state.is_committed());
+    }
+
+    #[test]
+    fn test_votes_for_different_hashes_do_not_combine() {
+        let mut state = RoundState::new();
+        state.record_vote(0, VoteStep::Prevote, Some("H1".into()), "a".into(), 50, 100);
+        let outcome = state.record_vote(0, VoteStep::Prevote, Some("H2".into()), "b".into(), 50, 100);
+        assert_eq!(
+            outcome,
+            VoteOutcome::Pending,
+            "a split vote across two hashes must not add up to a quorum for either"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_vote_from_same_voter_does_not_double_count() {
+        let mut state = RoundState::new();
+        state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 40, 100);
+        state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 40, 100);
+        let outcome = state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 40, 100);
+        assert_eq!(
+            outcome,
+            VoteOutcome::Pending,
+            "re-voting as the same validator must not let 40 count three times"
+        );
+    }
+
+    #[test]
+    fn test_votes_in_a_different_round_are_tallied_separately() {
+        let mut state = RoundState::new();
+        state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 40, 100);
+        let outcome = state.record_vote(1, VoteStep::Precommit, Some("H".into()), "b".into(), 34, 100);
+        assert_eq!(
+            outcome,
+            VoteOutcome::Pending,
+            "round 1's tally must start empty rather than inheriting round 0's votes"
+        );
+    }
+
+    #[test]
+    fn test_nil_vote_never_reaches_quorum() {
+        let mut state = RoundState::new();
+        let outcome = state.record_vote(0, VoteStep::Precommit, None, "a".into(), 90, 100);
+        assert_eq!(outcome, VoteOutcome::Pending);
+        assert!(!state.is_committed());
+    }
+
+    #[test]
+    fn test_advance_round_increments_round_counter() {
+        let mut state = RoundState::new();
+        assert_eq!(state.round, 0);
+        state.advance_round();
+        assert_eq!(state.round, 1);
+    }
+
+    #[test]
+    fn test_once_committed_further_votes_report_the_committed_hash() {
+        let mut state = RoundState::new();
+        state.record_vote(0, VoteStep::Precommit, Some("H".into()), "a".into(), 40, 100);
+        state.record_vote(0, VoteStep::Precommit, Some("H".into()), "b".into(), 34, 100);
+        let outcome = state.record_vote(5, VoteStep::Prevote, Some("other".into()), "c".into(), 1, 100);
+        assert_eq!(outcome, VoteOutcome::Committed { hash: "H".into() });
+    }
+}
@@ -1,17 +1,25 @@
+use crate::accounts::{Account, State};
 use crate::utils::Seed;
-use crate::config::EPOCH_DURATION;
+use std::collections::HashMap;
+
 pub struct Epoch {
     pub timestamp: u64,
+    /// How many ticks make up one epoch. Defaults to `EPOCH_DURATION` via
+    /// `Epoch::new`'s callers, but configurable per chain (see
+    /// `crate::chain_spec::ChainSpec::epoch_duration`) so a testnet/dev spec
+    /// can run shorter epochs without recompiling.
+    duration: u64,
 }
 
 
 impl Epoch {
-    pub fn new() -> Self {
-        Self { 
-            timestamp: 0 
+    pub fn new(duration: u64) -> Self {
+        Self {
+            timestamp: 0,
+            duration,
         }
     }
-    
+
     pub fn progress(&mut self) {
         self.timestamp += 1;
     }
@@ -21,6 +29,200 @@ impl Epoch {
     }
 
     pub fn is_end_of_epoch(&self) -> bool {
-        self.timestamp >= EPOCH_DURATION
+        self.timestamp >= self.duration
+    }
+
+    /// At the epoch boundary, credits every validator `accumulator` recorded
+    /// a block/stake for with its proportional share of the reward pool
+    /// (see `RewardAccumulator::compute_shares`), then resets the epoch
+    /// clock. A no-op before `is_end_of_epoch()`, so callers can invoke this
+    /// unconditionally alongside the rest of their end-of-epoch handling.
+    /// Does not clear `accumulator` itself: the caller owns its lifetime and
+    /// resets it (typically via `RewardAccumulator::reset`) once it's ready
+    /// to start recording the next epoch.
+    pub fn settle_rewards(&mut self, ledger: &mut State, accumulator: &RewardAccumulator) {
+        if !self.is_end_of_epoch() {
+            return;
+        }
+        for (account, share) in accumulator.compute_shares() {
+            if share == 0 {
+                continue;
+            }
+            ledger.add_account(account.clone());
+            ledger
+                .stake(account, share as f64)
+                .expect("a just-added account with a non-negative reward share is always a valid stake");
+        }
+        self.reset();
+    }
+}
+
+/// Tallies, over the course of one epoch, which validators proposed blocks
+/// and what stake they carried while doing so, so `Epoch::settle_rewards`
+/// can split `total_epoch_reward` among them proportional to stake.
+pub struct RewardAccumulator {
+    total_epoch_reward: u64,
+    block_counts: HashMap<Account, u64>,
+    stakes: HashMap<Account, f64>,
+}
+
+impl RewardAccumulator {
+    pub fn new(total_epoch_reward: u64) -> Self {
+        Self {
+            total_epoch_reward,
+            block_counts: HashMap::new(),
+            stakes: HashMap::new(),
+        }
+    }
+
+    /// Records that `proposer` produced a block while carrying `stake`
+    /// weight, overwriting any earlier stake snapshot with this (most
+    /// recent) one.
+    pub fn record_block(&mut self, proposer: Account, stake: f64) {
+        *self.block_counts.entry(proposer.clone()).or_insert(0) += 1;
+        self.stakes.insert(proposer, stake);
+    }
+
+    /// How many blocks `account` proposed this epoch.
+    pub fn block_count(&self, account: &Account) -> u64 {
+        self.block_counts.get(account).cloned().unwrap_or(0)
+    }
+
+    /// No validator has proposed a block (and so recorded stake) this epoch.
+    pub fn is_empty(&self) -> bool {
+        self.stakes.is_empty()
+    }
+
+    /// Clears every recorded block count and stake snapshot, ready for the
+    /// next epoch.
+    pub fn reset(&mut self) {
+        self.block_counts.clear();
+        self.stakes.clear();
+    }
+
+    fn total_stake(&self) -> f64 {
+        self.stakes.values().sum()
+    }
+
+    /// Splits `total_epoch_reward` among the recorded validators
+    /// proportional to stake: `share = total_epoch_reward * stake /
+    /// total_stake`, floored to whole reward units. Flooring leaves a
+    /// remainder of at most `stakes.len() - 1` units, which is credited
+    /// entirely to the highest-stake validator (ties broken by lowest
+    /// address) so the shares always sum to exactly `total_epoch_reward`
+    /// instead of drifting below it.
+    fn compute_shares(&self) -> Vec<(Account, u64)> {
+        let total_stake = self.total_stake();
+        if total_stake <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut shares: Vec<(Account, u64)> = self
+            .stakes
+            .iter()
+            .map(|(account, stake)| {
+                let share = (self.total_epoch_reward as f64 * stake / total_stake).floor() as u64;
+                (account.clone(), share)
+            })
+            .collect();
+
+        let distributed: u64 = shares.iter().map(|(_, share)| *share).sum();
+        let remainder = self.total_epoch_reward - distributed;
+        if remainder > 0 {
+            shares.sort_by(|(a, _), (b, _)| {
+                self.stakes[b]
+                    .partial_cmp(&self.stakes[a])
+                    .unwrap()
+                    .then_with(|| a.address.cmp(&b.address))
+            });
+            shares[0].1 += remainder;
+        }
+        shares
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_end_of_epoch_respects_the_configured_duration() {
+        let mut epoch = Epoch::new(3);
+        assert!(!epoch.is_end_of_epoch());
+        epoch.progress();
+        epoch.progress();
+        assert!(!epoch.is_end_of_epoch());
+        epoch.progress();
+        assert!(epoch.is_end_of_epoch());
+    }
+
+    #[test]
+    fn test_reset_restarts_the_epoch_clock() {
+        let mut epoch = Epoch::new(2);
+        epoch.progress();
+        epoch.progress();
+        assert!(epoch.is_end_of_epoch());
+        epoch.reset();
+        assert!(!epoch.is_end_of_epoch());
+    }
+
+    fn account(address: &str) -> Account {
+        Account { address: address.to_string() }
+    }
+
+    #[test]
+    fn test_settle_rewards_is_a_no_op_before_the_epoch_boundary() {
+        let mut epoch = Epoch::new(10);
+        let mut ledger = State::new();
+        let mut accumulator = RewardAccumulator::new(100);
+        accumulator.record_block(account("alice"), 100.0);
+
+        epoch.settle_rewards(&mut ledger, &accumulator);
+        assert_eq!(ledger.get_balance(account("alice")), 0.0);
+    }
+
+    #[test]
+    fn test_settle_rewards_splits_the_pool_proportional_to_stake() {
+        let mut epoch = Epoch::new(1);
+        epoch.progress();
+        let mut ledger = State::new();
+        let mut accumulator = RewardAccumulator::new(100);
+        accumulator.record_block(account("alice"), 75.0);
+        accumulator.record_block(account("bob"), 25.0);
+
+        epoch.settle_rewards(&mut ledger, &accumulator);
+
+        assert_eq!(ledger.get_balance(account("alice")), 75.0);
+        assert_eq!(ledger.get_balance(account("bob")), 25.0);
+        assert!(!epoch.is_end_of_epoch(), "settling rewards must reset the epoch clock");
+    }
+
+    #[test]
+    fn test_settle_rewards_assigns_the_rounding_remainder_to_the_highest_stake_validator() {
+        let mut epoch = Epoch::new(1);
+        epoch.progress();
+        let mut ledger = State::new();
+        let mut accumulator = RewardAccumulator::new(10);
+        accumulator.record_block(account("alice"), 2.0);
+        accumulator.record_block(account("bob"), 1.0);
+
+        // 10 * 2/3 = 6.67 -> 6, 10 * 1/3 = 3.33 -> 3; 1 leftover unit goes to
+        // alice, the higher-stake validator.
+        epoch.settle_rewards(&mut ledger, &accumulator);
+
+        assert_eq!(ledger.get_balance(account("alice")), 7.0);
+        assert_eq!(ledger.get_balance(account("bob")), 3.0);
+    }
+
+    #[test]
+    fn test_record_block_tracks_per_proposer_counts() {
+        let mut accumulator = RewardAccumulator::new(100);
+        accumulator.record_block(account("alice"), 50.0);
+        accumulator.record_block(account("alice"), 50.0);
+        accumulator.record_block(account("bob"), 50.0);
+
+        assert_eq!(accumulator.block_count(&account("alice")), 2);
+        assert_eq!(accumulator.block_count(&account("bob")), 1);
+        assert_eq!(accumulator.block_count(&account("carol")), 0);
     }
 }
@@ -0,0 +1,350 @@
+//! A Reed-Solomon-coded data-availability layer for block dissemination,
+//! modeled on the reliable-broadcast technique from hbbft: a block payload
+//! is split into `k` data shards, `n - k` parity shards are derived from a
+//! systematic generator matrix over `GF(256)`, and a `merkle::MerkleTreeBuilder`
+//! is built over all `n` shard byte-vectors so each shard can travel with a
+//! standalone inclusion proof. Any `k` verified shards (in any combination)
+//! are enough to `reconstruct` the original payload, so a node doesn't need
+//! all `n` peers to have answered, only a `k`-sized quorum of them — the
+//! same "f-of-n" tolerance hbbft relies on for broadcast.
+//!
+//! The field arithmetic and matrix inversion below are self-contained for
+//! the same reason `poseidon.rs`'s field arithmetic is: there's no
+//! erasure-coding dependency already in this crate to reach for.
+
+use crate::merkle::{CustomHasher, MerkleTreeBuilder};
+use rs_merkle::Hasher;
+
+/// `GF(256)` reduced modulo `x^8 + x^4 + x^3 + x^2 + 1` (0x11D), the
+/// polynomial commonly used for Reed-Solomon codes (e.g. QR codes).
+const GF_POLY: u16 = 0x11D;
+
+struct GaloisField {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+impl GaloisField {
+    fn new() -> Self {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        for i in 0..255usize {
+            exp[i] = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= GF_POLY;
+            }
+        }
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+        Self { exp, log }
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        let sum = self.log[a as usize] as usize + self.log[b as usize] as usize;
+        self.exp[sum]
+    }
+
+    fn inv(&self, a: u8) -> u8 {
+        assert!(a != 0, "zero has no multiplicative inverse in GF(256)");
+        self.exp[255 - self.log[a as usize] as usize]
+    }
+
+    fn div(&self, a: u8, b: u8) -> u8 {
+        if a == 0 {
+            return 0;
+        }
+        self.mul(a, self.inv(b))
+    }
+
+    fn pow(&self, a: u8, e: usize) -> u8 {
+        if e == 0 {
+            return 1;
+        }
+        if a == 0 {
+            return 0;
+        }
+        let log = self.log[a as usize] as usize * e % 255;
+        self.exp[log]
+    }
+}
+
+/// Inverts a `size x size` matrix over `GF(256)` via Gauss-Jordan
+/// elimination with partial pivoting. Errors if the matrix is singular
+/// (shouldn't happen for the Vandermonde-derived submatrices this module
+/// builds, since any `k` full rows of a `k`-column Vandermonde matrix with
+/// distinct evaluation points form a nonsingular matrix).
+fn invert_matrix(gf: &GaloisField, matrix: &[Vec<u8>], size: usize) -> Result<Vec<Vec<u8>>, String> {
+    let mut augmented: Vec<Vec<u8>> = (0..size)
+        .map(|i| {
+            let mut row = matrix[i].clone();
+            row.resize(size, 0);
+            let mut identity_row = vec![0u8; size];
+            identity_row[i] = 1;
+            row.extend(identity_row);
+            row
+        })
+        .collect();
+
+    for col in 0..size {
+        let pivot_row = (col..size)
+            .find(|&r| augmented[r][col] != 0)
+            .ok_or_else(|| "matrix is singular and cannot be inverted".to_string())?;
+        augmented.swap(col, pivot_row);
+
+        let pivot_inv = gf.inv(augmented[col][col]);
+        for value in augmented[col].iter_mut() {
+            *value = gf.mul(*value, pivot_inv);
+        }
+
+        for row in 0..size {
+            if row == col {
+                continue;
+            }
+            let factor = augmented[row][col];
+            if factor == 0 {
+                continue;
+            }
+            for c in 0..2 * size {
+                let scaled = gf.mul(factor, augmented[col][c]);
+                augmented[row][c] ^= scaled;
+            }
+        }
+    }
+
+    Ok(augmented.into_iter().map(|row| row[size..].to_vec()).collect())
+}
+
+/// Builds the `n x k` systematic generator matrix: starts from a
+/// Vandermonde matrix over distinct nonzero evaluation points `1..=n`, then
+/// left-multiplies by the inverse of its own top `k` rows so the result's
+/// first `k` rows are the identity (the data shards pass through
+/// unmodified) while every other `k`-row subset remains invertible too.
+fn generator_matrix(gf: &GaloisField, k: usize, n: usize) -> Result<Vec<Vec<u8>>, String> {
+    let vandermonde: Vec<Vec<u8>> = (0..n)
+        .map(|i| {
+            let x = (i + 1) as u8;
+            (0..k).map(|j| gf.pow(x, j)).collect()
+        })
+        .collect();
+
+    let top_k_inverse = invert_matrix(gf, &vandermonde[..k], k)?;
+
+    let mut generator = Vec::with_capacity(n);
+    for row in &vandermonde {
+        let mut out_row = vec![0u8; k];
+        for (j, slot) in out_row.iter_mut().enumerate() {
+            let mut acc = 0u8;
+            for (t, v) in row.iter().enumerate() {
+                acc ^= gf.mul(*v, top_k_inverse[t][j]);
+            }
+            *slot = acc;
+        }
+        generator.push(out_row);
+    }
+    Ok(generator)
+}
+
+/// One erasure-coded shard of a block payload, self-contained enough to be
+/// broadcast on its own: it carries its own Merkle inclusion proof against
+/// `merkle_root`, so a receiver can `verify_shard` it without first
+/// collecting every other shard.
+#[derive(Debug, Clone)]
+pub struct Shard {
+    pub index: usize,
+    pub bytes: Vec<u8>,
+    pub merkle_root: Vec<u8>,
+    pub proof: Vec<Vec<u8>>,
+    pub total_shards: usize,
+    pub data_shards: usize,
+    pub original_len: usize,
+}
+
+/// Splits `payload` into `k` data shards, derives `n - k` parity shards via
+/// the systematic generator matrix, and builds a Merkle tree over all `n`
+/// shard byte-vectors so each one can be emitted with its own proof.
+pub fn encode(payload: &[u8], k: usize, n: usize) -> Result<Vec<Shard>, String> {
+    if k == 0 || n < k {
+        return Err(format!("invalid erasure parameters: k={} n={}", k, n));
+    }
+
+    let gf = GaloisField::new();
+    let generator = generator_matrix(&gf, k, n)?;
+
+    let shard_len = payload.len().div_ceil(k).max(1);
+    let mut data_shards: Vec<Vec<u8>> = Vec::with_capacity(k);
+    for i in 0..k {
+        let start = i * shard_len;
+        let end = (start + shard_len).min(payload.len());
+        let mut shard = vec![0u8; shard_len];
+        if start < payload.len() {
+            shard[..end - start].copy_from_slice(&payload[start..end]);
+        }
+        data_shards.push(shard);
+    }
+
+    let mut shard_bytes: Vec<Vec<u8>> = Vec::with_capacity(n);
+    for row in &generator {
+        let mut out = vec![0u8; shard_len];
+        for (byte_pos, out_byte) in out.iter_mut().enumerate() {
+            let mut acc = 0u8;
+            for (j, coeff) in row.iter().enumerate() {
+                acc ^= gf.mul(*coeff, data_shards[j][byte_pos]);
+            }
+            *out_byte = acc;
+        }
+        shard_bytes.push(out);
+    }
+
+    let mut builder = MerkleTreeBuilder::new();
+    builder.build(&shard_bytes)?;
+    let root = builder.root();
+
+    let shards = (0..n)
+        .map(|i| Shard {
+            index: i,
+            bytes: shard_bytes[i].clone(),
+            merkle_root: root.clone(),
+            proof: builder.prove(&[i]),
+            total_shards: n,
+            data_shards: k,
+            original_len: payload.len(),
+        })
+        .collect();
+    Ok(shards)
+}
+
+/// Verifies `shard`'s Merkle proof against its own `merkle_root`, i.e. that
+/// this shard really is shard `shard.index` of the tree it claims to
+/// belong to.
+pub fn verify_shard(shard: &Shard) -> bool {
+    let leaf = CustomHasher::hash(&bincode::serialize(&shard.bytes).unwrap_or_default());
+    MerkleTreeBuilder::verify(
+        &shard.merkle_root,
+        &shard.proof,
+        &[shard.index],
+        shard.total_shards,
+        &[leaf],
+    )
+}
+
+/// Reconstructs the original payload from any `k` verified shards (more
+/// than `k` is fine; only the first `k` distinct indices are used). Errors
+/// if fewer than `k` shards are present, any shard fails `verify_shard`, or
+/// the shards disagree on which root they belong to.
+pub fn reconstruct(shards: &[Shard]) -> Result<Vec<u8>, String> {
+    let first = shards.first().ok_or("no shards provided")?;
+    let k = first.data_shards;
+    let root = first.merkle_root.clone();
+
+    let mut by_index: Vec<&Shard> = Vec::new();
+    for shard in shards {
+        if shard.merkle_root != root {
+            return Err("shards disagree on merkle root".to_string());
+        }
+        if !verify_shard(shard) {
+            return Err(format!("shard {} failed Merkle verification", shard.index));
+        }
+        if !by_index.iter().any(|s| s.index == shard.index) {
+            by_index.push(shard);
+        }
+    }
+    if by_index.len() < k {
+        return Err(format!(
+            "need at least {} verified shards, got {}",
+            k,
+            by_index.len()
+        ));
+    }
+    by_index.sort_by_key(|s| s.index);
+    by_index.truncate(k);
+
+    let gf = GaloisField::new();
+    let generator = generator_matrix(&gf, k, first.total_shards)?;
+    let selected_rows: Vec<Vec<u8>> = by_index.iter().map(|s| generator[s.index].clone()).collect();
+    let selected_inverse = invert_matrix(&gf, &selected_rows, k)?;
+
+    let shard_len = by_index[0].bytes.len();
+    let mut payload = Vec::with_capacity(k * shard_len);
+    for j in 0..k {
+        for byte_pos in 0..shard_len {
+            let mut acc = 0u8;
+            for (row, shard) in by_index.iter().enumerate() {
+                acc ^= gf.mul(selected_inverse[j][row], shard.bytes[byte_pos]);
+            }
+            payload.push(acc);
+        }
+    }
+
+    // Sanity-check the reconstruction by re-deriving every shard from the
+    // recovered payload and confirming the Merkle root it implies matches
+    // what every shard proved membership against.
+    let re_encoded = encode(&payload[..first.original_len.min(payload.len())], k, first.total_shards)?;
+    let re_root = re_encoded
+        .first()
+        .map(|s| s.merkle_root.clone())
+        .unwrap_or_default();
+    if re_root != root {
+        return Err("reconstructed payload does not reproduce the proven Merkle root".to_string());
+    }
+
+    payload.truncate(first.original_len);
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_then_reconstruct_from_exactly_k_shards() {
+        let payload = b"the quick brown fox jumps over the lazy dog".to_vec();
+        let shards = encode(&payload, 4, 7).expect("encode failed");
+
+        let subset: Vec<Shard> = shards[1..5].to_vec();
+        let recovered = reconstruct(&subset).expect("reconstruct failed");
+        assert_eq!(recovered, payload);
+    }
+
+    #[test]
+    fn test_reconstruct_from_systematic_shards_only() {
+        let payload = b"systematic shards should pass through untouched".to_vec();
+        let shards = encode(&payload, 4, 7).expect("encode failed");
+
+        let subset: Vec<Shard> = shards[..4].to_vec();
+        let recovered = reconstruct(&subset).expect("reconstruct failed");
+        assert_eq!(recovered, payload);
+    }
+
+    #[test]
+    fn test_reconstruct_from_parity_only_shards() {
+        let payload = b"parity-only reconstruction exercises the Vandermonde path".to_vec();
+        let shards = encode(&payload, 4, 8).expect("encode failed");
+
+        let subset: Vec<Shard> = shards[4..8].to_vec();
+        let recovered = reconstruct(&subset).expect("reconstruct failed");
+        assert_eq!(recovered, payload);
+    }
+
+    #[test]
+    fn test_verify_shard_rejects_tampered_bytes() {
+        let payload = b"tamper test".to_vec();
+        let mut shards = encode(&payload, 3, 5).expect("encode failed");
+        shards[0].bytes[0] ^= 0xFF;
+        assert!(!verify_shard(&shards[0]));
+    }
+
+    #[test]
+    fn test_reconstruct_errors_with_too_few_shards() {
+        let payload = b"not enough shards here".to_vec();
+        let shards = encode(&payload, 4, 7).expect("encode failed");
+        let subset: Vec<Shard> = shards[..2].to_vec();
+        assert!(reconstruct(&subset).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use crate::accounts::{Account, State};
+use crate::chain_spec::ChainSpec;
 use crate::transaction::Transaction;
 use serde::{Serialize, Deserialize};
 
@@ -10,13 +12,32 @@ impl Genesis {
     pub fn new(stake_txn: Transaction) -> Self {
         Self { stake_txn }
     }
+
+    /// Builds the pre-funded genesis ledger a `ChainSpec` describes: an
+    /// `Account` (with seeded balance) for every declared allocation, plus
+    /// the spec's initial staking set. Returns the ledger and staking
+    /// transactions separately rather than wrapping them in a `Genesis`,
+    /// since `Genesis` itself still models the single-validator bootstrap
+    /// gossiped over `p2p::GENESIS_TOPIC` (see `p2p.rs`), not a whole
+    /// chain-spec's worth of accounts.
+    pub fn from_spec(spec: &ChainSpec) -> (State, Vec<Transaction>) {
+        let mut state = State::new();
+        for allocation in &spec.allocations {
+            let account = Account {
+                address: allocation.address.clone(),
+            };
+            state.add_account(account.clone());
+            state.balances.insert(account, allocation.balance);
+        }
+        (state, spec.genesis_stakes.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::chain_spec::{Allocation, Network};
     use crate::wallet::Wallet;
-    use crate::accounts::Account;
     use crate::transaction::TransactionType;
 
     #[test]
@@ -24,15 +45,18 @@ mod tests {
         // Create a new wallet for testing
         let mut wallet = Wallet::new().unwrap();
         let account = Account { address: wallet.get_public_key().to_string() };
-        
+
         // Create a stake transaction
+        let key_ownership_proof = crate::zkid::prove_key_ownership(&wallet);
         let stake_txn = Transaction::new(
             &mut wallet,
             account.clone(),
             account.clone(),
             1000.0,
             0,
-            TransactionType::STAKE
+            0,
+            TransactionType::STAKE,
+            Some(key_ownership_proof)
         ).unwrap();
 
         // Create a hash chain message
@@ -52,4 +76,24 @@ mod tests {
         assert_eq!(deserialized.stake_txn.recipient.address, genesis.stake_txn.recipient.address);
         assert_eq!(deserialized.stake_txn.txn_type, genesis.stake_txn.txn_type);
     }
+
+    #[test]
+    fn test_from_spec_seeds_the_ledger_and_returns_the_genesis_stakes() {
+        let spec = ChainSpec {
+            network: Network::Dev,
+            epoch_duration: 5,
+            allocations: vec![
+                Allocation { address: "addr-1".to_string(), balance: 100.0 },
+                Allocation { address: "addr-2".to_string(), balance: 250.0 },
+            ],
+            genesis_stakes: vec![],
+        };
+
+        let (state, stakes) = Genesis::from_spec(&spec);
+
+        assert_eq!(state.get_balance(Account { address: "addr-1".to_string() }), 100.0);
+        assert_eq!(state.get_balance(Account { address: "addr-2".to_string() }), 250.0);
+        assert_eq!(state.accounts.len(), 2);
+        assert!(stakes.is_empty());
+    }
 }
\ No newline at end of file
@@ -12,7 +12,11 @@ pub struct HashChain {
 #[derive(Debug,Serialize, Deserialize, Clone)]
 pub struct HashChainCom {
     pub hash_chain_index: String,
-    pub sender: Account
+    pub sender: Account,
+    /// This validator's leader-lottery coin commitment for the epoch, so
+    /// peers can later verify its `LeaderProof`s without learning its
+    /// secret key. See `crate::lottery`.
+    pub coin_commitment: [u8; 32],
 }
 
 #[derive(Debug,Serialize, Deserialize, Clone)]
@@ -52,8 +56,12 @@ impl HashChain {
         HashChain { hash_chain }
     }
 
-    pub fn get_hash(&self, index: usize, sender: Account) -> HashChainCom {
-        HashChainCom { hash_chain_index: self.hash_chain[index].clone(), sender: sender }
+    pub fn get_hash(&self, index: usize, sender: Account, coin_commitment: [u8; 32]) -> HashChainCom {
+        HashChainCom {
+            hash_chain_index: self.hash_chain[index].clone(),
+            sender,
+            coin_commitment,
+        }
     }
 }
 
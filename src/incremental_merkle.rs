@@ -0,0 +1,271 @@
+//! An append-only Merkle tree that keeps a fixed-depth "frontier" (the
+//! rightmost filled node at each level, Tornado-Cash-style) so appending a
+//! leaf only touches `O(depth)` nodes instead of `merkle::MerkleTreeBuilder`'s
+//! full `O(n)` rebuild from every leaf. Useful for streaming in per-epoch
+//! sidechain commitments one at a time rather than re-hashing the whole
+//! history on every insert.
+//!
+//! `witness` still proves membership in the `MerkleTreeBuilder`/
+//! `verify_merkle_tree` proof-hash format, so downstream verifiers don't
+//! need to know whether a root was produced incrementally or via a one-shot
+//! `build`.
+
+use crate::merkle::CustomHasher;
+use rs_merkle::{Hasher, MerkleTree};
+use serde::Serialize;
+
+/// Snapshot of the tree's state at the time `checkpoint` was called, kept
+/// so a later `rewind` (e.g. unwinding commitments from a reorged block)
+/// can restore the frontier in `O(depth)` instead of replaying every leaf.
+struct Checkpoint<H: Hasher<Hash = [u8; 32]>> {
+    leaf_count: usize,
+    filled_subtrees: Vec<H::Hash>,
+    next_index: usize,
+    root: H::Hash,
+}
+
+/// A fixed-depth, append-only Merkle tree over an arbitrary `Hasher`.
+/// Defaults to [`CustomHasher`] (Keccak256) to match `MerkleTreeBuilder`.
+pub struct IncrementalMerkleTree<H: Hasher<Hash = [u8; 32]> = CustomHasher> {
+    depth: usize,
+    /// `empty_hashes[i]` is the root of an empty subtree of height `i`;
+    /// `empty_hashes[0]` is the hash of an empty leaf.
+    empty_hashes: Vec<H::Hash>,
+    /// The frontier: `filled_subtrees[i]` is the left sibling to combine
+    /// with the next hash bubbling up to level `i`, valid once that half
+    /// of the subtree has been filled.
+    filled_subtrees: Vec<H::Hash>,
+    /// All appended leaves, kept so `witness` can produce an authentication
+    /// path for any already-appended index without tracking a live witness
+    /// per position the way a marked-position "Bridge" would.
+    leaves: Vec<H::Hash>,
+    next_index: usize,
+    root: H::Hash,
+    checkpoints: Vec<Checkpoint<H>>,
+}
+
+impl<H: Hasher<Hash = [u8; 32]>> IncrementalMerkleTree<H> {
+    /// Builds an empty tree that can hold up to `2^depth` leaves.
+    pub fn new(depth: usize) -> Self {
+        let mut empty_hashes = Vec::with_capacity(depth + 1);
+        empty_hashes.push(H::hash(&[]));
+        for i in 0..depth {
+            let prev = empty_hashes[i];
+            empty_hashes.push(H::hash(&[prev.as_ref(), prev.as_ref()].concat()));
+        }
+
+        let root = empty_hashes[depth];
+        Self {
+            depth,
+            filled_subtrees: empty_hashes.clone(),
+            empty_hashes,
+            leaves: Vec::new(),
+            next_index: 0,
+            root,
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Number of leaves appended so far.
+    pub fn len(&self) -> usize {
+        self.next_index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.next_index == 0
+    }
+
+    /// Current root, reflecting every leaf appended so far.
+    pub fn root(&self) -> Vec<u8> {
+        self.root.as_ref().to_vec()
+    }
+
+    /// Serializes and appends `item` as the next leaf, updating the
+    /// frontier and root in `O(depth)` without touching any other leaf.
+    /// Returns the leaf's index.
+    pub fn append<T: Serialize>(&mut self, item: &T) -> Result<usize, String> {
+        if self.next_index >= (1usize << self.depth) {
+            return Err(format!(
+                "tree of depth {} is full ({} leaves)",
+                self.depth,
+                1usize << self.depth
+            ));
+        }
+
+        let bytes = bincode::serialize(item).map_err(|e| format!("Serialization error: {}", e))?;
+        let leaf_hash = H::hash(&bytes);
+        self.leaves.push(leaf_hash);
+
+        let mut index = self.next_index;
+        let mut current = leaf_hash;
+        for level in 0..self.depth {
+            let (left, right) = if index % 2 == 0 {
+                self.filled_subtrees[level] = current;
+                (current, self.empty_hashes[level])
+            } else {
+                (self.filled_subtrees[level], current)
+            };
+            current = H::hash(&[left.as_ref(), right.as_ref()].concat());
+            index /= 2;
+        }
+
+        // Fold the complete subtrees (one per set bit of the new leaf count)
+        // right to left, promoting an unpaired one the way `rs_merkle` does,
+        // so the root matches `MerkleTreeBuilder::build` over the same leaves.
+        let count = self.next_index + 1;
+        let mut root: Option<H::Hash> = None;
+        for level in 0..=self.depth {
+            if count & (1usize << level) == 0 { continue; }
+            let peak = if level == self.depth { current } else { self.filled_subtrees[level] };
+            root = Some(match root {
+                None => peak,
+                Some(right) => H::hash(&[peak.as_ref(), right.as_ref()].concat()),
+            });
+        }
+        self.root = root.unwrap_or(current);
+        self.next_index += 1;
+        Ok(self.next_index - 1)
+    }
+
+    /// Authentication path for leaf `index`, in the same `proof_hashes`
+    /// format `MerkleTreeBuilder::verify` expects. Rebuilds a one-shot
+    /// `rs_merkle` tree over the leaves stored so far: cheaper than the
+    /// incremental `append` is not the point here, correctness and format
+    /// compatibility are.
+    pub fn witness(&self, index: usize) -> Result<Vec<Vec<u8>>, String> {
+        if index >= self.leaves.len() {
+            return Err(format!(
+                "leaf {} has not been appended (tree has {} leaves)",
+                index,
+                self.leaves.len()
+            ));
+        }
+
+        let tree = MerkleTree::<H>::from_leaves(&self.leaves);
+        let proof = tree.proof(&[index]);
+        Ok(proof
+            .proof_hashes()
+            .iter()
+            .map(|hash| hash.as_ref().to_vec())
+            .collect())
+    }
+
+    /// Records the current state so a later `rewind` can restore it,
+    /// returning a checkpoint id to pass back in.
+    pub fn checkpoint(&mut self) -> usize {
+        self.checkpoints.push(Checkpoint {
+            leaf_count: self.leaves.len(),
+            filled_subtrees: self.filled_subtrees.clone(),
+            next_index: self.next_index,
+            root: self.root,
+        });
+        self.checkpoints.len() - 1
+    }
+
+    /// Rewinds the tree to the state it was in when `checkpoint_id` was
+    /// taken, discarding every leaf appended since (e.g. after a reorg
+    /// drops the blocks that contributed those commitments) along with any
+    /// later checkpoints.
+    pub fn rewind(&mut self, checkpoint_id: usize) -> Result<(), String> {
+        if checkpoint_id >= self.checkpoints.len() {
+            return Err(format!("no such checkpoint {}", checkpoint_id));
+        }
+
+        self.checkpoints.truncate(checkpoint_id + 1);
+        let restored = self
+            .checkpoints
+            .pop()
+            .expect("checkpoint_id was just checked to be in range");
+
+        self.leaves.truncate(restored.leaf_count);
+        self.filled_subtrees = restored.filled_subtrees;
+        self.next_index = restored.next_index;
+        self.root = restored.root;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::MerkleTreeBuilder;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Item(u64);
+
+    #[test]
+    fn test_new_tree_starts_empty() {
+        let tree: IncrementalMerkleTree = IncrementalMerkleTree::new(4);
+        assert_eq!(tree.len(), 0);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_append_matches_one_shot_build_root() {
+        let items: Vec<Item> = (0..8u64).map(Item).collect();
+
+        let mut incremental: IncrementalMerkleTree = IncrementalMerkleTree::new(3);
+        for item in &items {
+            incremental.append(item).expect("append failed");
+        }
+
+        let mut builder = MerkleTreeBuilder::new();
+        builder.build(&items).expect("build failed");
+
+        assert_eq!(incremental.root(), builder.root());
+    }
+
+    #[test]
+    fn test_witness_verifies_against_builder_verify() {
+        let items: Vec<Item> = (0..5u64).map(Item).collect();
+        let mut tree: IncrementalMerkleTree = IncrementalMerkleTree::new(3);
+        for item in &items {
+            tree.append(item).expect("append failed");
+        }
+
+        let bytes = bincode::serialize(&items[2]).unwrap();
+        let leaf = CustomHasher::hash(&bytes);
+        let proof = tree.witness(2).expect("witness failed");
+
+        assert!(MerkleTreeBuilder::verify(
+            &tree.root(),
+            &proof,
+            &[2],
+            tree.len(),
+            &[leaf],
+        ));
+    }
+
+    #[test]
+    fn test_witness_rejects_unappended_index() {
+        let tree: IncrementalMerkleTree = IncrementalMerkleTree::new(3);
+        assert!(tree.witness(0).is_err());
+    }
+
+    #[test]
+    fn test_append_rejects_once_full() {
+        let mut tree: IncrementalMerkleTree = IncrementalMerkleTree::new(1);
+        tree.append(&Item(1)).expect("first append failed");
+        tree.append(&Item(2)).expect("second append failed");
+        assert!(tree.append(&Item(3)).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_and_rewind_restores_root_and_length() {
+        let mut tree: IncrementalMerkleTree = IncrementalMerkleTree::new(4);
+        tree.append(&Item(1)).unwrap();
+        tree.append(&Item(2)).unwrap();
+        let checkpoint = tree.checkpoint();
+        let root_at_checkpoint = tree.root();
+
+        tree.append(&Item(3)).unwrap();
+        tree.append(&Item(4)).unwrap();
+        assert_ne!(tree.root(), root_at_checkpoint);
+
+        tree.rewind(checkpoint).expect("rewind failed");
+        assert_eq!(tree.root(), root_at_checkpoint);
+        assert_eq!(tree.len(), 2);
+        assert!(tree.witness(2).is_err());
+    }
+}
@@ -1,19 +1,36 @@
 pub mod accounts;
 pub mod block;
 pub mod blockchain;
+pub mod block_queue;
+pub mod bridge;
 pub mod ccok;
+pub mod chain_spec;
+pub mod command;
 pub mod config;
+pub mod consensus;
 pub mod epoch;
+pub mod erasure;
 pub mod genesis;
 pub mod hashchain;
+pub mod incremental_merkle;
+pub mod lottery;
 pub mod mempool;
 pub mod merkle;
+pub mod mnemonic;
 pub mod networking;
 pub mod p2p;
+pub mod poseidon;
+pub mod rln;
+pub mod settlement;
+pub mod sig_pool;
+pub mod sparse_merkle;
+pub mod storage;
 pub mod transaction;
 pub mod utils;
 pub mod validator;
+pub mod versioned_merkle;
 pub mod wallet;
+pub mod zkid;
 // Re-export main types for easier access
 pub use ccok::{Builder, Certificate, Params, Participant};
-pub use merkle::MerkleTreeBuilder;
+pub use merkle::{MerkleMultiProof, MerkleTreeBuilder};
@@ -0,0 +1,240 @@
+//! Private, VRF-style block-proposer lottery (modeled on Nomos's
+//! cryptarchia leadership), replacing the old public proposer-sorting
+//! predictor. Each validator holds a secret "coin" `(sk, nonce, weight)`
+//! bound to its staked weight; per slot it privately computes a ticket and
+//! only the winner ever learns it won, so competitors can't grind ahead of
+//! time to figure out who the next proposer will be. Both the published
+//! commitment and ticket are derived purely from public inputs (the
+//! proposer's public key, its registered weight, and the coin's current
+//! `nonce`), so `LeaderProof::verify` recomputes and checks them itself
+//! rather than trusting whatever a proposer claims.
+
+use blake2::digest::{Update, VariableOutput};
+use blake2::Blake2bVar;
+use serde::{Deserialize, Serialize};
+
+/// Default active-slot coefficient `f`: the target fraction of slots with at
+/// least one leader.
+pub const ACTIVE_SLOT_COEFFICIENT: f64 = 0.05;
+
+/// A validator's private lottery ticket. `sk` must never leave the node
+/// that owns it; only `commitment()` and `LeaderProof`s derived from it are
+/// ever published.
+#[derive(Debug, Clone, Copy)]
+pub struct Coin {
+    pub sk: [u8; 32],
+    pub nonce: [u8; 32],
+    pub weight: f64,
+}
+
+impl Coin {
+    pub fn new(sk: [u8; 32], nonce: [u8; 32], weight: f64) -> Self {
+        Self { sk, nonce, weight }
+    }
+
+    /// Commitment binding this coin to its owner's public key and staked
+    /// weight: `C = H(pk || weight)`. Unlike a commitment to `(sk, nonce)`,
+    /// this is recomputable by any node that already knows the validator
+    /// set, so `LeaderProof::verify` can check it directly against the
+    /// proposer's registered stake instead of trusting a pre-published
+    /// opaque hash.
+    pub fn commitment(&self, pk: &[u8]) -> [u8; 32] {
+        Self::commitment_for(pk, self.weight)
+    }
+
+    fn commitment_for(pk: &[u8], weight: f64) -> [u8; 32] {
+        hash_labeled(b"commit", &[pk, &weight.to_be_bytes()])
+    }
+
+    /// Per-slot ticket `t = H("lead" || epoch_nonce || slot || nonce)`.
+    /// Deliberately excludes `sk`, so once `nonce` is published in a
+    /// `LeaderProof` anyone can recompute `t` and check it themselves
+    /// rather than trusting a self-reported value.
+    fn ticket_for(epoch_nonce: &[u8; 32], slot: usize, nonce: &[u8; 32]) -> [u8; 32] {
+        hash_labeled(b"lead", &[epoch_nonce, &slot.to_be_bytes(), nonce])
+    }
+
+    /// Nullifier for this coin, preventing it from winning twice within the
+    /// same epoch. Still bound to `sk` (unlike the ticket), since only the
+    /// coin's owner ever needs to produce it and it is never recomputed by
+    /// a verifier — only checked for prior use.
+    fn nullifier(&self) -> [u8; 32] {
+        hash_labeled(b"nullifier", &[&self.sk, &self.nonce])
+    }
+
+    /// Evolves the coin so a spent one can't be reused:
+    /// `nonce' = H("coin-evolve" || sk || nonce)`.
+    pub fn evolve(&self) -> Self {
+        Self {
+            sk: self.sk,
+            nonce: hash_labeled(b"coin-evolve", &[&self.sk, &self.nonce]),
+            weight: self.weight,
+        }
+    }
+
+    /// Tries to win the lottery for `slot`, returning a `LeaderProof` iff
+    /// the privately-computed ticket clears the epoch's win threshold.
+    pub fn try_claim_slot(
+        &self,
+        epoch_nonce: [u8; 32],
+        slot: usize,
+        total_active_stake: f64,
+        active_slot_coefficient: f64,
+        pk: &[u8],
+    ) -> Option<LeaderProof> {
+        let alpha = self.weight / total_active_stake;
+        let threshold = phi(active_slot_coefficient, alpha);
+        let ticket = Self::ticket_for(&epoch_nonce, slot, &self.nonce);
+        if ticket_fraction(&ticket) < threshold {
+            Some(LeaderProof {
+                epoch_nonce,
+                slot,
+                nonce: self.nonce,
+                commitment: self.commitment(pk),
+                nullifier: self.nullifier(),
+                ticket,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// `phi(f, alpha) = 1 - (1 - f)^alpha`: the probability that a coin holding
+/// stake share `alpha` wins a given slot, under active-slot coefficient `f`.
+pub fn phi(f: f64, alpha: f64) -> f64 {
+    1.0 - (1.0 - f).powf(alpha)
+}
+
+/// Maps a 256-bit ticket onto `[0, 1)` by reading its leading 8 bytes as a
+/// big-endian fraction of `2^64`, matching the `h / 2^256 < phi(...)` rule
+/// up to the precision of an `f64`.
+fn ticket_fraction(ticket: &[u8; 32]) -> f64 {
+    let mut high = [0u8; 8];
+    high.copy_from_slice(&ticket[0..8]);
+    (u64::from_be_bytes(high) as f64) / (u64::MAX as f64 + 1.0)
+}
+
+fn hash_labeled(label: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Blake2bVar::new(32).expect("32 is a valid Blake2b output size");
+    hasher.update(label);
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut out = [0u8; 32];
+    hasher
+        .finalize_variable(&mut out)
+        .expect("output buffer matches the requested Blake2b size");
+    out
+}
+
+/// A published proof that a coin privately won the leader lottery for a
+/// slot, verifiable by any node against the proposer's public key and
+/// registered stake without ever learning its secret key: both the
+/// commitment and the ticket are recomputed from public inputs rather than
+/// trusted at face value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderProof {
+    pub epoch_nonce: [u8; 32],
+    pub slot: usize,
+    pub nonce: [u8; 32],
+    pub commitment: [u8; 32],
+    pub nullifier: [u8; 32],
+    pub ticket: [u8; 32],
+}
+
+impl LeaderProof {
+    /// Verifies the proof against the proposer's public key and its
+    /// *registered* `weight` (i.e. the caller's own view of that
+    /// validator's staked balance, not anything taken from the proof
+    /// itself): recomputes `commitment` from `(pk, weight)`, recomputes
+    /// `ticket` from `(epoch_nonce, slot, nonce)`, and checks both match
+    /// what was published and that the ticket clears the win threshold for
+    /// `weight`. Callers must separately check `nullifier` against the set
+    /// of nullifiers already spent this epoch to reject equivocation.
+    pub fn verify(
+        &self,
+        pk: &[u8],
+        weight: f64,
+        total_active_stake: f64,
+        active_slot_coefficient: f64,
+    ) -> bool {
+        if self.commitment != Coin::commitment_for(pk, weight) {
+            return false;
+        }
+        let recomputed_ticket = Coin::ticket_for(&self.epoch_nonce, self.slot, &self.nonce);
+        if recomputed_ticket != self.ticket {
+            return false;
+        }
+        let alpha = weight / total_active_stake;
+        let threshold = phi(active_slot_coefficient, alpha);
+        ticket_fraction(&recomputed_ticket) < threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phi_monotonically_increases_with_stake_share() {
+        assert!(phi(0.05, 0.1) < phi(0.05, 0.5));
+        assert!(phi(0.05, 0.5) < phi(0.05, 1.0));
+    }
+
+    #[test]
+    fn test_coin_evolves_to_a_different_nonce_but_same_weight() {
+        let coin = Coin::new([1u8; 32], [2u8; 32], 100.0);
+        let evolved = coin.evolve();
+        assert_ne!(coin.nonce, evolved.nonce);
+        assert_eq!(coin.sk, evolved.sk);
+        assert_eq!(coin.weight, evolved.weight);
+    }
+
+    #[test]
+    fn test_nullifier_changes_after_evolution() {
+        let coin = Coin::new([3u8; 32], [4u8; 32], 50.0);
+        let evolved = coin.evolve();
+        assert_ne!(coin.nullifier(), evolved.nullifier());
+    }
+
+    #[test]
+    fn test_leader_proof_round_trips_through_verify() {
+        let coin = Coin::new([5u8; 32], [6u8; 32], 1000.0);
+        let epoch_nonce = [9u8; 32];
+        let pk = b"validator-pk";
+        // With weight == total stake, alpha == 1, so phi == f; scan forward
+        // until a slot actually wins to exercise verification end-to-end.
+        let proof = (0..10_000)
+            .find_map(|slot| coin.try_claim_slot(epoch_nonce, slot, 1000.0, 0.9, pk))
+            .expect("a coin holding 100% of stake with f=0.9 should win within 10000 slots");
+
+        assert!(proof.verify(pk, 1000.0, 1000.0, 0.9));
+        assert!(!proof.verify(b"someone-else", 1000.0, 1000.0, 0.9));
+        assert!(!proof.verify(pk, 999.0, 1000.0, 0.9), "a verifier using the wrong registered weight must reject the proof");
+    }
+
+    #[test]
+    fn test_try_claim_slot_never_wins_with_zero_active_slot_coefficient() {
+        let coin = Coin::new([7u8; 32], [8u8; 32], 1000.0);
+        let epoch_nonce = [1u8; 32];
+        let won = (0..1_000).any(|slot| coin.try_claim_slot(epoch_nonce, slot, 1000.0, 0.0, b"validator-pk").is_some());
+        assert!(!won, "an active-slot coefficient of 0 must never produce a winner");
+    }
+
+    #[test]
+    fn test_verify_rejects_a_ticket_that_does_not_match_the_published_nonce() {
+        let coin = Coin::new([5u8; 32], [6u8; 32], 1000.0);
+        let epoch_nonce = [9u8; 32];
+        let pk = b"validator-pk";
+        let mut proof = (0..10_000)
+            .find_map(|slot| coin.try_claim_slot(epoch_nonce, slot, 1000.0, 0.9, pk))
+            .expect("a coin holding 100% of stake with f=0.9 should win within 10000 slots");
+
+        // A forger can't just assert a winning ticket: it must actually be
+        // `H("lead" || epoch_nonce || slot || nonce)`, which `verify` now
+        // recomputes itself instead of trusting the proof's claimed value.
+        proof.ticket = [0u8; 32];
+        assert!(!proof.verify(pk, 1000.0, 1000.0, 0.9));
+    }
+}
@@ -19,22 +19,40 @@ use tokio::{
 mod accounts;
 mod block;
 mod blockchain;
+mod block_queue;
+mod bridge;
 mod ccok;
+mod chain_spec;
+mod command;
 mod config;
+mod consensus;
 mod epoch;
+mod erasure;
 mod genesis;
 mod hashchain;
+mod incremental_merkle;
+mod lottery;
 mod mempool;
 mod merkle;
+mod mnemonic;
 mod networking;
 mod p2p;
+mod poseidon;
+mod rln;
+mod settlement;
+mod sig_pool;
+mod sparse_merkle;
+mod storage;
 mod transaction;
 mod utils;
 mod validator;
+mod versioned_merkle;
 mod wallet;
+mod zkid;
 
 use accounts::Account;
 use blockchain::Blockchain;
+use chain_spec::{ChainSpec, Network};
 use config::*;
 use genesis::Genesis;
 use hashchain::HashChain;
@@ -54,7 +72,28 @@ async fn main() {
     let (rpc_sender, mut rpc_rcv) = mpsc::unbounded_channel::<Transaction>();
 
     let wallet = wallet::Wallet::new().unwrap();
-    let blockchain = Arc::new(Mutex::new(Blockchain::new(wallet)));
+
+    // Nodes launch from an optional JSON chain spec (`CHAIN_SPEC_PATH`,
+    // defaulting to `chain_spec.json` in the working directory) so an
+    // operator can point at an isolated testnet/dev spec without
+    // recompiling; with no spec file present, fall back to a `Dev` spec
+    // matching `crate::config`'s compiled-in defaults and no extra
+    // allocations, which behaves exactly like the old hardcoded startup.
+    let chain_spec_path =
+        std::env::var("CHAIN_SPEC_PATH").unwrap_or_else(|_| "chain_spec.json".to_string());
+    let chain_spec = std::fs::read_to_string(&chain_spec_path)
+        .ok()
+        .and_then(|json| ChainSpec::from_json(&json).ok())
+        .unwrap_or(ChainSpec {
+            network: Network::Dev,
+            epoch_duration: EPOCH_DURATION,
+            allocations: vec![],
+            genesis_stakes: vec![],
+        });
+
+    let blockchain = Blockchain::open_with_spec(wallet, DEFAULT_DB_PATH, &chain_spec)
+        .expect("failed to open blockchain storage");
+    let blockchain = Arc::new(Mutex::new(blockchain));
 
     // --- Initialize TPS Tracker ---
     let tps_tracker = Arc::new(Mutex::new(TpsTracker {
@@ -168,14 +207,14 @@ async fn main() {
         if let Some(event) = evt {
             match event {
                 EventType::Command(cmd) => {
-                    // TODO: handle commands
-                    info!("command: {:?}", cmd);
+                    command::dispatch(&cmd, &blockchain, &mut swarm, &tps_tracker, &rpc_sender);
                 }
 
                 EventType::Genesis => {
                     let mut blockchain_guard = blockchain.lock().unwrap();
                     info!("Genesis event");
                     // Create a stake transaction
+                    let key_ownership_proof = crate::zkid::prove_key_ownership(&blockchain_guard.wallet);
                     let wallet = &mut blockchain_guard.wallet;
                     let public_key_str = wallet.get_public_key().to_string();
                     let account = Account {
@@ -188,7 +227,9 @@ async fn main() {
                         account.clone(),
                         100.00,
                         0,
+                        0,
                         TransactionType::STAKE,
+                        Some(key_ownership_proof),
                     )
                     .unwrap();
                     let genesis = Genesis::new(stake_txn.clone());
@@ -218,6 +259,7 @@ async fn main() {
                     let hash_chain_message = HashChainCom {
                         hash_chain_index: commitment.clone(),
                         sender: my_address.clone(),
+                        coin_commitment: blockchain.coin_commitment(),
                     };
 
                     blockchain
@@ -299,8 +341,13 @@ async fn main() {
         swarm: &mut libp2p::Swarm<p2p::AppBehaviour>,
         tps_tracker: Arc<Mutex<TpsTracker>>,
     ) {
-        let proposer = blockchain.select_block_proposer(seed);
-        if proposer.address == blockchain.wallet.get_public_key().to_string() {
+        // Privately check the leader lottery first: only a node whose coin
+        // wins this slot ever proposes, so the rest of the network can't
+        // grind ahead of time to learn who the next proposer will be.
+        let my_address = Account {
+            address: blockchain.wallet.get_public_key().to_string(),
+        };
+        if let Some(leader_proof) = blockchain.try_claim_block(seed) {
             info!(
                 "{}",
                 format!(
@@ -309,21 +356,23 @@ async fn main() {
                 )
                 .bright_green()
             );
+            // `get_hash` only hands back `.hash_chain_index` below; the
+            // coin commitment it also bundles is irrelevant here and left
+            // as a placeholder.
             let hash_chain_index = blockchain.hash_chain.get_hash(
                 EPOCH_DURATION as usize - blockchain.epoch.timestamp as usize + 1,
-                proposer.clone(),
+                my_address.clone(),
+                [0u8; 32],
             );
             // --- Fetch Transactions from Mempool ---
             let txns_to_include = blockchain.mempool.get_transactions(MAX_TXNS_PER_BLOCK);
             // --- End Fetch Transactions ---
-            let my_address = Account {
-                address: blockchain.wallet.get_public_key().to_string(),
-            };
             let new_block = blockchain.propose_block(
                 hash_chain_index.hash_chain_index,
                 my_address,
                 txns_to_include,
-                seed);
+                seed,
+                Some(leader_proof));
             let confirmed_txns_count = new_block.txn.len() as u64;
             blockchain.execute_block(new_block.clone());
 
@@ -1,5 +1,6 @@
+use crate::poseidon::PoseidonHasher;
 use rs_merkle::{Hasher, MerkleProof, MerkleTree};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 
 /// Custom hasher using Keccak256 (SHA3)
@@ -16,11 +17,23 @@ impl Hasher for CustomHasher {
     }
 }
 
-pub struct MerkleTreeBuilder {
-    tree: MerkleTree<CustomHasher>,
+/// Builds, proves against, and verifies a Merkle tree over an arbitrary
+/// `Hasher`. Defaults to [`CustomHasher`] (Keccak256) so existing callers
+/// are unaffected; pass [`PoseidonMerkleTreeBuilder`]'s hasher instead when
+/// the tree needs to be opened inside a ZK circuit (see
+/// `poseidon::poseidon_merkle_root_gadget`), since Poseidon's arithmetic
+/// (field add/mul) is vastly cheaper to arithmetize than Keccak's bitwise
+/// permutation.
+pub struct MerkleTreeBuilder<H: Hasher<Hash = [u8; 32]> = CustomHasher> {
+    tree: MerkleTree<H>,
 }
 
-impl MerkleTreeBuilder {
+/// A `MerkleTreeBuilder` whose leaves and internal nodes are committed with
+/// the Poseidon-based [`PoseidonHasher`] instead of Keccak256, so membership
+/// in the resulting root can be proven with `poseidon::poseidon_merkle_root_gadget`.
+pub type PoseidonMerkleTreeBuilder = MerkleTreeBuilder<PoseidonHasher>;
+
+impl<H: Hasher<Hash = [u8; 32]>> MerkleTreeBuilder<H> {
     /// Create a new empty Merkle tree
     pub fn new() -> Self {
         Self {
@@ -32,22 +45,34 @@ impl MerkleTreeBuilder {
     pub fn build<T: Serialize>(&mut self, items: &[T]) -> Result<(), String> {
         let leaves: Vec<[u8; 32]> = items
             .iter()
-            .map(|item| {
-                let bytes =
-                    bincode::serialize(item).map_err(|e| format!("Serialization error: {}", e))?;
-                Ok(CustomHasher::hash(&bytes))
-            })
+            .map(Self::hash_leaf)
             .collect::<Result<Vec<_>, String>>()?;
 
-        self.tree = MerkleTree::<CustomHasher>::from_leaves(&leaves);
+        self.tree = MerkleTree::<H>::from_leaves(&leaves);
         Ok(())
     }
 
+    /// Serializes `item` the same way every leaf in `build` is hashed
+    /// (`bincode` then `H::hash`), so call sites that need to recompute a
+    /// single leaf hash to check against a proof — rather than rebuild a
+    /// whole tree — use exactly one serialization path instead of
+    /// duplicating it ad hoc.
+    pub fn hash_leaf<T: Serialize>(item: &T) -> Result<H::Hash, String> {
+        let bytes =
+            bincode::serialize(item).map_err(|e| format!("Serialization error: {}", e))?;
+        Ok(H::hash(&bytes))
+    }
+
     /// Get the root hash of the Merkle tree
     pub fn root(&self) -> Vec<u8> {
         self.tree.root().unwrap_or_default().to_vec()
     }
 
+    /// Total number of leaves committed into the tree.
+    pub fn leaves_len(&self) -> usize {
+        self.tree.leaves_len()
+    }
+
     /// Generate Merkle proofs for given positions
     pub fn prove(&self, positions: &[usize]) -> Vec<Vec<u8>> {
         let proof = self.tree.proof(positions);
@@ -58,6 +83,41 @@ impl MerkleTreeBuilder {
             .collect()
     }
 
+    /// Generates a self-contained inclusion proof for the single leaf at
+    /// `index`, suitable for a caller that only ever holds that one leaf's
+    /// encoding rather than the full dataset `build` was called with (unlike
+    /// `MerkleMultiProof::new`, which re-hashes leaves from the original
+    /// items). Proof hashes are collected into a vector sized to the tree's
+    /// depth up front, since that's the most sibling hashes a single
+    /// inclusion proof carries, avoiding reallocation as rows are appended.
+    pub fn prove_leaf(&self, index: usize) -> MerkleInclusionProof {
+        let proof = self.tree.proof(&[index]);
+        let depth = (self.leaves_len().max(1) as f64).log2().ceil() as usize;
+        let mut proof_hashes = Vec::with_capacity(depth);
+        proof_hashes.extend(proof.proof_hashes().iter().map(|hash| hash.to_vec()));
+        MerkleInclusionProof {
+            index,
+            proof_hashes,
+            total_leaves: self.leaves_len(),
+        }
+    }
+
+    /// Verifies that `leaf` (hashed the same way as any other leaf bytes,
+    /// with no re-serialization step) is included in the tree committed to
+    /// by `root` at `proof.index`, generic over anything that can be viewed
+    /// as a byte slice so callers don't need the original typed item — only
+    /// its encoding and the proof that travelled with it.
+    pub fn verify_leaf<L: AsRef<[u8]>>(root: &[u8], leaf: L, proof: &MerkleInclusionProof) -> bool {
+        let leaf_hash = H::hash(leaf.as_ref());
+        Self::verify(
+            root,
+            &proof.proof_hashes,
+            &[proof.index],
+            proof.total_leaves,
+            &[leaf_hash],
+        )
+    }
+
     /// Verify a Merkle proof
     pub fn verify(
         root: &[u8],
@@ -66,7 +126,7 @@ impl MerkleTreeBuilder {
         total_leaves: usize,
         leaves: &[[u8; 32]],
     ) -> bool {
-        let proof = MerkleProof::<CustomHasher>::new(
+        let proof = MerkleProof::<H>::new(
             proof_hashes
                 .iter()
                 .map(|h| {
@@ -82,10 +142,265 @@ impl MerkleTreeBuilder {
 
         proof.verify(root_hash, positions, leaves, total_leaves)
     }
+
+    /// Verifies a multiproof given as `(position, leaf_hash)` pairs plus the
+    /// accompanying proof hashes, directly against `root` — this never
+    /// reconstructs the tree from the revealed subset, it only folds the
+    /// subset's own hashes up alongside the proof's sibling hashes. Pairs
+    /// need not arrive pre-sorted: they're sorted here first, then split
+    /// into position/leaf vectors preallocated to the pair count up front
+    /// (the same preallocate-each-row discipline as parity-zcash's
+    /// `merkle_root`, which sizes every parent row with
+    /// `Vec::with_capacity(n / 2)` instead of growing it one push at a
+    /// time) before handing off to the underlying proof fold.
+    pub fn verify_multiproof(
+        root: &[u8],
+        proof_hashes: &[Vec<u8>],
+        leaf_pairs: &[(usize, H::Hash)],
+        total_leaves: usize,
+    ) -> bool {
+        let mut sorted_pairs = leaf_pairs.to_vec();
+        sorted_pairs.sort_unstable_by_key(|(pos, _)| *pos);
+
+        let mut positions = Vec::with_capacity(sorted_pairs.len());
+        let mut leaves = Vec::with_capacity(sorted_pairs.len());
+        for (pos, leaf) in sorted_pairs {
+            positions.push(pos);
+            leaves.push(leaf);
+        }
+
+        Self::verify(root, proof_hashes, &positions, total_leaves, &leaves)
+    }
 }
 
-impl Default for MerkleTreeBuilder {
+impl<H: Hasher<Hash = [u8; 32]>> Default for MerkleTreeBuilder<H> {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// A self-contained, serializable Merkle inclusion proof for a single leaf,
+/// produced by [`MerkleTreeBuilder::prove_leaf`] and checked with
+/// [`MerkleTreeBuilder::verify_leaf`]. Unlike [`MerkleMultiProof`], which
+/// bundles the committed leaf hashes of a whole subset re-derived from the
+/// original dataset, this only ever concerns one leaf and expects the
+/// caller to supply its bytes directly — the shape a light client needs
+/// when it holds a single record (e.g. one validator's entry) rather than
+/// the full set the tree was built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleInclusionProof {
+    /// The leaf's position in the tree.
+    pub index: usize,
+    /// The accompanying Merkle proof hashes.
+    pub proof_hashes: Vec<Vec<u8>>,
+    /// Total number of leaves in the tree the proof was generated against.
+    pub total_leaves: usize,
+}
+
+/// A self-contained, serializable Merkle inclusion proof for an arbitrary
+/// subset of a tree's leaves. Unlike `MerkleTreeBuilder::prove`/`verify`,
+/// which split the proof hashes from the positions, leaves and leaf count a
+/// caller must separately track, this bundles everything needed to check
+/// membership into one opaque blob that can be transmitted and verified on
+/// its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerkleMultiProof {
+    /// Sorted, deduplicated leaf positions this proof attests membership for.
+    pub positions: Vec<usize>,
+    /// The committed leaf hashes at `positions`, in the same order.
+    pub leaves: Vec<[u8; 32]>,
+    /// The accompanying Merkle proof hashes.
+    pub proof_hashes: Vec<Vec<u8>>,
+    /// Total number of leaves in the tree the proof was generated against.
+    pub total_leaves: usize,
+}
+
+impl MerkleMultiProof {
+    /// Builds a multiproof for `positions` against `builder`, re-hashing
+    /// `items` at those positions so the leaves travel with the proof.
+    /// `items` must be the same list (and order) `builder` was built from.
+    pub fn new<T: Serialize>(
+        builder: &MerkleTreeBuilder,
+        items: &[T],
+        positions: &[usize],
+    ) -> Result<Self, String> {
+        let mut sorted_positions = positions.to_vec();
+        sorted_positions.sort_unstable();
+        sorted_positions.dedup();
+
+        // One hash per requested position; for large validator sets this
+        // avoids repeated reallocation as the subset is filled in.
+        let mut leaves = Vec::with_capacity(sorted_positions.len());
+        for &pos in &sorted_positions {
+            let item = items
+                .get(pos)
+                .ok_or_else(|| format!("position {} out of range", pos))?;
+            leaves.push(MerkleTreeBuilder::<CustomHasher>::hash_leaf(item)?);
+        }
+
+        let proof_hashes = builder.prove(&sorted_positions);
+
+        Ok(Self {
+            positions: sorted_positions,
+            leaves,
+            proof_hashes,
+            total_leaves: builder.leaves_len(),
+        })
+    }
+
+    /// Verifies this proof's leaves are included in the tree committed to by
+    /// `root`.
+    pub fn verify(&self, root: &[u8]) -> bool {
+        MerkleTreeBuilder::verify(
+            root,
+            &self.proof_hashes,
+            &self.positions,
+            self.total_leaves,
+            &self.leaves,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Item(u64);
+
+    fn build_tree(count: usize) -> (MerkleTreeBuilder, Vec<Item>) {
+        let items: Vec<Item> = (0..count as u64).map(Item).collect();
+        let mut builder = MerkleTreeBuilder::new();
+        builder.build(&items).expect("failed to build tree");
+        (builder, items)
+    }
+
+    #[test]
+    fn test_multiproof_round_trips_through_bincode() {
+        let (builder, items) = build_tree(5);
+        let proof = MerkleMultiProof::new(&builder, &items, &[1, 3])
+            .expect("failed to build multiproof");
+
+        let bytes = bincode::serialize(&proof).expect("failed to serialize multiproof");
+        let decoded: MerkleMultiProof =
+            bincode::deserialize(&bytes).expect("failed to deserialize multiproof");
+
+        assert!(decoded.verify(&builder.root()));
+    }
+
+    #[test]
+    fn test_multiproof_single_leaf_tree_root_equals_leaf() {
+        let (builder, items) = build_tree(1);
+        let proof =
+            MerkleMultiProof::new(&builder, &items, &[0]).expect("failed to build multiproof");
+
+        assert_eq!(proof.leaves.len(), 1);
+        assert_eq!(builder.root(), proof.leaves[0].to_vec());
+        assert!(proof.verify(&builder.root()));
+    }
+
+    #[test]
+    fn test_multiproof_full_set() {
+        let (builder, items) = build_tree(7);
+        let all_positions: Vec<usize> = (0..items.len()).collect();
+        let proof = MerkleMultiProof::new(&builder, &items, &all_positions)
+            .expect("failed to build multiproof");
+
+        assert!(proof.verify(&builder.root()));
+    }
+
+    #[test]
+    fn test_multiproof_empty_subset() {
+        let (builder, items) = build_tree(4);
+        let proof =
+            MerkleMultiProof::new(&builder, &items, &[]).expect("failed to build multiproof");
+
+        assert!(proof.positions.is_empty());
+        assert!(proof.leaves.is_empty());
+        assert!(proof.verify(&builder.root()));
+    }
+
+    #[test]
+    fn test_multiproof_rejects_out_of_range_position() {
+        let (builder, items) = build_tree(3);
+        let result = MerkleMultiProof::new(&builder, &items, &[10]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_verify_multiproof_accepts_unsorted_pairs_against_commit() {
+        let (builder, items) = build_tree(6);
+        let positions = [4usize, 1, 2];
+        let proof_hashes = builder.prove(&positions);
+        let pairs: Vec<(usize, [u8; 32])> = positions
+            .iter()
+            .map(|&pos| (pos, MerkleTreeBuilder::hash_leaf(&items[pos]).unwrap()))
+            .rev() // hand the pairs in to verify_multiproof out of order
+            .collect();
+
+        assert!(MerkleTreeBuilder::verify_multiproof(
+            &builder.root(),
+            &proof_hashes,
+            &pairs,
+            builder.leaves_len(),
+        ));
+    }
+
+    #[test]
+    fn test_verify_multiproof_rejects_wrong_leaf_hash() {
+        let (builder, items) = build_tree(6);
+        let positions = [4usize, 1, 2];
+        let proof_hashes = builder.prove(&positions);
+        let mut pairs: Vec<(usize, [u8; 32])> = positions
+            .iter()
+            .map(|&pos| (pos, MerkleTreeBuilder::hash_leaf(&items[pos]).unwrap()))
+            .collect();
+        pairs[0].1 = MerkleTreeBuilder::hash_leaf(&Item(999)).unwrap();
+
+        assert!(!MerkleTreeBuilder::verify_multiproof(
+            &builder.root(),
+            &proof_hashes,
+            &pairs,
+            builder.leaves_len(),
+        ));
+    }
+
+    #[test]
+    fn test_prove_leaf_verifies_against_the_caller_supplied_leaf_bytes() {
+        let (builder, items) = build_tree(5);
+        let proof = builder.prove_leaf(3);
+
+        let leaf_bytes = bincode::serialize(&items[3]).expect("failed to serialize leaf");
+        assert!(MerkleTreeBuilder::verify_leaf(&builder.root(), &leaf_bytes, &proof));
+    }
+
+    #[test]
+    fn test_verify_leaf_rejects_bytes_for_a_different_leaf() {
+        let (builder, items) = build_tree(5);
+        let proof = builder.prove_leaf(3);
+
+        let wrong_bytes = bincode::serialize(&items[4]).expect("failed to serialize leaf");
+        assert!(!MerkleTreeBuilder::verify_leaf(&builder.root(), &wrong_bytes, &proof));
+    }
+
+    #[test]
+    fn test_verify_leaf_rejects_proof_claimed_for_the_wrong_index() {
+        let (builder, items) = build_tree(5);
+        let mut proof = builder.prove_leaf(3);
+        proof.index = 1;
+
+        let leaf_bytes = bincode::serialize(&items[3]).expect("failed to serialize leaf");
+        assert!(!MerkleTreeBuilder::verify_leaf(&builder.root(), &leaf_bytes, &proof));
+    }
+
+    #[test]
+    fn test_prove_leaf_single_leaf_tree_root_equals_leaf() {
+        let (builder, items) = build_tree(1);
+        let proof = builder.prove_leaf(0);
+        assert!(proof.proof_hashes.is_empty());
+
+        let leaf_bytes = bincode::serialize(&items[0]).expect("failed to serialize leaf");
+        assert!(MerkleTreeBuilder::verify_leaf(&builder.root(), &leaf_bytes, &proof));
+    }
+}
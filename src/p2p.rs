@@ -1,10 +1,12 @@
 use crate::accounts::Account;
 use crate::block::Block;
 use crate::blockchain::Blockchain;
+use crate::consensus::{VoteOutcome, VoteStep};
 use crate::genesis::Genesis;
 use crate::hashchain::{verify_hash_chain_index, HashChainCom, HashChainMessage};
 use crate::transaction::Transaction;
 use crate::validator::Validator;
+use crystals_dilithium::dilithium2::{PublicKey, Signature};
 use libp2p::{
     gossipsub::{
         Behaviour, ConfigBuilder, Event, IdentTopic as Topic, MessageAuthenticity, PeerScoreParams,
@@ -34,6 +36,8 @@ pub static TRANSACTION_TOPIC: Lazy<Topic> = Lazy::new(|| Topic::new("transaction
 pub static HASH_CHAIN_TOPIC: Lazy<Topic> = Lazy::new(|| Topic::new("hash_chains"));
 pub static HASH_CHAIN_MESSAGE_TOPIC: Lazy<Topic> = Lazy::new(|| Topic::new("hash_chain_messages"));
 pub static BLOCK_SIGNATURE_TOPIC: Lazy<Topic> = Lazy::new(|| Topic::new("block_signatures"));
+pub static VOTE_TOPIC: Lazy<Topic> = Lazy::new(|| Topic::new("bft_votes"));
+pub static SLASHING_TOPIC: Lazy<Topic> = Lazy::new(|| Topic::new("slashing_evidence"));
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChainRequest {
@@ -90,6 +94,97 @@ pub struct BlockSignature {
     pub signature: Vec<u8>,
 }
 
+/// Proof that `account` signed two different block hashes at the same
+/// `height` — a `BlockSignature` for `hash_a` and another for `hash_b`,
+/// `hash_a != hash_b`. Independently verifiable by any node from just
+/// `account`'s public key and the two Dilithium signatures, so slashing
+/// doesn't require trusting whoever reported it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingEvidence {
+    pub account: Account,
+    pub height: usize,
+    pub hash_a: String,
+    pub sig_a: Vec<u8>,
+    pub hash_b: String,
+    pub sig_b: Vec<u8>,
+}
+
+impl SlashingEvidence {
+    /// Checks both signatures were produced by `account`'s own key over
+    /// their respective (distinct) hashes, without consulting any
+    /// blockchain state — the same Dilithium key material every other
+    /// signature in this codebase is verified against.
+    pub fn verify(&self) -> bool {
+        if self.hash_a == self.hash_b {
+            return false;
+        }
+        let Ok(pubkey_bytes) = hex::decode(&self.account.address) else {
+            return false;
+        };
+        let public_key = PublicKey::from_bytes(&pubkey_bytes);
+        let (Ok(sig_a), Ok(sig_b)) = (
+            Signature::try_from(self.sig_a.as_slice()),
+            Signature::try_from(self.sig_b.as_slice()),
+        ) else {
+            return false;
+        };
+        public_key.verify(self.hash_a.as_bytes(), &sig_a)
+            && public_key.verify(self.hash_b.as_bytes(), &sig_b)
+    }
+}
+
+/// A single Prevote or Precommit cast into the round-based BFT finality
+/// gadget (see `crate::consensus`). Gossiped on its own topic, separate
+/// from `BlockSignature`, since the two serve different purposes: a
+/// `BlockSignature` feeds the compact-certificate proof attached to the
+/// *next* block, while a `VoteMessage` drives same-round quorum detection
+/// that can gate `execute_block` directly via `Blockchain::record_bft_vote`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteMessage {
+    pub height: usize,
+    pub round: u64,
+    pub step: VoteStep,
+    /// `None` casts a nil vote, e.g. after a round times out with no block.
+    pub block_hash: Option<String>,
+    pub sender: Account,
+    pub signature: Vec<u8>,
+}
+
+impl VoteMessage {
+    /// Canonical bytes `signature` must cover: binds `height`/`round`/`step`
+    /// and `block_hash` together so a signature cast for one vote can't be
+    /// replayed as a different one — a Prevote replayed as a Precommit, or a
+    /// vote from one round/height reused for another, since those would
+    /// otherwise all sign identical bytes (just the block hash).
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        format!(
+            "{}:{}:{:?}:{}",
+            self.height,
+            self.round,
+            self.step,
+            self.block_hash.as_deref().unwrap_or("nil")
+        )
+        .into_bytes()
+    }
+
+    /// Checks `signature` was produced by `sender`'s own key over this
+    /// vote's canonical content — the same Dilithium key material every
+    /// other signature in this codebase (`BlockSignature`,
+    /// `SlashingEvidence`) is verified against. Without this,
+    /// `Blockchain::record_bft_vote` would tally voting weight against a
+    /// `VoteMessage` whose `sender` is entirely attacker-chosen.
+    pub fn verify(&self) -> bool {
+        let Ok(pubkey_bytes) = hex::decode(&self.sender.address) else {
+            return false;
+        };
+        let public_key = PublicKey::from_bytes(&pubkey_bytes);
+        let Ok(signature) = Signature::try_from(self.signature.as_slice()) else {
+            return false;
+        };
+        public_key.verify(&self.signing_bytes(), &signature)
+    }
+}
+
 impl AppBehaviour {
     pub async fn new() -> Self {
         let gossipsub_config = ConfigBuilder::default()
@@ -134,6 +229,8 @@ impl AppBehaviour {
             .gossipsub
             .subscribe(&BLOCK_SIGNATURE_TOPIC)
             .unwrap();
+        behaviour.gossipsub.subscribe(&VOTE_TOPIC).unwrap();
+        behaviour.gossipsub.subscribe(&SLASHING_TOPIC).unwrap();
         behaviour.gossipsub.subscribe(&TRANSACTION_TOPIC).unwrap();
         behaviour.gossipsub.subscribe(&HASH_CHAIN_TOPIC).unwrap();
         behaviour
@@ -185,16 +282,44 @@ impl AppBehaviour {
                 blockchain.validator.state.accounts.len()
             );
         } else if let Ok(resp) = serde_json::from_slice::<ChainResponse>(data) {
-            if resp.from_peer_id == PEER_ID.to_string() {
-                info!("Received chain from {:?}", source);
-                // Handle the ChainResponse
+            // Gossipsub can echo our own publications back to us
+            // (`allow_self_origin`); only act on a response someone else sent.
+            if resp.from_peer_id != PEER_ID.to_string() {
+                info!(
+                    "Received chain response from {:?} with {} blocks",
+                    source,
+                    resp.blocks.len()
+                );
+                match blockchain.handle_chain_response(resp.blocks) {
+                    Ok(true) => info!("Adopted a longer, validated chain from {:?}", source),
+                    Ok(false) => info!(
+                        "Chain response from {:?} was not longer than the local chain; ignoring",
+                        source
+                    ),
+                    Err(e) => error!("Rejected invalid chain response from {:?}: {}", source, e),
+                }
+                for txn in resp.txns {
+                    if txn.verify().unwrap_or(false) && !blockchain.mempool.txn_exists(&txn.hash) {
+                        blockchain.mempool.add_transaction(txn);
+                    }
+                }
             }
         } else if let Ok(req) = serde_json::from_slice::<ChainRequest>(data) {
             info!("Received chain request from {:?}", source);
-            info!("Sending the chain and mempool to {:?}", source);
             let peer_id = req.from_peer_id;
-            if peer_id == *PEER_ID {
-                // TODO: send the chain and mempool
+            if peer_id != *PEER_ID {
+                info!("Sending the chain and mempool to {:?}", source);
+                let mut blocks = blockchain.chain.clone();
+                blocks.sort_by_key(|b| b.id);
+                let response = ChainResponse {
+                    blocks,
+                    txns: blockchain.mempool.get_mempool(),
+                    from_peer_id: PEER_ID.to_string(),
+                };
+                let json = serde_json::to_string(&response).expect("Failed to serialize chain response");
+                if let Err(e) = self.gossipsub.publish(CHAIN_TOPIC.clone(), json.into_bytes()) {
+                    eprintln!("Failed to publish chain response: {}", e);
+                }
             }
 
         // Receive a Transaction
@@ -215,40 +340,63 @@ impl AppBehaviour {
         // Receive a Block
         else if let Ok(block) = serde_json::from_slice::<Block>(data) {
             info!("Received a block from {:?}", source);
-            if blockchain.verify_block(block.clone()) {
-                if !blockchain.block_exists(block.clone()) {
-                    blockchain.execute_block(block.clone());
+            let block_seed = block.seed.clone();
+            match blockchain.receive_block(block.clone(), block_seed) {
+                Ok(true) => {
                     info!("Executed block {:?}", block.id);
                     // Progress the epoch once when executing a new block
                     blockchain.epoch.progress();
-                }
 
-                // NEW: Ensure every node signs if it hasn't already
-                {
-                    let local_pub = blockchain.wallet.get_public_key().to_string();
-                    // Check if this node already signed the block
-                    let already_signed = blockchain
-                        .pending_signatures
-                        .get(&block.id)
-                        .map(|sigs| sigs.iter().any(|s| s.sender.address == local_pub))
-                        .unwrap_or(false);
-                    if !already_signed {
-                        let block_hash_hex = hex::encode(&block.hash);
-                        let signature = blockchain.wallet.sign_message(block_hash_hex.as_bytes());
-                        let block_sig = BlockSignature {
-                            block_id: block.id,
-                            block_hash: block_hash_hex,
-                            sender: Account { address: local_pub },
-                            signature: signature.to_vec(),
-                        };
-                        let json = serde_json::to_string(&block_sig).unwrap();
-                        self.gossipsub
-                            .publish(BLOCK_SIGNATURE_TOPIC.clone(), json.into_bytes())
-                            .unwrap();
+                    // NEW: Ensure every node signs if it hasn't already
+                    {
+                        let local_pub = blockchain.wallet.get_public_key().to_string();
+                        // Check if this node already signed the block
+                        let already_signed = blockchain
+                            .pending_signatures
+                            .get(&block.id)
+                            .map(|sigs| sigs.iter().any(|s| s.sender.address == local_pub))
+                            .unwrap_or(false);
+                        if !already_signed {
+                            let block_hash_hex = hex::encode(&block.hash);
+                            let signature = blockchain.wallet.sign_message(block_hash_hex.as_bytes());
+                            let block_sig = BlockSignature {
+                                block_id: block.id,
+                                block_hash: block_hash_hex.clone(),
+                                sender: Account { address: local_pub.clone() },
+                                signature: signature.to_vec(),
+                            };
+                            let json = serde_json::to_string(&block_sig).unwrap();
+                            self.gossipsub
+                                .publish(BLOCK_SIGNATURE_TOPIC.clone(), json.into_bytes())
+                                .unwrap();
+
+                            // Cast this node's own Precommit for the block it
+                            // just executed, so `record_bft_vote`'s quorum
+                            // tally is actually driven by validators casting
+                            // real votes rather than only by test-constructed
+                            // `VoteMessage`s.
+                            let mut vote = VoteMessage {
+                                height: block.id,
+                                round: blockchain.bft_round(block.id),
+                                step: VoteStep::Precommit,
+                                block_hash: Some(block_hash_hex),
+                                sender: Account { address: local_pub },
+                                signature: Vec::new(),
+                            };
+                            vote.signature = blockchain.wallet.sign_message(&vote.signing_bytes()).to_vec();
+                            let vote_json = serde_json::to_string(&vote).unwrap();
+                            self.gossipsub
+                                .publish(VOTE_TOPIC.clone(), vote_json.into_bytes())
+                                .unwrap();
+                        }
                     }
                 }
-            } else {
-                info!("Block failed verification from {:?}", source);
+                Ok(false) => {
+                    info!("Ignoring block {:?} from {:?}: lost fork-choice", block.id, source);
+                }
+                Err(e) => {
+                    info!("Block failed validation from {:?}: {}", source, e);
+                }
             }
 
             // Check if it is the end of the epoch
@@ -298,8 +446,71 @@ impl AppBehaviour {
                 "Received block signature for block {} from {:?}",
                 block_sig.block_id, source
             );
+
+            // Equivocation check: a second, distinct hash signed by the
+            // same account for the same height is slashable.
+            if let Some((prior_hash, prior_sig)) = blockchain.validator.record_block_signature_witness(
+                &block_sig.sender,
+                block_sig.block_id,
+                &block_sig.block_hash,
+                &block_sig.signature,
+            ) {
+                let evidence = SlashingEvidence {
+                    account: block_sig.sender.clone(),
+                    height: block_sig.block_id,
+                    hash_a: prior_hash,
+                    sig_a: prior_sig,
+                    hash_b: block_sig.block_hash.clone(),
+                    sig_b: block_sig.signature.clone(),
+                };
+                if evidence.verify() {
+                    blockchain.validator.slash(&evidence.account);
+                    error!(
+                        "Slashed validator {} for equivocating at height {}",
+                        evidence.account.address, evidence.height
+                    );
+                    let json = serde_json::to_string(&evidence)
+                        .expect("Failed to serialize slashing evidence");
+                    if let Err(e) = self.gossipsub.publish(SLASHING_TOPIC.clone(), json.into_bytes()) {
+                        eprintln!("Failed to publish slashing evidence: {}", e);
+                    }
+                }
+            }
+
             // Let the blockchain (if this node is the block producer) collect the signature
             blockchain.collect_block_signature(block_sig);
+        }
+        // NEW: Process SlashingEvidence gossiped by a peer that independently
+        // detected equivocation; re-verify before acting so a node never
+        // slashes based on a reporter's say-so alone.
+        else if let Ok(evidence) = serde_json::from_slice::<SlashingEvidence>(data) {
+            if evidence.verify() {
+                blockchain.validator.slash(&evidence.account);
+                error!(
+                    "Slashed validator {} for equivocating at height {} (evidence from {:?})",
+                    evidence.account.address, evidence.height, source
+                );
+            } else {
+                error!("Rejected invalid slashing evidence from {:?}", source);
+            }
+        }
+        // Process a Prevote/Precommit cast into the round-based BFT gadget,
+        // alongside (not instead of) the compact-certificate path above.
+        else if let Ok(vote) = serde_json::from_slice::<VoteMessage>(data) {
+            // `record_bft_vote` re-verifies `vote.signature` itself before
+            // touching any tally, so an unverifiable vote is silently a
+            // no-op (`VoteOutcome::Pending`) rather than counted weight for
+            // an attacker-chosen `sender`.
+            info!(
+                "Received {:?} vote for height {} round {} from {:?}",
+                vote.step, vote.height, vote.round, source
+            );
+            if let VoteOutcome::Committed { hash } = blockchain.record_bft_vote(&vote) {
+                info!(
+                    "BFT precommit quorum reached for height {} on hash {}",
+                    vote.height, hash
+                );
+            }
         } else {
             info!("Received an unknown message from {:?}: {:?}", source, data);
         }
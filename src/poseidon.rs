@@ -0,0 +1,250 @@
+//! A lightweight Poseidon-style hash over the Mersenne31 field
+//! (`p = 2^31 - 1`), offered as an arithmetization-friendly alternative to
+//! the Keccak256-backed [`crate::merkle::CustomHasher`]. Poseidon's round
+//! function is built entirely from field add/mul (an `x^5` S-box plus a
+//! linear MDS mix), which costs a handful of constraints per round in a
+//! circuit, versus the thousands of bitwise gates Keccak needs for the same
+//! job — see `bin/poseidon_merkle_gadget.rs` for the in-circuit opening of a
+//! tree built with this hasher.
+//!
+//! The round constants and MDS matrix below are generated deterministically
+//! (not drawn from the reference Poseidon paper's audited parameter sets),
+//! so this should be treated as a "Poseidon-lite" construction: structurally
+//! faithful to the sponge/permutation design, but its constants have not
+//! been reviewed for the algebraic attacks the real parameter-generation
+//! process screens for. Good enough for committing sidechain data to a
+//! circuit-friendly root; not a drop-in replacement for an audited Poseidon
+//! instantiation in a production ZK system.
+
+use once_cell::sync::Lazy;
+use rs_merkle::Hasher;
+
+/// Mersenne31: `2^31 - 1`, prime.
+pub const M31: u64 = (1u64 << 31) - 1;
+
+pub const STATE_WIDTH: usize = 3;
+pub const FULL_ROUNDS: usize = 8;
+pub const PARTIAL_ROUNDS: usize = 22;
+pub const TOTAL_ROUNDS: usize = FULL_ROUNDS + PARTIAL_ROUNDS;
+
+fn field_add(a: u64, b: u64) -> u64 {
+    let sum = a + b;
+    if sum >= M31 {
+        sum - M31
+    } else {
+        sum
+    }
+}
+
+fn field_mul(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % M31 as u128) as u64
+}
+
+fn field_pow(mut base: u64, mut exp: u64) -> u64 {
+    let mut result = 1u64;
+    base %= M31;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = field_mul(result, base);
+        }
+        base = field_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// `a^(p-2) mod p`, i.e. `a^-1` by Fermat's little theorem. Only used by
+/// the MDS-matrix generator below, which needs `1/(x_i + y_j)` for a
+/// handful of fixed small inputs at startup, not on any hot path.
+fn field_inverse(a: u64) -> u64 {
+    field_pow(a, M31 - 2)
+}
+
+/// `x^5`, Poseidon's standard S-box over fields where `gcd(5, p-1) == 1`
+/// (true for M31: `p - 1 = 2 * 3^2 * 7 * 11 * 31 * 151 * 331`, not divisible by 5).
+fn sbox(x: u64) -> u64 {
+    let x2 = field_mul(x, x);
+    let x4 = field_mul(x2, x2);
+    field_mul(x4, x)
+}
+
+/// Deterministic constant-generation stream so the round constants below
+/// don't need to be hand-copied from an external parameter file. Only
+/// `ROUND_CONSTANTS` draws from it; the MDS matrix is a fixed Cauchy
+/// matrix.
+fn splitmix64_stream(mut seed: u64) -> impl FnMut() -> u64 {
+    move || {
+        seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+static ROUND_CONSTANTS: Lazy<[[u64; STATE_WIDTH]; TOTAL_ROUNDS]> = Lazy::new(|| {
+    let mut next = splitmix64_stream(0x504f5345_49444f4e); // "POSEIDON" (truncated)
+    let mut constants = [[0u64; STATE_WIDTH]; TOTAL_ROUNDS];
+    for round in constants.iter_mut() {
+        for slot in round.iter_mut() {
+            *slot = next() % M31;
+        }
+    }
+    constants
+});
+
+/// A `STATE_WIDTH`x`STATE_WIDTH` MDS matrix built as a Cauchy matrix
+/// (`m[i][j] = 1 / (x_i + y_j)`) over small distinct `x`/`y`, which is
+/// guaranteed to be maximum-distance-separable (all square submatrices
+/// nonsingular) without needing to search for one.
+static MDS_MATRIX: Lazy<[[u64; STATE_WIDTH]; STATE_WIDTH]> = Lazy::new(|| {
+    let xs = [1u64, 2, 3];
+    let ys = [4u64, 5, 6];
+    let mut matrix = [[0u64; STATE_WIDTH]; STATE_WIDTH];
+    for (i, x) in xs.iter().enumerate() {
+        for (j, y) in ys.iter().enumerate() {
+            matrix[i][j] = field_inverse(field_add(*x, *y));
+        }
+    }
+    matrix
+});
+
+/// Exposes the round-constant table so `bin/poseidon_merkle_gadget.rs` can
+/// re-embed the exact same constants inside a circuit — the in-circuit
+/// permutation only proves anything about roots produced by this native
+/// one if both sides use identical constants.
+pub fn round_constants() -> &'static [[u64; STATE_WIDTH]; TOTAL_ROUNDS] {
+    &ROUND_CONSTANTS
+}
+
+/// Exposes the MDS matrix for the same reason as [`round_constants`].
+pub fn mds_matrix() -> &'static [[u64; STATE_WIDTH]; STATE_WIDTH] {
+    &MDS_MATRIX
+}
+
+fn mds_mix(state: &[u64; STATE_WIDTH]) -> [u64; STATE_WIDTH] {
+    let mds = &*MDS_MATRIX;
+    let mut out = [0u64; STATE_WIDTH];
+    for (i, out_slot) in out.iter_mut().enumerate() {
+        let mut acc = 0u64;
+        for (j, s) in state.iter().enumerate() {
+            acc = field_add(acc, field_mul(mds[i][j], *s));
+        }
+        *out_slot = acc;
+    }
+    out
+}
+
+/// Runs the full Poseidon permutation in place: `FULL_ROUNDS / 2` full
+/// rounds (S-box on every lane), `PARTIAL_ROUNDS` partial rounds (S-box on
+/// lane 0 only, cheaper in-circuit), then the remaining half of the full
+/// rounds — the standard full/partial/full sandwich.
+pub fn permute(state: &mut [u64; STATE_WIDTH]) {
+    let constants = &*ROUND_CONSTANTS;
+    let half_full = FULL_ROUNDS / 2;
+
+    for round in 0..TOTAL_ROUNDS {
+        for (lane, c) in state.iter_mut().zip(constants[round].iter()) {
+            *lane = field_add(*lane, *c);
+        }
+
+        let is_full_round = round < half_full || round >= half_full + PARTIAL_ROUNDS;
+        if is_full_round {
+            for lane in state.iter_mut() {
+                *lane = sbox(*lane);
+            }
+        } else {
+            state[0] = sbox(state[0]);
+        }
+
+        *state = mds_mix(state);
+    }
+}
+
+/// 2-to-1 compression: absorbs `(a, b)` into the capacity-1/rate-2 state
+/// `[0, a, b]`, permutes, and squeezes lane 0 — the building block both
+/// `hash_bytes` (Merkle-Damgard over the input) and the Merkle tree's
+/// internal-node hashing (via [`PoseidonHasher`]) reduce to.
+pub fn compress2(a: u64, b: u64) -> u64 {
+    let mut state = [0u64, a % M31, b % M31];
+    permute(&mut state);
+    state[0]
+}
+
+/// Hashes arbitrary bytes down to 32 bytes by chunking the input into
+/// 8-byte limbs (reduced mod `M31`), folding them through `compress2` in a
+/// Merkle-Damgard chain seeded with the byte length (domain-separating
+/// short inputs from truncated long ones), then squeezing 8 field elements
+/// (4 bytes of each kept, little-endian) to fill the 32-byte digest.
+pub fn hash_bytes(data: &[u8]) -> [u8; 32] {
+    let mut state = data.len() as u64 % M31;
+    for chunk in data.chunks(8) {
+        let mut limb_bytes = [0u8; 8];
+        limb_bytes[..chunk.len()].copy_from_slice(chunk);
+        let limb = u64::from_le_bytes(limb_bytes) % M31;
+        state = compress2(state, limb);
+    }
+
+    let mut digest = [0u8; 32];
+    for (i, out) in digest.chunks_mut(4).enumerate() {
+        state = compress2(state, i as u64);
+        out.copy_from_slice(&(state as u32).to_le_bytes());
+    }
+    digest
+}
+
+/// [`rs_merkle::Hasher`] implementation backed by [`hash_bytes`], so a
+/// `MerkleTreeBuilder<PoseidonHasher>` (aliased as
+/// `merkle::PoseidonMerkleTreeBuilder`) commits leaves and internal nodes
+/// with the Poseidon permutation instead of Keccak256.
+#[derive(Default, Clone)]
+pub struct PoseidonHasher;
+
+impl Hasher for PoseidonHasher {
+    type Hash = [u8; 32];
+
+    fn hash(data: &[u8]) -> Self::Hash {
+        hash_bytes(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_inverse_round_trips() {
+        for a in [1u64, 2, 3, 4, 12345, M31 - 1] {
+            let inv = field_inverse(a);
+            assert_eq!(field_mul(a, inv), 1);
+        }
+    }
+
+    #[test]
+    fn test_compress2_is_deterministic_and_domain_separated() {
+        assert_eq!(compress2(1, 2), compress2(1, 2));
+        assert_ne!(compress2(1, 2), compress2(2, 1));
+    }
+
+    #[test]
+    fn test_hash_bytes_is_deterministic_and_avalanches() {
+        let a = hash_bytes(b"hello world");
+        let b = hash_bytes(b"hello world");
+        let c = hash_bytes(b"hello worle");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_hash_bytes_distinguishes_empty_and_nonempty() {
+        let empty = hash_bytes(b"");
+        let nonempty = hash_bytes(b"\0");
+        assert_ne!(empty, nonempty);
+    }
+
+    #[test]
+    fn test_poseidon_hasher_matches_hash_bytes() {
+        let data = b"poseidon merkle leaf";
+        assert_eq!(PoseidonHasher::hash(data), hash_bytes(data));
+    }
+}
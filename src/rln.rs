@@ -0,0 +1,104 @@
+//! Rate-limiting nullifiers (RLN) for `crate::hashchain::HashChain` signals:
+//! a participant who reveals two signals in the same epoch leaks their
+//! secret key, so honest single-per-epoch signalers stay private while
+//! double-signalers can be slashed. Mirrors the construction used by
+//! Semaphore-RLN, adapted to this repo's `sk_s`/`PQZKCircuit` naming from
+//! `bin/test_circuit.rs`.
+//!
+//! For a given epoch, a signal is a point `(x, y)` on the line
+//! `y = sk_s + a1 * x`, where `a1 = SHA3(sk_s || epoch)` is the line's slope
+//! and `x = SHA3(message)` is the per-message point. One point alone
+//! reveals nothing about `sk_s`; two points sharing the same `nullifier`
+//! (i.e. the same `a1`, hence the same epoch) pin down the line and let
+//! [`recover_secret`] solve for `sk_s`.
+//!
+//! The line arithmetic is done over the same Mersenne31 field
+//! `crate::poseidon` uses, so the in-circuit assertion of `y = sk_s + a1*x`
+//! (see `bin/rln_nullifier_gadget.rs`) runs in expander-compiler's native
+//! `M31Config` with no field mismatch between witness generation here and
+//! the gadget.
+
+use crate::poseidon::M31;
+use sha3::{Digest, Sha3_256};
+
+fn field_reduce(bytes: [u8; 8]) -> u64 {
+    u64::from_le_bytes(bytes) % M31
+}
+
+fn field_add(a: u64, b: u64) -> u64 {
+    (a + b) % M31
+}
+
+fn field_sub(a: u64, b: u64) -> u64 {
+    (a + M31 - (b % M31)) % M31
+}
+
+fn field_mul(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % M31 as u128) as u64
+}
+
+fn field_inverse(a: u64) -> u64 {
+    // Fermat's little theorem: a^(p-2) mod p.
+    let mut base = a % M31;
+    let mut exp = M31 - 2;
+    let mut result = 1u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = field_mul(result, base);
+        }
+        base = field_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Hashes `parts` with SHA3-256 and reduces the leading 8 bytes into the
+/// field, the same truncate-and-reduce trick `bin/circuits.rs::a_coeff` uses
+/// for its field-element derivations.
+fn hash_to_field(parts: &[&[u8]]) -> u64 {
+    let mut hasher = Sha3_256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    let digest = hasher.finalize();
+    field_reduce(digest[0..8].try_into().unwrap())
+}
+
+/// A single RLN signal: a point on the per-epoch line plus the public
+/// nullifier that ties it to that epoch's slope without revealing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signal {
+    pub nullifier: [u8; 32],
+    pub x: u64,
+    pub y: u64,
+}
+
+/// Derives the RLN signal for revealing `message` under secret key `sk_s`
+/// during `epoch`. Honest participants call this at most once per epoch;
+/// a second call in the same epoch produces a second point on the same
+/// line, which [`recover_secret`] can use to recover `sk_s`.
+pub fn derive_share(sk_s: u64, epoch: u64, message: &[u8]) -> Signal {
+    let a1_bytes: [u8; 32] = Sha3_256::digest(
+        [sk_s.to_le_bytes().as_slice(), epoch.to_le_bytes().as_slice()].concat(),
+    )
+    .into();
+    let a1 = field_reduce(a1_bytes[0..8].try_into().unwrap());
+    let x = hash_to_field(&[message]);
+    let y = field_add(sk_s % M31, field_mul(a1, x));
+    let nullifier: [u8; 32] = Sha3_256::digest(a1_bytes).into();
+    Signal { nullifier, x, y }
+}
+
+/// Recovers `sk_s` from two signals that share a `nullifier`, i.e. two
+/// signals revealed in the same epoch. Returns `None` if the signals are
+/// from different epochs (mismatched nullifier) or are the same point
+/// (`x` repeated), in which case the line isn't determined.
+pub fn recover_secret(a: &Signal, b: &Signal) -> Option<u64> {
+    if a.nullifier != b.nullifier || a.x == b.x {
+        return None;
+    }
+    let dx = field_sub(b.x, a.x);
+    let dy = field_sub(b.y, a.y);
+    let slope = field_mul(dy, field_inverse(dx));
+    Some(field_sub(a.y, field_mul(a.x, slope)))
+}
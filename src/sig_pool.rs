@@ -0,0 +1,298 @@
+use crate::ccok::{Builder, Params, Participant};
+use crystals_dilithium::dilithium2::{PublicKey, Signature};
+use std::collections::HashMap;
+
+/// Why a signature offered to a [`SignaturePool`] was rejected, modeled on
+/// the attestation-pool rejection path from Lighthouse: a submission is
+/// either malformed/unauthenticated, or simply stale relative to the pool's
+/// current round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignaturePoolError {
+    InvalidPosition(usize),
+    ZeroWeight(usize),
+    AlreadyHave(usize),
+    InvalidSignature(usize),
+    /// The submission's epoch doesn't match the pool's current `Params`,
+    /// i.e. it was gathered for a round this pool has already moved past.
+    TooOld { expected_epoch: u64, got_epoch: u64 },
+}
+
+impl std::fmt::Display for SignaturePoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignaturePoolError::InvalidPosition(pos) => {
+                write!(f, "invalid participant position: {}", pos)
+            }
+            SignaturePoolError::ZeroWeight(pos) => {
+                write!(f, "participant {} has zero weight", pos)
+            }
+            SignaturePoolError::AlreadyHave(pos) => {
+                write!(f, "already have a signature for participant {}", pos)
+            }
+            SignaturePoolError::InvalidSignature(pos) => {
+                write!(f, "signature for participant {} failed verification", pos)
+            }
+            SignaturePoolError::TooOld {
+                expected_epoch,
+                got_epoch,
+            } => write!(
+                f,
+                "signature is for epoch {} but the pool is collecting epoch {}",
+                got_epoch, expected_epoch
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SignaturePoolError {}
+
+/// Incrementally accumulates validated partial signatures toward a compact
+/// certificate, so a node can start forwarding/merging signatures as they
+/// stream in off the network instead of waiting to collect them all before
+/// handing a full `Vec<SigSlot>` to [`Builder`]. Every signature is verified
+/// against its claimed participant and the pool's `Params::msg` the moment
+/// it arrives, so `into_builder` never has to re-validate.
+#[derive(Debug, Clone)]
+pub struct SignaturePool {
+    pub params: Params,
+    pub participants: Vec<Participant>,
+    pub party_tree_root: Vec<u8>,
+    /// Validated signatures received so far, keyed by participant position.
+    pub signatures: HashMap<usize, Signature>,
+    pub signed_weight: u64,
+}
+
+impl SignaturePool {
+    pub fn new(params: Params, participants: Vec<Participant>, party_tree_root: Vec<u8>) -> Self {
+        Self {
+            params,
+            participants,
+            party_tree_root,
+            signatures: HashMap::new(),
+            signed_weight: 0,
+        }
+    }
+
+    /// Validate and record a signature for participant `pos` at `epoch`.
+    /// Rejects a stale `epoch`, an out-of-range or zero-weight position, a
+    /// duplicate, or a signature that doesn't verify against the
+    /// participant's public key and `self.params.msg`.
+    pub fn add_signature(
+        &mut self,
+        pos: usize,
+        signature: Signature,
+        epoch: u64,
+    ) -> Result<(), SignaturePoolError> {
+        if epoch != self.params.epoch {
+            return Err(SignaturePoolError::TooOld {
+                expected_epoch: self.params.epoch,
+                got_epoch: epoch,
+            });
+        }
+
+        let participant = self
+            .participants
+            .get(pos)
+            .ok_or(SignaturePoolError::InvalidPosition(pos))?;
+
+        if participant.weight == 0 {
+            return Err(SignaturePoolError::ZeroWeight(pos));
+        }
+
+        if self.signatures.contains_key(&pos) {
+            return Err(SignaturePoolError::AlreadyHave(pos));
+        }
+
+        let pubkey_bytes = hex::decode(&participant.public_key)
+            .map_err(|_| SignaturePoolError::InvalidSignature(pos))?;
+        let public_key: [u8; 1312] = pubkey_bytes
+            .try_into()
+            .map_err(|_| SignaturePoolError::InvalidSignature(pos))?;
+        let pk = PublicKey::from_bytes(&public_key);
+        if !pk.verify(&self.params.msg, &signature) {
+            return Err(SignaturePoolError::InvalidSignature(pos));
+        }
+
+        self.signatures.insert(pos, signature);
+        self.signed_weight += participant.weight;
+        Ok(())
+    }
+
+    /// Absorbs every signature from `other` that this pool doesn't already
+    /// have, so signatures gathered by different nodes can be combined.
+    /// Silently skips entries for a different epoch or that fail the same
+    /// validation `add_signature` would apply — a node merging in a peer's
+    /// pool shouldn't be taken down by that peer's bad or stale data.
+    pub fn merge(&mut self, other: &SignaturePool) {
+        for (&pos, signature) in other.signatures.iter() {
+            let _ = self.add_signature(pos, *signature, other.params.epoch);
+        }
+    }
+
+    /// Whether enough weight has signed to clear `proven_weight`.
+    pub fn is_ready(&self, proven_weight: u64) -> bool {
+        self.signed_weight >= proven_weight
+    }
+
+    /// Hands off the accumulated signatures to a fresh [`Builder`].
+    /// [`Builder::build`] succeeds only once `signed_weight` strictly
+    /// exceeds `params.proven_weight`; at equality `is_ready` is already
+    /// true but `build` still errs.
+    pub fn into_builder(self) -> Builder {
+        let mut builder = Builder::new(self.params, self.participants, self.party_tree_root);
+        for (pos, signature) in self.signatures {
+            // Every signature already verified on the way into the pool;
+            // `add_signature` re-validates, which is redundant but keeps
+            // `Builder`'s own invariants (weight bookkeeping, duplicate
+            // checks) as the single source of truth for slot state.
+            let _ = builder.add_signature(pos, signature);
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::MerkleTreeBuilder;
+    use crate::wallet::Wallet;
+
+    fn test_pool(participants: Vec<(String, u64)>, msg: Vec<u8>) -> SignaturePool {
+        let total_weight: u64 = participants.iter().map(|(_, w)| w).sum();
+        let participants: Vec<Participant> = participants
+            .into_iter()
+            .map(|(public_key, weight)| Participant {
+                public_key,
+                weight,
+                key_schedule_root: None,
+                weight_commitment: None,
+            })
+            .collect();
+
+        let mut party_tree = MerkleTreeBuilder::new();
+        party_tree
+            .build(&participants)
+            .expect("Failed to build party tree");
+        let party_tree_root = party_tree.root();
+
+        let params = Params {
+            msg,
+            proven_weight: total_weight / 2,
+            security_param: 128,
+            epoch: 0,
+        };
+
+        SignaturePool::new(params, participants, party_tree_root)
+    }
+
+    #[test]
+    fn test_add_signature_accumulates_weight() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let msg = b"pool message".to_vec();
+        let mut pool = test_pool(
+            vec![(wallet1.get_public_key(), 10), (wallet2.get_public_key(), 20)],
+            msg.clone(),
+        );
+
+        pool.add_signature(0, wallet1.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+        assert_eq!(pool.signed_weight, 10);
+        assert!(!pool.is_ready(30));
+
+        pool.add_signature(1, wallet2.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+        assert_eq!(pool.signed_weight, 30);
+        assert!(pool.is_ready(30));
+    }
+
+    #[test]
+    fn test_add_signature_rejects_wrong_epoch() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let msg = b"pool message".to_vec();
+        let mut pool = test_pool(vec![(wallet1.get_public_key(), 10)], msg.clone());
+
+        let result = pool.add_signature(0, wallet1.sign_message(&msg), 1);
+        assert_eq!(
+            result,
+            Err(SignaturePoolError::TooOld {
+                expected_epoch: 0,
+                got_epoch: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_add_signature_rejects_invalid_signature() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let msg = b"pool message".to_vec();
+        let mut pool = test_pool(vec![(wallet1.get_public_key(), 10)], msg.clone());
+
+        // wallet2's signature doesn't match participant 0's committed key.
+        let result = pool.add_signature(0, wallet2.sign_message(&msg), 0);
+        assert_eq!(result, Err(SignaturePoolError::InvalidSignature(0)));
+    }
+
+    #[test]
+    fn test_add_signature_rejects_duplicate() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let msg = b"pool message".to_vec();
+        let mut pool = test_pool(vec![(wallet1.get_public_key(), 10)], msg.clone());
+
+        pool.add_signature(0, wallet1.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+        let result = pool.add_signature(0, wallet1.sign_message(&msg), 0);
+        assert_eq!(result, Err(SignaturePoolError::AlreadyHave(0)));
+    }
+
+    #[test]
+    fn test_merge_combines_independently_gathered_signatures() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let msg = b"pool message".to_vec();
+
+        let mut pool_a = test_pool(
+            vec![(wallet1.get_public_key(), 10), (wallet2.get_public_key(), 20)],
+            msg.clone(),
+        );
+        let mut pool_b = test_pool(
+            vec![(wallet1.get_public_key(), 10), (wallet2.get_public_key(), 20)],
+            msg.clone(),
+        );
+
+        pool_a
+            .add_signature(0, wallet1.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+        pool_b
+            .add_signature(1, wallet2.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+
+        pool_a.merge(&pool_b);
+        assert_eq!(pool_a.signed_weight, 30);
+        assert!(pool_a.signatures.contains_key(&0));
+        assert!(pool_a.signatures.contains_key(&1));
+    }
+
+    #[test]
+    fn test_into_builder_produces_verifiable_certificate() {
+        let wallet1 = Wallet::new().expect("Failed to create wallet 1");
+        let wallet2 = Wallet::new().expect("Failed to create wallet 2");
+        let msg = b"pool message".to_vec();
+        let mut pool = test_pool(
+            vec![(wallet1.get_public_key(), 10), (wallet2.get_public_key(), 20)],
+            msg.clone(),
+        );
+
+        pool.add_signature(0, wallet1.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+        pool.add_signature(1, wallet2.sign_message(&msg), 0)
+            .expect("Failed to add signature");
+
+        let builder = pool.into_builder();
+        let cert = builder.build().expect("Failed to build certificate");
+        assert!(cert
+            .verify(&builder.params, &builder.party_tree_root)
+            .unwrap());
+    }
+}
@@ -0,0 +1,283 @@
+//! A sparse Merkle tree over `accounts::State`'s balances, keyed by
+//! `Sha3_256(account.address)` (a 256-bit path), so the sidechain can
+//! publish a succinct root that attests to every account's balance —
+//! including the absence of an account, via a non-membership proof against
+//! the default (empty-subtree) hashes. `State` itself keeps balances in a
+//! plain `HashMap` with no commitment; this sits alongside it rather than
+//! replacing it, the same way `merkle::MerkleTreeBuilder` sits alongside
+//! raw `Vec`s of transactions.
+//!
+//! Unlike `merkle::MerkleTreeBuilder`, which rebuilds every layer from a
+//! full leaf slice, a tree this deep (`DEPTH = 256`) is never built from
+//! scratch: only the non-default nodes touched by an `update` are stored,
+//! and `prove` walks the same path reading (instead of writing) them.
+
+use crate::accounts::Account;
+use crate::merkle::CustomHasher;
+use rs_merkle::Hasher;
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+
+/// Number of levels: one per bit of the `Sha3_256` account-address digest.
+pub const DEPTH: usize = 256;
+
+/// `Sha3_256(account.address)`, read as `DEPTH` bits, most-significant bit
+/// first — the root-to-leaf path for `account`.
+fn path_bits(account: &Account) -> [bool; DEPTH] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(account.address.as_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+
+    let mut bits = [false; DEPTH];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = (digest[i / 8] >> (7 - (i % 8))) & 1 == 1;
+    }
+    bits
+}
+
+/// A leaf commits to the account's balance; absent accounts (and accounts
+/// explicitly zeroed out) use the empty-leaf default so they're
+/// indistinguishable from "never touched" for non-membership purposes.
+fn leaf_hash<H: Hasher<Hash = [u8; 32]>>(balance: f64, empty_leaf: H::Hash) -> H::Hash {
+    if balance == 0.0 {
+        return empty_leaf;
+    }
+    let bytes = balance.to_le_bytes();
+    H::hash(&bytes)
+}
+
+/// A single non-default sibling encountered while walking a path from leaf
+/// to root, paired with its height (0 = leaf level, `DEPTH - 1` = just
+/// below the root) so the verifier knows which default hash to fall back
+/// to everywhere else.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleProof {
+    pub siblings: Vec<(usize, [u8; 32])>,
+}
+
+/// A sparse Merkle tree over an arbitrary `Hasher`, defaulting to
+/// [`CustomHasher`] (Keccak256) like `merkle::MerkleTreeBuilder`.
+pub struct SparseMerkleTree<H: Hasher<Hash = [u8; 32]> = CustomHasher> {
+    /// `default_hashes[h]` is the root of an empty subtree of height `h`;
+    /// `default_hashes[0]` is the hash of an empty leaf,
+    /// `default_hashes[DEPTH]` is the root of a wholly-empty tree.
+    default_hashes: Vec<H::Hash>,
+    /// Only the non-default nodes, keyed by `(height, prefix)` where
+    /// `prefix` is the first `DEPTH - height` path bits shared by every
+    /// leaf under that node.
+    nodes: HashMap<(usize, Vec<bool>), H::Hash>,
+    root: H::Hash,
+}
+
+impl<H: Hasher<Hash = [u8; 32]>> SparseMerkleTree<H> {
+    pub fn new() -> Self {
+        let mut default_hashes = Vec::with_capacity(DEPTH + 1);
+        default_hashes.push(H::hash(&[]));
+        for h in 0..DEPTH {
+            let prev = default_hashes[h];
+            default_hashes.push(H::hash(&[prev.as_ref(), prev.as_ref()].concat()));
+        }
+        let root = default_hashes[DEPTH];
+        Self {
+            default_hashes,
+            nodes: HashMap::new(),
+            root,
+        }
+    }
+
+    /// Current root, reflecting every `update` applied so far.
+    pub fn get_root(&self) -> Vec<u8> {
+        self.root.as_ref().to_vec()
+    }
+
+    /// Replays every account in `state` at its current balance and returns
+    /// the new root; leaves already in the tree for accounts not in `state`
+    /// are left as they are. Convenient for bootstrapping a tree from an existing
+    /// `accounts::State` snapshot (see `accounts::State`) rather than
+    /// reconstructing one update at a time from chain history.
+    pub fn sync_from_state(&mut self, state: &crate::accounts::State) -> Vec<u8> {
+        for account in &state.accounts {
+            let balance = state.get_balance(account.clone());
+            self.update(account, balance);
+        }
+        self.get_root()
+    }
+
+    /// Sets `account`'s committed balance to `balance` (zero clears it back
+    /// to the empty/non-member leaf) and returns the new root. Touches only
+    /// the `DEPTH` nodes on `account`'s path.
+    pub fn update(&mut self, account: &Account, balance: f64) -> Vec<u8> {
+        let bits = path_bits(account);
+        let leaf = leaf_hash::<H>(balance, self.default_hashes[0]);
+
+        let mut prefix = bits.to_vec();
+        self.nodes.insert((0, prefix.clone()), leaf);
+
+        let mut current = leaf;
+        for height in 0..DEPTH {
+            let bit = prefix.pop().expect("prefix has DEPTH - height bits remaining");
+            let mut sibling_prefix = prefix.clone();
+            sibling_prefix.push(!bit);
+            let sibling = self
+                .nodes
+                .get(&(height, sibling_prefix))
+                .copied()
+                .unwrap_or(self.default_hashes[height]);
+
+            let (left, right) = if bit { (sibling, current) } else { (current, sibling) };
+            current = H::hash(&[left.as_ref(), right.as_ref()].concat());
+
+            if height + 1 < DEPTH {
+                self.nodes.insert((height + 1, prefix.clone()), current);
+            }
+        }
+
+        self.root = current;
+        self.get_root()
+    }
+
+    /// Builds a compact proof of `account`'s current (or, if never updated,
+    /// absent) leaf: only siblings stored by some earlier `update` are
+    /// included; every other sibling is the default hash at its height,
+    /// which the verifier already knows.
+    pub fn prove(&self, account: &Account) -> SparseMerkleProof {
+        let bits = path_bits(account);
+        let mut prefix = bits.to_vec();
+        let mut siblings = Vec::new();
+
+        for height in 0..DEPTH {
+            let bit = prefix.pop().expect("prefix has DEPTH - height bits remaining");
+            let mut sibling_prefix = prefix.clone();
+            sibling_prefix.push(!bit);
+            if let Some(sibling) = self.nodes.get(&(height, sibling_prefix)) {
+                siblings.push((height, *sibling));
+            }
+        }
+
+        SparseMerkleProof { siblings }
+    }
+
+    /// Recomputes the root implied by `proof` for `account` holding
+    /// `balance` (`None` for a non-membership / absence proof) and checks
+    /// it matches `root`.
+    pub fn verify(
+        root: &[u8],
+        account: &Account,
+        balance: Option<f64>,
+        proof: &SparseMerkleProof,
+    ) -> bool {
+        let default_hashes = Self::new().default_hashes;
+        let bits = path_bits(account);
+
+        let mut current = leaf_hash::<H>(balance.unwrap_or(0.0), default_hashes[0]);
+        let mut prefix = bits.to_vec();
+
+        for height in 0..DEPTH {
+            let bit = match prefix.pop() {
+                Some(bit) => bit,
+                None => return false,
+            };
+            let sibling = proof
+                .siblings
+                .iter()
+                .find(|(h, _)| *h == height)
+                .map(|(_, hash)| *hash)
+                .unwrap_or(default_hashes[height]);
+
+            let (left, right) = if bit { (sibling, current) } else { (current, sibling) };
+            current = H::hash(&[left.as_ref(), right.as_ref()].concat());
+        }
+
+        current.as_ref() == root
+    }
+}
+
+impl<H: Hasher<Hash = [u8; 32]>> Default for SparseMerkleTree<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(address: &str) -> Account {
+        Account {
+            address: address.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_empty_tree_same_root_from_two_instances() {
+        let a: SparseMerkleTree = SparseMerkleTree::new();
+        let b: SparseMerkleTree = SparseMerkleTree::new();
+        assert_eq!(a.get_root(), b.get_root());
+    }
+
+    #[test]
+    fn test_update_changes_root() {
+        let mut tree: SparseMerkleTree = SparseMerkleTree::new();
+        let empty_root = tree.get_root();
+        let root_after = tree.update(&account("alice"), 100.0);
+        assert_ne!(empty_root, root_after);
+    }
+
+    #[test]
+    fn test_prove_and_verify_membership() {
+        let mut tree: SparseMerkleTree = SparseMerkleTree::new();
+        tree.update(&account("alice"), 100.0);
+        tree.update(&account("bob"), 50.0);
+
+        let proof = tree.prove(&account("alice"));
+        assert!(SparseMerkleTree::<CustomHasher>::verify(
+            &tree.get_root(),
+            &account("alice"),
+            Some(100.0),
+            &proof,
+        ));
+    }
+
+    #[test]
+    fn test_prove_and_verify_non_membership() {
+        let mut tree: SparseMerkleTree = SparseMerkleTree::new();
+        tree.update(&account("alice"), 100.0);
+
+        let proof = tree.prove(&account("carol"));
+        assert!(SparseMerkleTree::<CustomHasher>::verify(
+            &tree.get_root(),
+            &account("carol"),
+            None,
+            &proof,
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_balance() {
+        let mut tree: SparseMerkleTree = SparseMerkleTree::new();
+        tree.update(&account("alice"), 100.0);
+
+        let proof = tree.prove(&account("alice"));
+        assert!(!SparseMerkleTree::<CustomHasher>::verify(
+            &tree.get_root(),
+            &account("alice"),
+            Some(999.0),
+            &proof,
+        ));
+    }
+
+    #[test]
+    fn test_zero_balance_update_returns_to_non_membership() {
+        let mut tree: SparseMerkleTree = SparseMerkleTree::new();
+        tree.update(&account("alice"), 100.0);
+        tree.update(&account("alice"), 0.0);
+
+        let proof = tree.prove(&account("alice"));
+        assert!(SparseMerkleTree::<CustomHasher>::verify(
+            &tree.get_root(),
+            &account("alice"),
+            None,
+            &proof,
+        ));
+    }
+}
@@ -0,0 +1,337 @@
+//! Persistent, crash-recoverable blockchain storage.
+//!
+//! Wraps a SQLite database (an Alfis-style `blockchain.db`, by default)
+//! holding executed blocks, account/stake balances, and hashchain
+//! commitments, so a restarted node reconstructs its tip from disk instead
+//! of re-syncing from genesis. `Blockchain::open` is the persistent
+//! counterpart to `Blockchain::new`.
+
+use crate::accounts::{Account, State};
+use crate::block::Block;
+use crate::chain_spec::Network;
+use crate::hashchain::HashChain;
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub struct ChainStore {
+    conn: Connection,
+}
+
+impl ChainStore {
+    /// Opens (creating if necessary) the database at `path` and ensures its
+    /// schema exists.
+    pub fn open(path: &str) -> Result<Self, String> {
+        let conn =
+            Connection::open(path).map_err(|e| format!("failed to open chain db at {}: {}", path, e))?;
+        let store = Self { conn };
+        store.init_schema()?;
+        Ok(store)
+    }
+
+    /// Opens a throwaway in-memory database, used for tests.
+    pub fn open_in_memory() -> Result<Self, String> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| format!("failed to open in-memory chain db: {}", e))?;
+        let store = Self { conn };
+        store.init_schema()?;
+        Ok(store)
+    }
+
+    /// Opens (or creates) the database at `path` the same way as
+    /// [`ChainStore::open`], then checks the network a prior run persisted
+    /// here (if any) against `network`. A fresh database simply records
+    /// `network`; a mismatch is rejected outright, so a node can't
+    /// accidentally resume a `Mainnet` chain's database against a
+    /// `Testnet`/`Dev` spec or vice versa.
+    pub fn open_for_network(path: &str, network: Network) -> Result<Self, String> {
+        let store = Self::open(path)?;
+        match store.load_network()? {
+            Some(persisted) if persisted != network => Err(format!(
+                "chain db at {} was created for {:?} but this node is configured for {:?}",
+                path, persisted, network
+            )),
+            Some(_) => Ok(store),
+            None => {
+                store.persist_network(network)?;
+                Ok(store)
+            }
+        }
+    }
+
+    fn init_schema(&self) -> Result<(), String> {
+        self.conn
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS blocks (
+                    id   INTEGER PRIMARY KEY,
+                    data TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS account_state (
+                    address TEXT PRIMARY KEY,
+                    balance REAL NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS hashchain (
+                    idx  INTEGER PRIMARY KEY,
+                    hash TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS meta (
+                    key   TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                );",
+            )
+            .map_err(|e| format!("failed to initialize chain db schema: {}", e))
+    }
+
+    /// Atomically persists a newly executed block together with the account
+    /// state it produced, so a crash between the two writes can never leave
+    /// the on-disk chain and its balances disagreeing.
+    pub fn persist_block(&mut self, block: &Block, state: &State) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("failed to start chain db transaction: {}", e))?;
+
+        let block_json =
+            serde_json::to_string(block).map_err(|e| format!("failed to serialize block: {}", e))?;
+        tx.execute(
+            "INSERT OR REPLACE INTO blocks (id, data) VALUES (?1, ?2)",
+            params![block.id as i64, block_json],
+        )
+        .map_err(|e| format!("failed to persist block {}: {}", block.id, e))?;
+
+        for account in &state.accounts {
+            let balance = state.balances.get(account).cloned().unwrap_or(0.0);
+            tx.execute(
+                "INSERT OR REPLACE INTO account_state (address, balance) VALUES (?1, ?2)",
+                params![account.address, balance],
+            )
+            .map_err(|e| format!("failed to persist balance for {}: {}", account.address, e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("failed to commit chain db transaction: {}", e))
+    }
+
+    /// Persists the full hashchain, keyed by its position.
+    pub fn persist_hash_chain(&self, hash_chain: &HashChain) -> Result<(), String> {
+        for (idx, hash) in hash_chain.hash_chain.iter().enumerate() {
+            self.conn
+                .execute(
+                    "INSERT OR REPLACE INTO hashchain (idx, hash) VALUES (?1, ?2)",
+                    params![idx as i64, hash],
+                )
+                .map_err(|e| format!("failed to persist hashchain entry {}: {}", idx, e))?;
+        }
+        Ok(())
+    }
+
+    /// Loads every block, ordered by id, so the chain can be replayed on boot.
+    pub fn load_chain(&self) -> Result<Vec<Block>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM blocks ORDER BY id ASC")
+            .map_err(|e| format!("failed to prepare block load query: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("failed to query blocks: {}", e))?;
+
+        let mut blocks = Vec::new();
+        for row in rows {
+            let json = row.map_err(|e| format!("failed to read block row: {}", e))?;
+            let block: Block = serde_json::from_str(&json)
+                .map_err(|e| format!("failed to deserialize block: {}", e))?;
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    /// Loads the persisted account/stake state.
+    pub fn load_state(&self) -> Result<State, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT address, balance FROM account_state")
+            .map_err(|e| format!("failed to prepare state load query: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?)))
+            .map_err(|e| format!("failed to query account state: {}", e))?;
+
+        let mut state = State::new();
+        for row in rows {
+            let (address, balance) = row.map_err(|e| format!("failed to read state row: {}", e))?;
+            let account = Account { address };
+            state.accounts.push(account.clone());
+            state.balances.insert(account, balance);
+        }
+        Ok(state)
+    }
+
+    /// Records which `Network` this database belongs to.
+    pub fn persist_network(&self, network: Network) -> Result<(), String> {
+        let json = serde_json::to_string(&network)
+            .map_err(|e| format!("failed to serialize network: {}", e))?;
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES ('network', ?1)",
+                params![json],
+            )
+            .map_err(|e| format!("failed to persist network: {}", e))?;
+        Ok(())
+    }
+
+    /// Loads the `Network` a prior run persisted here, if any.
+    pub fn load_network(&self) -> Result<Option<Network>, String> {
+        let value: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'network'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("failed to query persisted network: {}", e))?;
+
+        match value {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| format!("failed to deserialize persisted network: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads the persisted hashchain, ordered by position.
+    pub fn load_hash_chain(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT hash FROM hashchain ORDER BY idx ASC")
+            .map_err(|e| format!("failed to prepare hashchain load query: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("failed to query hashchain: {}", e))?;
+
+        let mut hashes = Vec::new();
+        for row in rows {
+            hashes.push(row.map_err(|e| format!("failed to read hashchain row: {}", e))?);
+        }
+        Ok(hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::Account;
+    use crate::utils::Seed;
+    use std::fs;
+
+    fn sample_block(id: usize) -> Block {
+        let account = Account {
+            address: "test-proposer".to_string(),
+        };
+        Block::new(
+            id,
+            [0u8; 32],
+            id,
+            vec![],
+            account.clone(),
+            "proposer-hash".to_string(),
+            Seed { seed: [0u8; 32] },
+            None,
+            None,
+        )
+        .expect("failed to build test block")
+    }
+
+    #[test]
+    fn test_persist_and_load_chain_round_trips() {
+        let mut store = ChainStore::open_in_memory().expect("failed to open in-memory store");
+        let block = sample_block(1);
+        let mut state = State::new();
+        let account = Account {
+            address: "alice".to_string(),
+        };
+        state.add_account(account.clone());
+        state.stake(account, 50.0).unwrap();
+
+        store
+            .persist_block(&block, &state)
+            .expect("failed to persist block");
+
+        let loaded_chain = store.load_chain().expect("failed to load chain");
+        assert_eq!(loaded_chain.len(), 1);
+        assert_eq!(loaded_chain[0].id, 1);
+
+        let loaded_state = store.load_state().expect("failed to load state");
+        assert_eq!(loaded_state.get_balance(Account {
+            address: "alice".to_string(),
+        }), 50.0);
+    }
+
+    #[test]
+    fn test_persist_hash_chain_round_trips() {
+        let store = ChainStore::open_in_memory().expect("failed to open in-memory store");
+        let hash_chain = HashChain {
+            hash_chain: vec!["aa".to_string(), "bb".to_string()],
+        };
+        store
+            .persist_hash_chain(&hash_chain)
+            .expect("failed to persist hashchain");
+
+        let loaded = store.load_hash_chain().expect("failed to load hashchain");
+        assert_eq!(loaded, hash_chain.hash_chain);
+    }
+
+    #[test]
+    fn test_persist_and_load_network_round_trips() {
+        let store = ChainStore::open_in_memory().expect("failed to open in-memory store");
+        assert_eq!(store.load_network().expect("failed to load network"), None);
+
+        store
+            .persist_network(Network::Testnet)
+            .expect("failed to persist network");
+        assert_eq!(
+            store.load_network().expect("failed to load network"),
+            Some(Network::Testnet)
+        );
+    }
+
+    #[test]
+    fn test_open_for_network_records_the_network_on_a_fresh_database() {
+        let path = format!("{}/chain_store_test_{}.db", std::env::temp_dir().display(), "fresh");
+        let _ = fs::remove_file(&path);
+
+        let store =
+            ChainStore::open_for_network(&path, Network::Dev).expect("failed to open for network");
+        assert_eq!(store.load_network().unwrap(), Some(Network::Dev));
+        drop(store);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_open_for_network_rejects_a_mismatched_network_on_reopen() {
+        let path = format!("{}/chain_store_test_{}.db", std::env::temp_dir().display(), "mismatch");
+        let _ = fs::remove_file(&path);
+
+        {
+            let _store = ChainStore::open_for_network(&path, Network::Testnet)
+                .expect("failed to open for network");
+        }
+
+        let result = ChainStore::open_for_network(&path, Network::Mainnet);
+        assert!(result.is_err(), "reopening a Testnet db as Mainnet must be rejected");
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_persist_block_overwrites_same_id() {
+        let mut store = ChainStore::open_in_memory().expect("failed to open in-memory store");
+        let state = State::new();
+        store
+            .persist_block(&sample_block(1), &state)
+            .expect("failed to persist first block");
+        store
+            .persist_block(&sample_block(1), &state)
+            .expect("failed to persist replacement block");
+
+        let loaded_chain = store.load_chain().expect("failed to load chain");
+        assert_eq!(loaded_chain.len(), 1, "re-persisting the same block id must not duplicate rows");
+    }
+}
@@ -1,5 +1,6 @@
 use crate::accounts::Account;
 use crate::wallet::Wallet;
+use crate::zkid::KeyOwnershipProof;
 use chrono::Utc;
 use crystals_dilithium::dilithium2::{PublicKey, Signature};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -34,6 +35,8 @@ pub enum TransactionType {
     VALIDATOR,
     ValidatorReward,
     COMMIT,
+    BRIDGE_DEPOSIT,
+    WITHDRAW,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,7 +50,17 @@ pub struct Transaction {
     pub amount: f64,
     pub timestamp: usize,
     pub fee: usize,
+    /// This sender's expected next nonce at the time of signing. Checked
+    /// (and advanced) by `State::apply_transaction` so a captured
+    /// transaction can't be replayed and an out-of-order one is rejected.
+    pub nonce: u64,
     pub txn_type: TransactionType,
+    /// Proof that `sender` holds the secret key behind its committed
+    /// post-quantum public key, required on `STAKE` transactions so
+    /// `Validator::add_validator` can bind admission to provable key
+    /// ownership instead of trusting the stake amount blindly. `None` for
+    /// every other transaction type, which doesn't need it.
+    pub key_ownership_proof: Option<KeyOwnershipProof>,
 }
 
 impl Transaction {
@@ -57,7 +70,9 @@ impl Transaction {
         recipient: Account,
         amount: f64,
         fee: usize,
+        nonce: u64,
         txn_type: TransactionType,
+        key_ownership_proof: Option<KeyOwnershipProof>,
     ) -> Result<Self, String> {
         let timestamp = Utc::now().timestamp_millis() as usize;
         let mut txn = Self {
@@ -68,7 +83,9 @@ impl Transaction {
             amount,
             timestamp,
             fee,
+            nonce,
             txn_type,
+            key_ownership_proof,
         };
         txn.hash = txn.compute_hash();
         txn.signature = sender_wallet.sign_message(&txn.hash);
@@ -81,6 +98,20 @@ impl Transaction {
         Ok(public_key.verify(msg, &self.signature))
     }
 
+    /// Checks this transaction's signature and, on success, consumes it
+    /// into a [`VerifiedTransaction`] — the only way to construct one.
+    /// Execution (`Blockchain::handle_transaction` and friends) takes a
+    /// `VerifiedTransaction` rather than re-running `verify()` itself, so a
+    /// block's signatures are each checked exactly once instead of two or
+    /// three times on the way to being applied.
+    pub fn verify_into(self) -> Result<VerifiedTransaction, String> {
+        if self.verify()? {
+            Ok(VerifiedTransaction(self))
+        } else {
+            Err(format!("invalid signature for transaction {:?}", self.hash))
+        }
+    }
+
     pub fn compute_hash(&self) -> [u8; 32] {
         let mut hasher = Sha3_256::new();
         hasher.update(self.sender.address.as_bytes());
@@ -88,7 +119,62 @@ impl Transaction {
         hasher.update(self.amount.to_string().as_bytes());
         hasher.update(self.timestamp.to_string().as_bytes());
         hasher.update(self.fee.to_string().as_bytes());
+        hasher.update(self.nonce.to_be_bytes());
         hasher.update(serde_json::to_string(&self.txn_type).unwrap().as_bytes());
         hasher.finalize().into()
     }
 }
+
+/// A `Transaction` whose signature has already been checked by
+/// [`Transaction::verify_into`]. Only that constructor can produce one, so
+/// any function taking `VerifiedTransaction` instead of `Transaction` has
+/// the compiler's guarantee that verification already happened.
+#[derive(Debug, Clone)]
+pub struct VerifiedTransaction(Transaction);
+
+impl VerifiedTransaction {
+    /// Discards the verified-ness guarantee and returns the plain
+    /// `Transaction`, e.g. to store it in the mempool or a block buffer.
+    pub fn into_inner(self) -> Transaction {
+        self.0
+    }
+}
+
+impl std::ops::Deref for VerifiedTransaction {
+    type Target = Transaction;
+
+    fn deref(&self) -> &Transaction {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_into_accepts_a_correctly_signed_transaction() {
+        let mut wallet = Wallet::new().unwrap();
+        let sender = Account { address: wallet.get_public_key() };
+        let recipient = Account { address: "recipient".to_string() };
+        let txn = Transaction::new(&mut wallet, sender, recipient, 10.0, 0, 0, TransactionType::TRANSACTION, None).unwrap();
+
+        let hash = txn.hash;
+        let verified = txn.verify_into().expect("a correctly signed transaction must verify");
+        assert_eq!(verified.hash, hash);
+    }
+
+    #[test]
+    fn test_verify_into_rejects_a_transaction_with_a_mismatched_sender() {
+        let mut wallet = Wallet::new().unwrap();
+        let sender = Account { address: wallet.get_public_key() };
+        let recipient = Account { address: "recipient".to_string() };
+        let mut txn = Transaction::new(&mut wallet, sender, recipient, 10.0, 0, 0, TransactionType::TRANSACTION, None).unwrap();
+
+        // Swap in an unrelated sender whose key never signed this payload.
+        let other = Wallet::new().unwrap();
+        txn.sender = Account { address: other.get_public_key() };
+
+        assert!(txn.verify_into().is_err(), "a transaction signed by a different key must fail verification");
+    }
+}
@@ -1,7 +1,6 @@
 use crate::accounts::Account;
 use crate::validator::Validator;
 use serde::{Deserialize, Serialize};
-use sha3::{Digest, Sha3_256};
 use std::time::Instant;
 
 // --- TPS Tracker Struct ---
@@ -49,47 +48,132 @@ pub fn get_block_seed(proposer_hash: String, prev_seed: [u8; 32]) -> Seed {
     }
 }
 
-pub fn select_block_proposer(seed: Seed, validator: &Validator) -> &Account {
-    let n: f64 = 1e9;
-    let mut weights = vec![0f64; validator.state.accounts.len()];
-    let mut proposer = &validator.state.accounts[0];
-
-    for (i, account) in validator.state.accounts.iter().enumerate() {
-        let mut hasher = Sha3_256::new();
-        hasher.update(seed.get_seed());
-        // validator hash chain commitment
-        // info!("Validator hash chain commitment: {:?}", validator.hash_chain_com.get(&account.address).unwrap().hash_chain_index);
-        // info!("Validator latest epoch hash: {:?}", validator.next_block_hash.get(&account));
-        // let mut hash_value = &validator.hash_chain_com.get(&account.address).unwrap().hash_chain_index;
-        // if validator.next_block_hash.get(&account).is_some() {
-        //     hash_value = validator.next_block_hash.get(&account).unwrap();
-        // }
-        if let Some(hash_value) = validator.hash_chain_com.get(&account.address) {
-            hasher.update(hash_value.hash_chain_index.as_bytes());
-            let hash_result = hasher.finalize();
-            let numeric_value = u64::from_be_bytes([
-                hash_result[0],
-                hash_result[1],
-                hash_result[2],
-                hash_result[3],
-                hash_result[4],
-                hash_result[5],
-                hash_result[6],
-                hash_result[7],
-            ]);
-
-            if let Some(balance) = validator.state.balances.get(&account) {
-                weights[i] = n - (numeric_value as f64 / balance);
+fn sha3_256_of(bytes: &[u8]) -> [u8; 32] {
+    use sha3::{Digest, Sha3_256};
+    let digest = Sha3_256::digest(bytes);
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(&digest);
+    buf
+}
+
+/// Deterministic, stake-weighted integer sortition for one BFT round.
+///
+/// Hashes `seed || round` with SHA3-256 to get a 256-bit value `h`, treats
+/// `h` as the numerator of a fraction `h / 2^256`, and walks the active
+/// validator set in canonical (address-sorted) order to find the account
+/// whose cumulative stake interval `[Σ_{j<i} stake_j, Σ_{j<=i} stake_j) /
+/// total_stake` contains that fraction. The containment test is done with
+/// `num_bigint::BigUint` (`(h * total_stake) >> 256` lands in
+/// `[0, total_stake)` and is compared against running integer sums), so two
+/// honest nodes with the same `Seed` and `Validator` state always compute
+/// the identical proposer — no floating-point rounding, which previously
+/// made the now-deleted float-weighted `select_block_proposer` liable to
+/// diverge between platforms. Folding `round` into the hash reselects a
+/// different proposer each time a round times out without quorum, instead
+/// of retrying the same one. Unlike `select_block_proposer`, this doesn't
+/// depend on `hash_chain_com` being populated for the current epoch, which
+/// keeps it usable as soon as a round starts.
+pub fn select_round_proposer(seed: Seed, round: u64, validator: &Validator) -> Option<Account> {
+    let mut stakes: Vec<(Account, u64)> = validator
+        .state
+        .accounts
+        .iter()
+        .filter_map(|account| {
+            let balance = validator.state.balances.get(account).cloned().unwrap_or(0.0);
+            if balance <= 0.0 {
+                return None;
             }
+            Some((account.clone(), balance as u64))
+        })
+        .collect();
+    stakes.sort_by(|(a, _), (b, _)| a.address.cmp(&b.address));
+
+    let total_stake: u64 = stakes.iter().map(|(_, stake)| stake).sum();
+    if total_stake == 0 {
+        return None;
+    }
+
+    let mut input = seed.get_seed().to_vec();
+    input.extend_from_slice(&round.to_be_bytes());
+    let h = num_bigint::BigUint::from_bytes_be(&sha3_256_of(&input));
+    let target = (h * num_bigint::BigUint::from(total_stake)) >> 256u32;
+    let target: u64 = target
+        .try_into()
+        .expect("(h * total_stake) >> 256 is always < total_stake, which fits in u64");
+
+    let mut cumulative: u64 = 0;
+    for (account, stake) in stakes {
+        cumulative += stake;
+        if target < cumulative {
+            return Some(account);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator_with_stakes(stakes: &[(&str, f64)]) -> Validator {
+        let mut validator = Validator::new();
+        for (address, balance) in stakes {
+            let account = Account { address: address.to_string() };
+            validator.state.add_account(account.clone());
+            validator.state.balances.insert(account, *balance);
+        }
+        validator
+    }
+
+    #[test]
+    fn test_select_round_proposer_is_deterministic_across_repeated_calls() {
+        let validator = validator_with_stakes(&[("a", 100.0), ("b", 200.0), ("c", 50.0)]);
+        let seed = Seed { seed: [7u8; 32] };
+        let first = select_round_proposer(seed, 3, &validator);
+        let second = select_round_proposer(seed, 3, &validator);
+        assert_eq!(first, second, "the same seed, round and state must always pick the same proposer");
+    }
+
+    #[test]
+    fn test_select_round_proposer_changes_when_the_round_changes() {
+        let validator = validator_with_stakes(&[("a", 100.0), ("b", 200.0), ("c", 50.0)]);
+        let seed = Seed { seed: [7u8; 32] };
+        let proposers: std::collections::HashSet<Option<String>> = (0..8)
+            .map(|round| select_round_proposer(seed, round, &validator).map(|a| a.address))
+            .collect();
+        assert!(
+            proposers.len() > 1,
+            "folding round into the hash should select different proposers across rounds, got {:?}",
+            proposers
+        );
+    }
+
+    #[test]
+    fn test_select_round_proposer_ignores_zero_and_negative_balance_accounts() {
+        let validator = validator_with_stakes(&[("a", 0.0), ("b", 100.0), ("c", -5.0)]);
+        let seed = Seed { seed: [1u8; 32] };
+        for round in 0..16 {
+            let proposer = select_round_proposer(seed, round, &validator)
+                .expect("an account with positive stake exists");
+            assert_eq!(proposer.address, "b");
         }
     }
 
-    let mut lowest_weight = f64::INFINITY;
-    for (i, weight) in weights.iter().enumerate() {
-        if *weight < lowest_weight {
-            lowest_weight = *weight;
-            proposer = &validator.state.accounts[i];
+    #[test]
+    fn test_select_round_proposer_returns_none_with_no_staked_accounts() {
+        let validator = Validator::new();
+        let seed = Seed { seed: [0u8; 32] };
+        assert!(select_round_proposer(seed, 0, &validator).is_none());
+    }
+
+    #[test]
+    fn test_select_round_proposer_only_ever_returns_an_active_account() {
+        let validator = validator_with_stakes(&[("a", 10.0), ("b", 90.0)]);
+        for round in 0..32 {
+            let seed = Seed { seed: [round as u8; 32] };
+            let proposer = select_round_proposer(seed, round, &validator)
+                .expect("some account should be selected");
+            assert!(["a", "b"].contains(&proposer.address.as_str()));
         }
     }
-    proposer
 }
@@ -2,6 +2,8 @@ use crate::accounts::{Account, State};
 use crate::config::STAKING_AMOUNT;
 use crate::hashchain::HashChainCom;
 use crate::transaction::{Transaction, TransactionType};
+use crate::zkid::verify_key_ownership;
+use log::error;
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -9,6 +11,17 @@ pub struct Validator {
     pub state: State,
     pub hash_chain_com: HashMap<String, HashChainCom>,
     pub next_block_hash: HashMap<Account, String>,
+    /// Coin commitments for the private leader lottery, registered each
+    /// epoch alongside the hashchain commitment (see `crate::lottery`).
+    pub coin_commitments: HashMap<String, [u8; 32]>,
+    /// Nullifiers of coins that have already won a slot this epoch, so a
+    /// coin can't be replayed for a second block before it evolves.
+    pub used_nullifiers: Vec<[u8; 32]>,
+    /// The first `(block_hash, signature)` seen from each
+    /// `(sender_address, block_id)` pair's `BlockSignature`, so a second,
+    /// differently-hashed signature for the same height reveals
+    /// equivocation. See `record_block_signature_witness`.
+    pub seen_block_signatures: HashMap<(String, usize), (String, Vec<u8>)>,
 }
 
 impl Validator {
@@ -17,24 +30,76 @@ impl Validator {
             state: State::new(),
             hash_chain_com: HashMap::new(),
             next_block_hash: HashMap::new(),
+            coin_commitments: HashMap::new(),
+            used_nullifiers: Vec::new(),
+            seen_block_signatures: HashMap::new(),
         }
     }
 
+    /// Total stake backing the active validator set, used as the lottery's
+    /// `total_active_stake` denominator.
+    pub fn total_active_stake(&self) -> f64 {
+        self.state.balances.values().sum()
+    }
+
+    pub fn get_coin_commitment(&self, account: &Account) -> Option<&[u8; 32]> {
+        self.coin_commitments.get(&account.address)
+    }
+
+    pub fn is_nullifier_used(&self, nullifier: &[u8; 32]) -> bool {
+        self.used_nullifiers.contains(nullifier)
+    }
+
+    pub fn mark_nullifier_used(&mut self, nullifier: [u8; 32]) {
+        self.used_nullifiers.push(nullifier);
+    }
+
+    /// Clears spent nullifiers at epoch boundaries, since coins are only
+    /// forbidden from winning twice within the same epoch.
+    pub fn reset_nullifiers(&mut self) {
+        self.used_nullifiers.clear();
+    }
+
     pub fn add_validator(&mut self, account: Account, txn: Transaction) -> Result<bool, String> {
+        if txn.txn_type == TransactionType::STAKE {
+            let proof = txn
+                .key_ownership_proof
+                .as_ref()
+                .ok_or_else(|| format!("STAKE transaction from {} carries no key-ownership proof", account.address))?;
+            if !verify_key_ownership(proof, &account) {
+                return Err(format!(
+                    "key-ownership proof for {} failed verification",
+                    account.address
+                ));
+            }
+        }
         self.state.add_account(account.clone());
         if txn.txn_type == TransactionType::STAKE && txn.amount >= STAKING_AMOUNT {
-            self.state.stake(account.clone(), txn.amount);
+            self.state
+                .stake(account.clone(), txn.amount)
+                .map_err(|e| e.to_string())?;
             self.state.balances.insert(account.clone(), txn.amount);
             // self.state.accounts.push(account.clone());
         }
         Ok(true)
     }
 
+    /// Applies each buffered `(account, txn)` pair via `add_validator`,
+    /// dropping (and logging) any pair `add_validator` rejects rather than
+    /// panicking. A buffered STAKE transaction can carry an attacker-chosen
+    /// `key_ownership_proof` — gossiped, signed, and buffered without ever
+    /// being checked against `verify_key_ownership` upstream — so rejection
+    /// here is an expected, not exceptional, outcome; unwrapping it would
+    /// let one malformed proof crash every node that calls `end_of_epoch`.
     pub fn apply_buffer(&mut self, accounts: Vec<Account>, txns: Vec<Transaction>) {
         // update the list of validators by calling add_validator for each account
         for (i, account) in accounts.iter().enumerate() {
-            self.add_validator(account.clone(), txns.get(i).unwrap().clone())
-                .unwrap();
+            if let Err(e) = self.add_validator(account.clone(), txns.get(i).unwrap().clone()) {
+                error!(
+                    "Dropping buffered validator update for {}: {}",
+                    account.address, e
+                );
+            }
         }
     }
 
@@ -55,16 +120,181 @@ impl Validator {
     }
 
     pub fn update_validator_com(&mut self, account: Account, com: HashChainCom) {
+        self.coin_commitments
+            .insert(account.address.clone(), com.coin_commitment);
         self.hash_chain_com.insert(account.address, com);
     }
 
     #[allow(dead_code)]
     pub fn reset_validator_com(&mut self) {
         self.hash_chain_com.clear();
+        self.coin_commitments.clear();
     }
 
     #[allow(dead_code)]
     pub fn hash_chain_received(&self) -> bool {
         self.hash_chain_com.len() == self.state.accounts.len()
     }
+
+    /// Records a `BlockSignature`'s `(hash, signature)` as the first one
+    /// seen from `account` at `height`. If a different hash signed by the
+    /// same account for the same height was already on file, that prior
+    /// `(hash, signature)` pair is returned instead of overwriting it —
+    /// that's the equivocation evidence the caller needs to build a
+    /// `SlashingEvidence`. A repeat of the exact same hash is not
+    /// equivocation and leaves the recorded witness untouched.
+    pub fn record_block_signature_witness(
+        &mut self,
+        account: &Account,
+        height: usize,
+        hash: &str,
+        signature: &[u8],
+    ) -> Option<(String, Vec<u8>)> {
+        let key = (account.address.clone(), height);
+        match self.seen_block_signatures.get(&key) {
+            Some((prior_hash, _)) if prior_hash == hash => None,
+            Some((prior_hash, prior_sig)) => Some((prior_hash.clone(), prior_sig.clone())),
+            None => {
+                self.seen_block_signatures
+                    .insert(key, (hash.to_string(), signature.to_vec()));
+                None
+            }
+        }
+    }
+
+    /// Slashes a validator proven (via independently-verifiable
+    /// `SlashingEvidence`) to have signed two different block hashes at the
+    /// same height: zeroes its stake and balance and drops it from the
+    /// active validator set entirely via `State::remove_account`, which
+    /// also removes its `balances` entry, so `get_balance` then reports 0.
+    pub fn slash(&mut self, account: &Account) {
+        self.state.balances.insert(account.clone(), 0.0);
+        self.state.remove_account(account.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::Wallet;
+    use crate::zkid::prove_key_ownership;
+
+    #[test]
+    fn test_add_validator_rejects_a_stake_with_no_key_ownership_proof() {
+        let mut validator = Validator::new();
+        let mut wallet = Wallet::new().unwrap();
+        let account = Account { address: wallet.get_public_key() };
+        let txn = Transaction::new(&mut wallet, account.clone(), account.clone(), STAKING_AMOUNT, 0, 0, TransactionType::STAKE, None).unwrap();
+
+        assert!(
+            validator.add_validator(account, txn).is_err(),
+            "a STAKE transaction with no key-ownership proof must be rejected"
+        );
+    }
+
+    #[test]
+    fn test_add_validator_accepts_a_stake_with_a_valid_key_ownership_proof() {
+        let mut validator = Validator::new();
+        let mut wallet = Wallet::new().unwrap();
+        let account = Account { address: wallet.get_public_key() };
+        let proof = prove_key_ownership(&wallet);
+        let txn = Transaction::new(&mut wallet, account.clone(), account.clone(), STAKING_AMOUNT, 0, 0, TransactionType::STAKE, Some(proof)).unwrap();
+
+        assert!(
+            validator.add_validator(account.clone(), txn).is_ok(),
+            "a STAKE transaction with a valid key-ownership proof must be admitted"
+        );
+        assert_eq!(validator.state.get_balance(account), STAKING_AMOUNT);
+    }
+
+    #[test]
+    fn test_add_validator_rejects_a_stake_with_someone_elses_key_ownership_proof() {
+        let mut validator = Validator::new();
+        let mut wallet = Wallet::new().unwrap();
+        let other_wallet = Wallet::new().unwrap();
+        let account = Account { address: wallet.get_public_key() };
+        let mismatched_proof = prove_key_ownership(&other_wallet);
+        let txn = Transaction::new(&mut wallet, account.clone(), account.clone(), STAKING_AMOUNT, 0, 0, TransactionType::STAKE, Some(mismatched_proof)).unwrap();
+
+        // `verify_key_ownership` binds `proof.digest` to the staking
+        // account's own public key, so a proof built for `other_wallet`
+        // (self-consistent on its own `s1`/`s2`/`t`, per
+        // `DilithiumCore::define`'s tautological in-circuit constraint) is
+        // still rejected here on the account/digest mismatch.
+        assert!(
+            validator.add_validator(account, txn).is_err(),
+            "a key-ownership proof built for a different wallet must be rejected"
+        );
+    }
+
+    #[test]
+    fn test_apply_buffer_drops_a_malformed_proof_instead_of_panicking() {
+        let mut validator = Validator::new();
+        let mut wallet = Wallet::new().unwrap();
+        let account = Account { address: wallet.get_public_key() };
+        let txn = Transaction::new(&mut wallet, account.clone(), account.clone(), STAKING_AMOUNT, 0, 0, TransactionType::STAKE, None).unwrap();
+
+        // Must not panic: a buffered STAKE transaction with no/garbage
+        // key-ownership proof is dropped, not unwrapped.
+        validator.apply_buffer(vec![account.clone()], vec![txn]);
+
+        assert!(
+            !validator.state.accounts.contains(&account),
+            "a rejected validator update must not be admitted"
+        );
+    }
+
+    #[test]
+    fn test_record_block_signature_witness_accepts_first_sighting_silently() {
+        let mut validator = Validator::new();
+        let account = Account { address: "validator-a".to_string() };
+        let conflict = validator.record_block_signature_witness(&account, 1, "hash-a", b"sig-a");
+        assert!(conflict.is_none(), "the first hash seen from an account at a height is not equivocation");
+    }
+
+    #[test]
+    fn test_record_block_signature_witness_ignores_a_repeated_identical_hash() {
+        let mut validator = Validator::new();
+        let account = Account { address: "validator-a".to_string() };
+        validator.record_block_signature_witness(&account, 1, "hash-a", b"sig-a");
+        let conflict = validator.record_block_signature_witness(&account, 1, "hash-a", b"sig-a");
+        assert!(conflict.is_none(), "re-gossip of the same signed hash must not look like equivocation");
+    }
+
+    #[test]
+    fn test_record_block_signature_witness_flags_a_second_distinct_hash() {
+        let mut validator = Validator::new();
+        let account = Account { address: "validator-a".to_string() };
+        validator.record_block_signature_witness(&account, 1, "hash-a", b"sig-a");
+        let conflict = validator
+            .record_block_signature_witness(&account, 1, "hash-b", b"sig-b")
+            .expect("a second distinct hash at the same height is equivocation evidence");
+        assert_eq!(conflict, ("hash-a".to_string(), b"sig-a".to_vec()));
+    }
+
+    #[test]
+    fn test_record_block_signature_witness_does_not_conflate_different_heights() {
+        let mut validator = Validator::new();
+        let account = Account { address: "validator-a".to_string() };
+        validator.record_block_signature_witness(&account, 1, "hash-a", b"sig-a");
+        let conflict = validator.record_block_signature_witness(&account, 2, "hash-b", b"sig-b");
+        assert!(conflict.is_none(), "a different height for the same account is not equivocation");
+    }
+
+    #[test]
+    fn test_slash_zeroes_balance_and_removes_the_account() {
+        let mut validator = Validator::new();
+        let account = Account { address: "validator-a".to_string() };
+        validator.state.add_account(account.clone());
+        validator.state.stake(account.clone(), 100.0).unwrap();
+        assert_eq!(validator.state.get_balance(account.clone()), 100.0);
+
+        validator.slash(&account);
+
+        assert_eq!(validator.state.get_balance(account.clone()), 0.0);
+        assert!(
+            !validator.state.accounts.contains(&account),
+            "a slashed validator must be dropped from the active set"
+        );
+    }
 }
@@ -0,0 +1,461 @@
+//! A persistent, versioned Merkle tree (modeled on zksync-era's versioned
+//! state tree + pruner) for nodes that don't fit, or shouldn't have to fit,
+//! entirely in memory like `merkle::MerkleTreeBuilder` does.
+//!
+//! Each [`VersionedMerkleTree::apply`] batches a set of leaf updates,
+//! writes only the branch/leaf nodes that changed into a pluggable
+//! [`Database`], and returns the new version number. `root`/`prove` can
+//! target any past version, so historical inclusion proofs stay servable
+//! even after later updates land — until [`MerkleTreePruner::prune`] drops
+//! node versions older than a retention cutoff, after which only versions
+//! at or after the cutoff remain provable.
+
+use crate::merkle::CustomHasher;
+use rs_merkle::Hasher;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// A key-value store for tree nodes, so `VersionedMerkleTree` doesn't care
+/// whether nodes live in memory or on disk.
+pub trait Database {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String>;
+    fn put(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<(), String>;
+    fn remove(&mut self, key: &[u8]) -> Result<(), String>;
+}
+
+/// An in-memory `Database`, useful for tests and for trees small enough
+/// that persistence doesn't matter.
+#[derive(Default)]
+pub struct InMemoryDatabase(HashMap<Vec<u8>, Vec<u8>>);
+
+impl InMemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Database for InMemoryDatabase {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.0.get(key).cloned())
+    }
+
+    fn put(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<(), String> {
+        self.0.insert(key, value);
+        Ok(())
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<(), String> {
+        self.0.remove(key);
+        Ok(())
+    }
+}
+
+/// An on-disk `Database` that stores each node as its own hex-named file
+/// under `dir`, so a long-running node can bound its in-memory footprint
+/// without pulling in a full embedded-KV-store dependency.
+pub struct FileDatabase {
+    dir: PathBuf,
+}
+
+impl FileDatabase {
+    /// Opens (creating if necessary) the node directory at `dir`.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, String> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create node db dir {}: {}", dir.display(), e))?;
+        Ok(Self { dir })
+    }
+
+    fn path_for(&self, key: &[u8]) -> PathBuf {
+        self.dir.join(hex::encode(key))
+    }
+}
+
+impl Database for FileDatabase {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        match std::fs::read(self.path_for(key)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("failed to read node {}: {}", hex::encode(key), e)),
+        }
+    }
+
+    fn put(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<(), String> {
+        std::fs::write(self.path_for(&key), value)
+            .map_err(|e| format!("failed to write node {}: {}", hex::encode(&key), e))
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<(), String> {
+        match std::fs::remove_file(self.path_for(key)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("failed to remove node {}: {}", hex::encode(key), e)),
+        }
+    }
+}
+
+/// Encodes the node at `(height, index)` as it existed starting at
+/// `version` into a flat byte key for `Database`.
+fn node_key(height: usize, index: usize, version: usize) -> Vec<u8> {
+    let mut key = Vec::with_capacity(24);
+    key.extend_from_slice(&(height as u64).to_be_bytes());
+    key.extend_from_slice(&(index as u64).to_be_bytes());
+    key.extend_from_slice(&(version as u64).to_be_bytes());
+    key
+}
+
+/// A fixed-depth Merkle tree whose nodes live in a pluggable [`Database`]
+/// and are tagged by the version that wrote them. Defaults to
+/// [`CustomHasher`] (Keccak256) and [`InMemoryDatabase`] like the rest of
+/// this crate's tree variants default to Keccak256.
+pub struct VersionedMerkleTree<H: Hasher<Hash = [u8; 32]> = CustomHasher, D: Database = InMemoryDatabase>
+{
+    depth: usize,
+    db: D,
+    /// `empty_hashes[h]` is the root of an empty subtree of height `h`.
+    empty_hashes: Vec<H::Hash>,
+    /// Ascending list of versions that wrote a new hash for `(height,
+    /// index)`, so a read at an arbitrary past version can find the latest
+    /// write at or before it without the `Database` needing to support
+    /// range scans.
+    node_versions: HashMap<(usize, usize), Vec<usize>>,
+    /// `roots[v]` is the root as of version `v`; version 0 is the empty
+    /// tree.
+    roots: Vec<H::Hash>,
+    current_version: usize,
+}
+
+impl<H: Hasher<Hash = [u8; 32]>, D: Database> VersionedMerkleTree<H, D> {
+    /// Builds an empty, version-0 tree of `2^depth` leaves backed by `db`.
+    pub fn new(depth: usize, db: D) -> Self {
+        let mut empty_hashes = Vec::with_capacity(depth + 1);
+        empty_hashes.push(H::hash(&[]));
+        for h in 0..depth {
+            let prev = empty_hashes[h];
+            empty_hashes.push(H::hash(&[prev.as_ref(), prev.as_ref()].concat()));
+        }
+        let root = empty_hashes[depth];
+
+        Self {
+            depth,
+            db,
+            empty_hashes,
+            node_versions: HashMap::new(),
+            roots: vec![root],
+            current_version: 0,
+        }
+    }
+
+    pub fn current_version(&self) -> usize {
+        self.current_version
+    }
+
+    /// Root as of `version`, which must not be newer than
+    /// `current_version`. Roots are kept separately from the node store,
+    /// so pruning never removes one; this errors only for an unknown
+    /// `version`.
+    pub fn root(&self, version: usize) -> Result<Vec<u8>, String> {
+        self.roots
+            .get(version)
+            .map(|h| h.as_ref().to_vec())
+            .ok_or_else(|| format!("no such version {}", version))
+    }
+
+    /// Reads the hash at `(height, index)` as of `version`, falling back to
+    /// the default empty-subtree hash if that coordinate had not been
+    /// written yet at `version`.
+    fn read_node(&self, height: usize, index: usize, version: usize) -> Result<H::Hash, String> {
+        let versions = match self.node_versions.get(&(height, index)) {
+            Some(versions) => versions,
+            None => return Ok(self.empty_hashes[height]),
+        };
+
+        match versions.iter().rev().find(|&&v| v <= version) {
+            None => Ok(self.empty_hashes[height]),
+            Some(&found_version) => {
+                let bytes = self
+                    .db
+                    .get(&node_key(height, index, found_version))?
+                    .ok_or_else(|| {
+                        format!(
+                            "node (height {}, index {}) missing for version {} (likely pruned)",
+                            height, index, found_version
+                        )
+                    })?;
+                let mut hash = [0u8; 32];
+                if bytes.len() != 32 {
+                    return Err(format!(
+                        "node (height {}, index {}) has malformed hash",
+                        height, index
+                    ));
+                }
+                hash.copy_from_slice(&bytes);
+                Ok(hash)
+            }
+        }
+    }
+
+    /// Applies a batch of `(leaf_index, leaf_hash)` updates, writing only
+    /// the changed branch/leaf nodes (tagged with the new version) and
+    /// returning that version. A no-op (empty `updates`) returns the
+    /// current version unchanged.
+    pub fn apply(&mut self, updates: &[(usize, H::Hash)]) -> Result<usize, String> {
+        if updates.is_empty() {
+            return Ok(self.current_version);
+        }
+
+        let max_leaves = 1usize << self.depth;
+        let mut pending: HashMap<(usize, usize), H::Hash> = HashMap::new();
+        let mut level_indices = Vec::with_capacity(updates.len());
+        for &(index, leaf) in updates {
+            if index >= max_leaves {
+                return Err(format!(
+                    "leaf index {} out of range for depth {}",
+                    index, self.depth
+                ));
+            }
+            pending.insert((0, index), leaf);
+            level_indices.push(index);
+        }
+
+        for height in 0..self.depth {
+            let mut parents: Vec<usize> = level_indices.iter().map(|i| i / 2).collect();
+            parents.sort_unstable();
+            parents.dedup();
+
+            for &parent in &parents {
+                let left_index = parent * 2;
+                let right_index = left_index + 1;
+                let left = match pending.get(&(height, left_index)) {
+                    Some(h) => *h,
+                    None => self.read_node(height, left_index, self.current_version)?,
+                };
+                let right = match pending.get(&(height, right_index)) {
+                    Some(h) => *h,
+                    None => self.read_node(height, right_index, self.current_version)?,
+                };
+                let combined = H::hash(&[left.as_ref(), right.as_ref()].concat());
+                pending.insert((height + 1, parent), combined);
+            }
+
+            level_indices = parents;
+        }
+
+        let new_version = self.current_version + 1;
+        let root = *pending
+            .get(&(self.depth, 0))
+            .ok_or_else(|| "apply produced no root".to_string())?;
+
+        for (&(height, index), &hash) in pending.iter() {
+            self.db
+                .put(node_key(height, index, new_version), hash.as_ref().to_vec())?;
+            self.node_versions
+                .entry((height, index))
+                .or_default()
+                .push(new_version);
+        }
+
+        self.roots.push(root);
+        self.current_version = new_version;
+        Ok(new_version)
+    }
+
+    /// Authentication path for `index` as of `version`, bottom-up: one
+    /// sibling hash for every level, default ones included (unlike
+    /// `sparse_merkle::SparseMerkleProof`, which keeps only stored siblings).
+    pub fn prove(&self, index: usize, version: usize) -> Result<Vec<Vec<u8>>, String> {
+        if version > self.current_version {
+            return Err(format!("no such version {}", version));
+        }
+        if index >= (1usize << self.depth) {
+            return Err(format!(
+                "leaf index {} out of range for depth {}",
+                index, self.depth
+            ));
+        }
+
+        let mut siblings = Vec::with_capacity(self.depth);
+        let mut idx = index;
+        for height in 0..self.depth {
+            let sibling_index = if idx % 2 == 0 { idx + 1 } else { idx - 1 };
+            let sibling = self.read_node(height, sibling_index, version)?;
+            siblings.push(sibling.as_ref().to_vec());
+            idx /= 2;
+        }
+        Ok(siblings)
+    }
+
+    /// Recomputes the root implied by `leaf` at `index` plus `proof` and
+    /// checks it matches `root`.
+    pub fn verify(root: &[u8], index: usize, leaf: H::Hash, proof: &[Vec<u8>]) -> bool {
+        let mut current = leaf;
+        let mut idx = index;
+        for sibling_bytes in proof {
+            if sibling_bytes.len() != 32 {
+                return false;
+            }
+            let mut sibling = [0u8; 32];
+            sibling.copy_from_slice(sibling_bytes);
+            let (left, right) = if idx % 2 == 0 {
+                (current, sibling)
+            } else {
+                (sibling, current)
+            };
+            current = H::hash(&[left.as_ref(), right.as_ref()].concat());
+            idx /= 2;
+        }
+        current.as_ref() == root
+    }
+}
+
+/// Removes node versions made unreachable by a retention cutoff: any
+/// `VersionedMerkleTree::root`/`prove` call for a version at or after the
+/// cutoff stays servable, since the newest write at or before the cutoff
+/// is always kept.
+pub struct MerkleTreePruner;
+
+impl MerkleTreePruner {
+    /// Prunes every node write older than `retain_from`, keeping the
+    /// latest write at or before it so reads for `retain_from` and any
+    /// later version remain correct. Returns the number of node versions
+    /// removed.
+    pub fn prune<H, D>(
+        tree: &mut VersionedMerkleTree<H, D>,
+        retain_from: usize,
+    ) -> Result<usize, String>
+    where
+        H: Hasher<Hash = [u8; 32]>,
+        D: Database,
+    {
+        let mut pruned = 0;
+        for (&(height, index), versions) in tree.node_versions.iter_mut() {
+            let keep_from = versions
+                .iter()
+                .rposition(|&v| v <= retain_from)
+                .unwrap_or(0);
+
+            for &stale_version in &versions[..keep_from] {
+                tree.db.remove(&node_key(height, index, stale_version))?;
+                pruned += 1;
+            }
+            versions.drain(..keep_from);
+        }
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle::CustomHasher;
+
+    fn leaf(n: u64) -> [u8; 32] {
+        CustomHasher::hash(&n.to_le_bytes())
+    }
+
+    #[test]
+    fn test_empty_tree_same_root_from_two_instances() {
+        let a: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        let b: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        assert_eq!(a.root(0).unwrap(), b.root(0).unwrap());
+    }
+
+    #[test]
+    fn test_apply_changes_root_and_bumps_version() {
+        let mut tree: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        let empty_root = tree.root(0).unwrap();
+
+        let version = tree.apply(&[(2, leaf(42))]).expect("apply failed");
+        assert_eq!(version, 1);
+        assert_eq!(tree.current_version(), 1);
+        assert_ne!(tree.root(1).unwrap(), empty_root);
+    }
+
+    #[test]
+    fn test_prove_and_verify_round_trip() {
+        let mut tree: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        tree.apply(&[(2, leaf(42)), (5, leaf(7))]).expect("apply failed");
+
+        let proof = tree.prove(2, 1).expect("prove failed");
+        assert!(VersionedMerkleTree::<CustomHasher, InMemoryDatabase>::verify(
+            &tree.root(1).unwrap(),
+            2,
+            leaf(42),
+            &proof,
+        ));
+    }
+
+    #[test]
+    fn test_past_version_still_provable_after_later_updates() {
+        let mut tree: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        tree.apply(&[(0, leaf(1))]).expect("first apply failed");
+        let root_v1 = tree.root(1).unwrap();
+        let proof_v1 = tree.prove(0, 1).expect("prove v1 failed");
+
+        tree.apply(&[(1, leaf(2))]).expect("second apply failed");
+
+        assert!(VersionedMerkleTree::<CustomHasher, InMemoryDatabase>::verify(
+            &root_v1, 0, leaf(1), &proof_v1,
+        ));
+        assert_ne!(tree.root(2).unwrap(), root_v1);
+    }
+
+    #[test]
+    fn test_apply_rejects_out_of_range_leaf_index() {
+        let mut tree: VersionedMerkleTree = VersionedMerkleTree::new(2, InMemoryDatabase::new());
+        assert!(tree.apply(&[(8, leaf(1))]).is_err());
+    }
+
+    #[test]
+    fn test_pruner_keeps_retained_version_provable() {
+        let mut tree: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        tree.apply(&[(0, leaf(1))]).unwrap();
+        tree.apply(&[(0, leaf(2))]).unwrap();
+        tree.apply(&[(0, leaf(3))]).unwrap();
+
+        let root_v2 = tree.root(2).unwrap();
+        let proof_v2 = tree.prove(0, 2).expect("prove before prune failed");
+
+        let pruned = MerkleTreePruner::prune(&mut tree, 2).expect("prune failed");
+        assert!(pruned > 0);
+
+        let proof_after = tree.prove(0, 2).expect("prove after prune failed");
+        assert_eq!(proof_v2, proof_after);
+        assert!(VersionedMerkleTree::<CustomHasher, InMemoryDatabase>::verify(
+            &root_v2, 0, leaf(2), &proof_after,
+        ));
+    }
+
+    #[test]
+    fn test_pruner_drops_stale_version_reads() {
+        let mut tree: VersionedMerkleTree = VersionedMerkleTree::new(3, InMemoryDatabase::new());
+        tree.apply(&[(0, leaf(1))]).unwrap();
+        tree.apply(&[(0, leaf(2))]).unwrap();
+
+        MerkleTreePruner::prune(&mut tree, 2).unwrap();
+
+        assert!(tree.prove(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_file_database_round_trips_like_in_memory() {
+        let dir = std::env::temp_dir().join(format!(
+            "niropok-versioned-merkle-test-{}",
+            rand::random::<u64>()
+        ));
+        let db = FileDatabase::open(&dir).expect("failed to open file db");
+        let mut tree: VersionedMerkleTree<CustomHasher, FileDatabase> =
+            VersionedMerkleTree::new(3, db);
+
+        tree.apply(&[(2, leaf(42))]).expect("apply failed");
+        let proof = tree.prove(2, 1).expect("prove failed");
+        assert!(VersionedMerkleTree::<CustomHasher, FileDatabase>::verify(
+            &tree.root(1).unwrap(),
+            2,
+            leaf(42),
+            &proof,
+        ));
+
+        std::fs::remove_dir_all(&dir).expect("failed to clean up test db dir");
+    }
+}
@@ -1,11 +1,76 @@
+use aes::cipher::{KeyIvInit, StreamCipher};
+use aes::Aes256;
 use crystals_dilithium::dilithium2::{Keypair, Signature};
+use ctr::Ctr64BE;
 use rand::Rng;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha3::{Digest, Keccak256};
+use std::io::Write;
+
+/// IV for the AES-256-CTR keystream used to encrypt wallet changesets at
+/// rest (see `derive_passphrase_key`/`Wallet::save`). Reusing one fixed IV
+/// across entries is safe here because every entry derives its key from a
+/// fresh random salt, so the same (key, IV) pair is never reused twice.
+const WALLET_AES_IV: &[u8; 16] = b"niropok/walletIV";
 
 pub struct Wallet {
     pub keypair: Keypair,
 }
 
+/// One entry in a wallet's on-disk changeset log (see `Wallet::save`):
+/// either the wallet's initial key material or a later re-keying. Entries
+/// are appended, never rewritten, so `Wallet::load` can replay the whole
+/// history and recover from a rotation as easily as from the original key.
+#[derive(Serialize, Deserialize)]
+enum ChangeKind {
+    Created,
+    Rotated,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WalletChangeSet {
+    kind: ChangeKind,
+    /// Hex-encoded 16-byte salt this entry's passphrase key was derived from.
+    salt: String,
+    /// Hex-encoded AES-256-CTR ciphertext of the Dilithium keypair bytes.
+    ciphertext: String,
+    /// Hex-encoded Keccak256(key || ciphertext), checked before decrypting
+    /// so a wrong passphrase fails loudly instead of yielding garbage keys.
+    tag: String,
+}
+
+/// Derives a 32-byte AES key from a passphrase and per-entry `salt`, the
+/// same Keccak256-based construction `mnemonic::derive_label_seed` uses for
+/// domain-separated seeds, just keyed by (salt, passphrase) instead of
+/// (seed, label).
+fn derive_passphrase_key(passphrase: &str, salt: &[u8; 16]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(salt);
+    hasher.update(passphrase.as_bytes());
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&hasher.finalize());
+    out
+}
+
+/// Authenticates a (key, ciphertext) pair so a wrong passphrase is caught
+/// before it's used to "decrypt" into a bogus keypair.
+fn changeset_tag(key: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(key);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+/// AES-256-CTR is a keystream XOR, so the same function encrypts and
+/// decrypts depending on which direction `data` is passed in.
+fn aes_ctr_apply(key: &[u8; 32], data: &[u8]) -> Vec<u8> {
+    type Aes256Ctr64BE = Ctr64BE<Aes256>;
+    let mut cipher = Aes256Ctr64BE::new(key.into(), WALLET_AES_IV.into());
+    let mut buf = data.to_vec();
+    cipher.apply_keystream(&mut buf);
+    buf
+}
+
 impl<'de> Deserialize<'de> for Wallet {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -45,6 +110,22 @@ impl Wallet {
         Ok(Self { keypair })
     }
 
+    /// Deterministically derives the Dilithium keypair from a master `seed`
+    /// via a domain-separated KDF label, so the same seed (or its mnemonic,
+    /// see [`Wallet::from_mnemonic`]) always reconstructs the same wallet.
+    pub fn from_seed(seed: &[u8; 32]) -> Result<Self, String> {
+        let dilithium_seed = crate::mnemonic::derive_label_seed(seed, b"niropok/wallet/dilithium");
+        let keypair = Keypair::generate(Some(&dilithium_seed));
+        Ok(Self { keypair })
+    }
+
+    /// Reconstructs a wallet from a mnemonic phrase produced by
+    /// [`crate::mnemonic::encode`].
+    pub fn from_mnemonic(phrase: &str) -> Result<Self, String> {
+        let seed = crate::mnemonic::decode(phrase)?;
+        Self::from_seed(&seed)
+    }
+
     pub fn sign_message(&self, msg: &[u8]) -> Signature {
         self.keypair.sign(msg)
     }
@@ -60,4 +141,164 @@ impl Wallet {
     pub fn get_private_key(&self) -> String {
         hex::encode(self.keypair.secret.to_bytes())
     }
+
+    /// Encrypts this wallet's keypair under a passphrase-derived AES-256-CTR
+    /// key and appends it to the changeset log at `path`, so a restarted
+    /// node can recover with `Wallet::load` and re-participate in
+    /// certificate signing under the exact same `get_public_key()` identity.
+    /// If `path` already holds entries, this is recorded as a `Rotated`
+    /// entry rather than `Created`, preserving prior keys for recovery.
+    pub fn save(&self, path: &str, passphrase: &str) -> Result<(), String> {
+        let is_rotation = std::path::Path::new(path).exists();
+
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill(&mut salt);
+        let key = derive_passphrase_key(passphrase, &salt);
+
+        let ciphertext = aes_ctr_apply(&key, &self.keypair.to_bytes());
+        let tag = changeset_tag(&key, &ciphertext);
+
+        let entry = WalletChangeSet {
+            kind: if is_rotation {
+                ChangeKind::Rotated
+            } else {
+                ChangeKind::Created
+            },
+            salt: hex::encode(salt),
+            ciphertext: hex::encode(ciphertext),
+            tag: hex::encode(tag),
+        };
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("failed to serialize wallet changeset: {}", e))?;
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("failed to open wallet store at {}: {}", path, e))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("failed to append wallet changeset to {}: {}", path, e))?;
+        Ok(())
+    }
+
+    /// Replays the changeset log at `path`, decrypting every entry with
+    /// `passphrase` and returning the wallet holding the most recently
+    /// appended key (i.e. after any rotations recorded since the file was
+    /// first created).
+    pub fn load(path: &str, passphrase: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read wallet store at {}: {}", path, e))?;
+
+        let mut keypair_bytes: Option<Vec<u8>> = None;
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: WalletChangeSet = serde_json::from_str(line)
+                .map_err(|e| format!("failed to parse wallet changeset entry {}: {}", i, e))?;
+
+            let salt = hex::decode(&entry.salt)
+                .map_err(|e| format!("malformed salt in changeset entry {}: {}", i, e))?;
+            let salt: [u8; 16] = salt
+                .try_into()
+                .map_err(|_| format!("salt in changeset entry {} is not 16 bytes", i))?;
+            let key = derive_passphrase_key(passphrase, &salt);
+
+            let ciphertext = hex::decode(&entry.ciphertext)
+                .map_err(|e| format!("malformed ciphertext in changeset entry {}: {}", i, e))?;
+            let expected_tag = hex::encode(changeset_tag(&key, &ciphertext));
+            if expected_tag != entry.tag {
+                return Err(format!(
+                    "wrong passphrase or corrupted wallet changeset entry {}",
+                    i
+                ));
+            }
+
+            match entry.kind {
+                ChangeKind::Created => log::info!("wallet store {}: replaying initial key", path),
+                ChangeKind::Rotated => log::info!("wallet store {}: replaying rotated key", path),
+            }
+            keypair_bytes = Some(aes_ctr_apply(&key, &ciphertext));
+        }
+
+        let keypair_bytes = keypair_bytes
+            .ok_or_else(|| format!("wallet store at {} has no changeset entries", path))?;
+        Ok(Self {
+            keypair: Keypair::from_bytes(&keypair_bytes),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("niropok-wallet-test-{}-{}", name, rand::random::<u64>()))
+    }
+
+    #[test]
+    fn test_save_load_round_trips_public_key_and_signature() {
+        let path = temp_store_path("round-trip");
+        let path_str = path.to_str().unwrap();
+        let wallet = Wallet::new().expect("failed to create wallet");
+        wallet.save(path_str, "correct horse battery staple").expect("failed to save wallet");
+
+        let reloaded = Wallet::load(path_str, "correct horse battery staple").expect("failed to load wallet");
+        assert_eq!(reloaded.get_public_key(), wallet.get_public_key());
+
+        let msg = b"post-restart signing";
+        let signature = reloaded.sign_message(msg);
+        assert!(wallet.verify(msg, &signature), "signature from the reloaded wallet must verify against the pre-save key");
+
+        std::fs::remove_file(&path).expect("failed to clean up test wallet store");
+    }
+
+    #[test]
+    fn test_load_with_wrong_passphrase_fails() {
+        let path = temp_store_path("wrong-passphrase");
+        let path_str = path.to_str().unwrap();
+        let wallet = Wallet::new().expect("failed to create wallet");
+        wallet.save(path_str, "correct passphrase").expect("failed to save wallet");
+
+        let result = Wallet::load(path_str, "wrong passphrase");
+        assert!(result.is_err(), "loading with the wrong passphrase must fail instead of returning garbage keys");
+
+        std::fs::remove_file(&path).expect("failed to clean up test wallet store");
+    }
+
+    #[test]
+    fn test_save_after_rotation_loads_latest_key() {
+        let path = temp_store_path("rotation");
+        let path_str = path.to_str().unwrap();
+        let original = Wallet::new().expect("failed to create wallet");
+        original.save(path_str, "passphrase").expect("failed to save original wallet");
+
+        let rotated = Wallet::new().expect("failed to create rotated wallet");
+        rotated.save(path_str, "passphrase").expect("failed to save rotated wallet");
+
+        let loaded = Wallet::load(path_str, "passphrase").expect("failed to load wallet");
+        assert_eq!(loaded.get_public_key(), rotated.get_public_key());
+        assert_ne!(loaded.get_public_key(), original.get_public_key());
+
+        std::fs::remove_file(&path).expect("failed to clean up test wallet store");
+    }
+
+    #[test]
+    fn test_loaded_wallet_matches_create_test_builder_participant_entry() {
+        let path = temp_store_path("participant-entry");
+        let path_str = path.to_str().unwrap();
+        let wallet = Wallet::new().expect("failed to create wallet");
+        wallet.save(path_str, "participant passphrase").expect("failed to save wallet");
+
+        let reloaded = Wallet::load(path_str, "participant passphrase").expect("failed to load wallet");
+        // `create_test_builder` in `ccok.rs` takes a `Vec<(String, u64)>` of
+        // (public_key, weight) participant entries; the reloaded wallet must
+        // yield the exact same public key so it can re-enter that set under
+        // the same identity it signed with before restart.
+        let participant_entry = (reloaded.get_public_key(), 100u64);
+        assert_eq!(participant_entry.0, wallet.get_public_key());
+
+        std::fs::remove_file(&path).expect("failed to clean up test wallet store");
+    }
 }
@@ -0,0 +1,229 @@
+//! Host-side mirror of the `DilithiumCore` in-circuit key-ownership relation
+//! (`src/bin/circuits.rs`): proves knowledge of small secret coefficient
+//! vectors `s1, s2` satisfying `t = A*s1 + s2` for a public `t`, bound to a
+//! SHA3-256 `digest` of the wallet's Dilithium *public* key, so a `STAKE`
+//! transaction can carry evidence that its sender actually holds the secret
+//! key behind their post-quantum public key — not just a signature over the
+//! transaction, which only proves the sender can sign, not that admitting
+//! them as a validator is backed by a real key-generation process.
+//! [`verify_key_ownership`] takes the staking [`Account`] alongside the
+//! proof and rejects it unless `digest` matches that account's own public
+//! key, so a proof built for one wallet can't be submitted on another
+//! wallet's stake.
+//!
+//! This crate has no `Cargo.toml`, so the real `expander_compiler`/BN254
+//! circuit this mirrors can only be compiled and run from a `bin/` target
+//! (see `circuits.rs`), never linked into every validating node's hot path.
+//! [`verify_key_ownership`] therefore re-checks the exact same range and
+//! linear-equation constraints `DilithiumCore::define` enforces, in plain
+//! `u64` arithmetic reduced mod [`FIELD_MODULUS`] instead of BN254's scalar
+//! field — consistent for proving and verifying here, but not a drop-in
+//! replacement for the real circuit's field. [`KeyOwnershipProof`] also
+//! carries `s1`/`s2` in the clear rather than behind a succinct SNARK proof,
+//! since no provable backend is pluggable without that compiled circuit;
+//! treat this the same way as `settlement::LoggingL1Checkpointer` — a
+//! faithful stand-in for the real verifier this module's doc comment
+//! describes, not a finished zero-knowledge system.
+
+use crate::accounts::Account;
+use crate::wallet::Wallet;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+const K: usize = 4;
+const L: usize = 4;
+const N: usize = 256;
+const RANGE_B: u64 = 5;
+
+/// A large prime host-side stand-in for `DilithiumCore`'s BN254 scalar
+/// field, chosen only to keep the mirrored arithmetic from silently
+/// wrapping in `u64`/`u128` — not meant to match BN254's modulus.
+const FIELD_MODULUS: u128 = (1u128 << 61) - 1;
+
+fn a_coeff(k: usize, j: usize, n: usize) -> u64 {
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"A");
+    hasher.update([k as u8, j as u8]);
+    hasher.update((n as u16).to_le_bytes());
+    let digest = hasher.finalize();
+    u64::from_le_bytes(digest[0..8].try_into().unwrap())
+}
+
+fn sha3_limbs4(data: &[u8]) -> [u64; 4] {
+    let digest = Sha3_256::digest(data);
+    [0, 8, 16, 24].map(|o| u64::from_le_bytes(digest[o..o + 8].try_into().unwrap()))
+}
+
+fn field_mul_add(acc: u128, a: u64, b: u64) -> u128 {
+    (acc + (a as u128 * b as u128)) % FIELD_MODULUS
+}
+
+/// A proof that its holder knows the secret Dilithium coefficient vectors
+/// behind a committed public `t`/`digest` pair. See the module doc comment
+/// for why `s1`/`s2` are plain fields here rather than hidden behind a
+/// succinct proof.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyOwnershipProof {
+    /// Public: `t = A*s1 + s2`, one row per `k in 0..K`.
+    pub t: Vec<Vec<u64>>,
+    /// Public: SHA3-256 limbs binding this proof to a specific secret key.
+    pub digest: [u64; 4],
+    s1: Vec<Vec<u64>>,
+    s2: Vec<Vec<u64>>,
+}
+
+/// Builds a [`KeyOwnershipProof`] for `wallet`'s Dilithium keypair, the way
+/// `bin/circuits.rs`'s `main()` derives `s1`/`s2`/`t` from a fresh keypair's
+/// secret bytes, just reusable instead of inlined in a one-shot binary.
+pub fn prove_key_ownership(wallet: &Wallet) -> KeyOwnershipProof {
+    let sk_bytes = hex::decode(wallet.get_private_key())
+        .expect("Wallet::get_private_key always returns valid hex");
+    let pk_bytes = hex::decode(wallet.get_public_key())
+        .expect("Wallet::get_public_key always returns valid hex");
+
+    let mut s1 = vec![vec![0u64; N]; L];
+    let mut s2 = vec![vec![0u64; N]; K];
+    let mut idx = 0;
+    for row in s1.iter_mut() {
+        for coeff in row.iter_mut() {
+            *coeff = (sk_bytes[idx % sk_bytes.len()] as u64) % RANGE_B;
+            idx += 1;
+        }
+    }
+    for row in s2.iter_mut() {
+        for coeff in row.iter_mut() {
+            *coeff = (sk_bytes[idx % sk_bytes.len()] as u64) % RANGE_B;
+            idx += 1;
+        }
+    }
+
+    let mut t = vec![vec![0u64; N]; K];
+    for k in 0..K {
+        for n in 0..N {
+            let mut acc: u128 = 0;
+            for j in 0..L {
+                acc = field_mul_add(acc, a_coeff(k, j, n), s1[j][n]);
+            }
+            acc = (acc + s2[k][n] as u128) % FIELD_MODULUS;
+            t[k][n] = acc as u64;
+        }
+    }
+
+    KeyOwnershipProof {
+        t,
+        digest: sha3_limbs4(&pk_bytes),
+        s1,
+        s2,
+    }
+}
+
+/// Re-checks every constraint `DilithiumCore::define` enforces in-circuit:
+/// every `s1`/`s2` coefficient lies in the allowed range `0..RANGE_B`, and
+/// `t[k][n] == Σ_j A(k,j,n)*s1[j][n] + s2[k][n]` for every `(k, n)`. Returns
+/// `false` on any shape mismatch (wrong `K`/`L`/`N` dimensions) as well as
+/// any failed constraint, mirroring `comp.layered_circuit.run(&wit)`
+/// returning anything other than `vec![true]`.
+///
+/// Also rejects a proof whose `digest` doesn't match `account`'s own public
+/// key: `DilithiumCore::define` only ever asserts `digest[i] == digest[i]`,
+/// a tautology that doesn't tie `digest` to anything else in-circuit, so
+/// this host-side check is what actually binds the proof to `account`
+/// rather than letting a self-consistent proof built for any wallet be
+/// submitted on someone else's stake.
+pub fn verify_key_ownership(proof: &KeyOwnershipProof, account: &Account) -> bool {
+    let Ok(pk_bytes) = hex::decode(&account.address) else {
+        return false;
+    };
+    if proof.digest != sha3_limbs4(&pk_bytes) {
+        return false;
+    }
+    if proof.t.len() != K || proof.s1.len() != L || proof.s2.len() != K {
+        return false;
+    }
+    if proof.t.iter().any(|row| row.len() != N)
+        || proof.s1.iter().any(|row| row.len() != N)
+        || proof.s2.iter().any(|row| row.len() != N)
+    {
+        return false;
+    }
+    for row in proof.s1.iter().chain(proof.s2.iter()) {
+        if row.iter().any(|coeff| *coeff >= RANGE_B) {
+            return false;
+        }
+    }
+
+    for k in 0..K {
+        for n in 0..N {
+            let mut acc: u128 = 0;
+            for j in 0..L {
+                acc = field_mul_add(acc, a_coeff(k, j, n), proof.s1[j][n]);
+            }
+            acc = (acc + proof.s2[k][n] as u128) % FIELD_MODULUS;
+            if acc as u64 != proof.t[k][n] {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_for(wallet: &Wallet) -> Account {
+        Account { address: wallet.get_public_key() }
+    }
+
+    #[test]
+    fn test_prove_then_verify_key_ownership_round_trips() {
+        let wallet = Wallet::new().expect("failed to create wallet");
+        let proof = prove_key_ownership(&wallet);
+        assert!(
+            verify_key_ownership(&proof, &account_for(&wallet)),
+            "a freshly generated proof must verify against its own wallet's account"
+        );
+    }
+
+    #[test]
+    fn test_verify_key_ownership_rejects_a_tampered_t() {
+        let wallet = Wallet::new().expect("failed to create wallet");
+        let mut proof = prove_key_ownership(&wallet);
+        proof.t[0][0] = proof.t[0][0].wrapping_add(1);
+        assert!(
+            !verify_key_ownership(&proof, &account_for(&wallet)),
+            "tampering with a public t entry must fail verification"
+        );
+    }
+
+    #[test]
+    fn test_verify_key_ownership_rejects_an_out_of_range_coefficient() {
+        let wallet = Wallet::new().expect("failed to create wallet");
+        let mut proof = prove_key_ownership(&wallet);
+        proof.s1[0][0] = RANGE_B;
+        assert!(
+            !verify_key_ownership(&proof, &account_for(&wallet)),
+            "a coefficient outside 0..RANGE_B must fail the range check"
+        );
+    }
+
+    #[test]
+    fn test_verify_key_ownership_rejects_someone_elses_proof() {
+        let wallet = Wallet::new().expect("failed to create wallet");
+        let other_wallet = Wallet::new().expect("failed to create wallet");
+        let proof = prove_key_ownership(&other_wallet);
+        assert!(
+            !verify_key_ownership(&proof, &account_for(&wallet)),
+            "a proof built for one wallet must not verify against a different wallet's account"
+        );
+    }
+
+    #[test]
+    fn test_two_wallets_produce_different_proofs() {
+        let wallet_a = Wallet::new().expect("failed to create wallet");
+        let wallet_b = Wallet::new().expect("failed to create wallet");
+        let proof_a = prove_key_ownership(&wallet_a);
+        let proof_b = prove_key_ownership(&wallet_b);
+        assert_ne!(proof_a.digest, proof_b.digest, "different public keys must bind to different digests");
+    }
+}